@@ -1,6 +1,14 @@
 use anyhow::Result;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
 use std::process::Command;
+use std::process::{Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::sync::Semaphore;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudibleMetadata {
@@ -13,6 +21,18 @@ pub struct AudibleMetadata {
     pub release_date: Option<String>,
     pub description: Option<String>,
     pub asin: Option<String>,
+    /// Marketplace the match came from (None = the CLI profile's default)
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Title + author similarity to the search, 0-100
+    #[serde(default)]
+    pub match_score: u32,
+    /// Largest product image Audible returned
+    #[serde(default)]
+    pub cover_url: Option<String>,
+    /// Category names from the product's category ladders, root first
+    #[serde(default)]
+    pub genres: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,47 +41,191 @@ pub struct AudibleSeries {
     pub position: Option<String>,
 }
 
+// Marketplaces the audible CLI accepts for --country-code
+pub const AUDIBLE_REGIONS: &[&str] = &["us", "ca", "uk", "au", "fr", "de", "es", "jp", "it", "in"];
+
+pub const DEFAULT_AUDIBLE_CONCURRENCY: usize = 2;
+pub const DEFAULT_AUDIBLE_TIMEOUT_SECS: u64 = 30;
+
+/// Why an audible CLI call produced no output
+#[derive(Debug)]
+pub enum AudibleCliError {
+    /// Ran past `audible_timeout_secs`; the process was killed and reaped
+    TimedOut(Duration),
+    /// Couldn't be started or waited on
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for AudibleCliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudibleCliError::TimedOut(after) => write!(f, "audible CLI timed out after {:.1}s", after.as_secs_f64()),
+            AudibleCliError::Io(e) => write!(f, "audible CLI could not run: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AudibleCliError {}
+
+// Shared by every scan worker, independent of the GPT limits, and rebuilt
+// only when the configured size changes
+static CLI_SLOTS: Lazy<Mutex<Option<(usize, Arc<Semaphore>)>>> = Lazy::new(|| Mutex::new(None));
+
+fn shared_cli_slots(limit: usize) -> Arc<Semaphore> {
+    let limit = limit.max(1);
+    let mut slots = CLI_SLOTS.lock().unwrap();
+    match slots.as_ref() {
+        Some((size, existing)) if *size == limit => Arc::clone(existing),
+        _ => {
+            let created = Arc::new(Semaphore::new(limit));
+            *slots = Some((limit, Arc::clone(&created)));
+            created
+        }
+    }
+}
+
+/// The configured CLI slot pool and per-call timeout
+fn cli_settings() -> (Arc<Semaphore>, Duration) {
+    let (limit, secs) = crate::config::load_config()
+        .map(|c| (c.audible_concurrency, c.audible_timeout_secs))
+        .unwrap_or((DEFAULT_AUDIBLE_CONCURRENCY, DEFAULT_AUDIBLE_TIMEOUT_SECS));
+    (shared_cli_slots(limit), Duration::from_secs(secs.max(1)))
+}
+
+/// Hold one CLI slot for as long as `run` takes. The permit drops with this
+/// future, so a failed, timed-out or cancelled call always gives it back.
+async fn with_cli_slot<T>(slots: Arc<Semaphore>, run: impl std::future::Future<Output = T>) -> Result<T> {
+    let _permit = slots.acquire_owned().await?;
+    Ok(run.await)
+}
+
+/// Run the CLI to completion or kill it once `limit` passes. Stdin is closed
+/// so a CLI waiting on an interactive prompt fails instead of stalling.
+async fn run_cli(mut cmd: tokio::process::Command, limit: Duration) -> std::result::Result<Output, AudibleCliError> {
+    let mut child = cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(AudibleCliError::Io)?;
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    
+    let finished = tokio::time::timeout(limit, async {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let (read_out, read_err, status) = tokio::join!(
+            stdout.read_to_end(&mut out),
+            stderr.read_to_end(&mut err),
+            child.wait(),
+        );
+        read_out?;
+        read_err?;
+        Ok::<_, std::io::Error>(Output { status: status?, stdout: out, stderr: err })
+    }).await;
+    
+    match finished {
+        Ok(output) => output.map_err(AudibleCliError::Io),
+        Err(_) => {
+            // kill() also waits, so the stalled process is reaped, not left a zombie
+            if let Err(e) = child.kill().await {
+                println!("             ⚠️  Couldn't kill timed-out audible CLI: {}", e);
+            }
+            Err(AudibleCliError::TimedOut(limit))
+        }
+    }
+}
+
+/// Search each configured marketplace in order and return the first match.
+/// An empty region list searches the CLI profile's default marketplace.
+pub async fn search_audible_regions(
+    title: &str,
+    author: &str,
+    cli_path: &str,
+    regions: &[String],
+    min_confidence: u32,
+) -> Result<Option<AudibleMetadata>> {
+    if regions.is_empty() {
+        return search_audible(title, author, cli_path, None, min_confidence).await;
+    }
+    
+    first_region_match(regions, |region| async move {
+        search_audible(title, author, cli_path, Some(&region), min_confidence).await
+    }).await
+}
+
+/// Try `search` on each known region in order. A region that fails (a
+/// timeout, say) is logged and skipped; its error is only returned when no
+/// later region has a match either, so the miss isn't taken as definitive.
+async fn first_region_match<F, Fut>(regions: &[String], mut search: F) -> Result<Option<AudibleMetadata>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<AudibleMetadata>>>,
+{
+    let mut failure = None;
+    for region in regions {
+        let region = region.trim().to_lowercase();
+        if !AUDIBLE_REGIONS.contains(&region.as_str()) {
+            println!("          ⚠️  Audible: unknown region '{}' (expected one of {})", region, AUDIBLE_REGIONS.join(", "));
+            continue;
+        }
+        
+        match search(region.clone()).await {
+            Ok(Some(meta)) => return Ok(Some(meta)),
+            Ok(None) => {}
+            Err(e) => {
+                println!("          ⚠️  Audible [{}]: {} - trying the next region", region, e);
+                failure.get_or_insert(e);
+            }
+        }
+    }
+    
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(None),
+    }
+}
+
 pub async fn search_audible(
     title: &str,
     author: &str,
     cli_path: &str,
+    region: Option<&str>,
+    min_confidence: u32,
 ) -> Result<Option<AudibleMetadata>> {
-    println!("          🎧 Audible: searching for '{}' by '{}'...", title, author);
+    match region {
+        Some(r) => println!("          🎧 Audible [{}]: searching for '{}' by '{}'...", r, title, author),
+        None => println!("          🎧 Audible: searching for '{}' by '{}'...", title, author),
+    }
     
     let search_query = format!("{} {}", title, author);
     
-    let output = match tokio::time::timeout(
-        std::time::Duration::from_secs(30),
-        tokio::task::spawn_blocking({
-            let query = search_query.clone();
-            let cli = cli_path.to_string();
-            move || {
-                Command::new(&cli)
-                    .arg("api")
-                    .arg("1.0/catalog/products")
-                    .arg("-p")
-                    .arg(format!("keywords={}", query))
-                    .arg("-p")
-                    .arg("num_results=3")
-                    .arg("-p")
-                    .arg("response_groups=product_desc,product_attrs,contributors,series")
-                    .output()
-            }
-        })
-    ).await {
-        Ok(Ok(Ok(output))) => output,
-        Ok(Ok(Err(e))) => {
+    let mut cmd = tokio::process::Command::new(cli_path);
+    cmd.arg("api")
+        .arg("1.0/catalog/products")
+        .arg("-p")
+        .arg(format!("keywords={}", search_query))
+        .arg("-p")
+        .arg("num_results=3")
+        .arg("-p")
+        .arg("response_groups=product_desc,product_attrs,contributors,series,media,category_ladders")
+        .arg("-p")
+        .arg("image_sizes=500,1024");
+    if let Some(r) = region {
+        cmd.arg("--country-code").arg(r);
+    }
+    
+    let (slots, limit) = cli_settings();
+    let output = match with_cli_slot(slots, run_cli(cmd, limit)).await? {
+        Ok(output) => output,
+        Err(AudibleCliError::Io(e)) => {
             println!("             ❌ CLI execution error: {}", e);
             println!("             💡 Make sure audible-cli is installed and authenticated");
-            return Ok(None);
-        }
-        Ok(Err(e)) => {
-            println!("             ❌ Task spawn error: {}", e);
-            return Ok(None);
+            return Err(AudibleCliError::Io(e).into());
         }
-        Err(_) => {
-            println!("             ⚠️  Timeout (30s)");
-            return Ok(None);
+        Err(e) => {
+            // Callers treat an error as no Audible data and carry on with Google Books
+            println!("             ⏱️  {} - killed it", e);
+            return Err(e.into());
         }
     };
     
@@ -69,38 +233,265 @@ pub async fn search_audible(
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
         println!("             ❌ Command failed with exit code: {:?}", output.status.code());
+        if let Some(r) = region {
+            if is_region_not_configured(&stderr) {
+                println!("             ⏭️  Skipping region '{}': {}", r, stderr.trim());
+                println!("             💡 audible CLI isn't configured for it — run `audible manage auth-file add -l {}`", r);
+                return Ok(None);
+            }
+        }
         if !stderr.is_empty() {
             println!("             📛 STDERR: {}", stderr.trim());
         }
         if !stdout.is_empty() {
             println!("             📄 STDOUT: {}", stdout.trim());
         }
-        return Ok(None);
+        anyhow::bail!("audible CLI exited with {:?}", output.status.code());
     }
     
     let stdout = String::from_utf8_lossy(&output.stdout);
-    
+    match_from_response(&stdout, title, author, min_confidence, region)
+}
+
+/// The best match in a catalog search's output. An empty result is a miss
+/// (`Ok(None)`); only output that doesn't parse is an error.
+pub(crate) fn match_from_response(
+    stdout: &str,
+    title: &str,
+    author: &str,
+    min_confidence: u32,
+    region: Option<&str>,
+) -> Result<Option<AudibleMetadata>> {
     if stdout.trim().is_empty() {
         println!("             ⚠️  No results");
         return Ok(None);
     }
     
-    match parse_response(&stdout) {
-        Ok(meta) => {
-            println!("             ✅ Title: {:?}", meta.title);
+    match parse_response(stdout) {
+        Ok(candidates) => {
+            let Some(mut meta) = pick_best_match(candidates, title, author, min_confidence) else {
+                println!("             ⚠️  No result above {}% confidence", min_confidence);
+                return Ok(None);
+            };
+            meta.region = region.map(|r| r.to_string());
+            println!("             ✅ Title: {:?} ({}% match)", meta.title, meta.match_score);
             println!("                Narrators: {:?}", meta.narrators);
             println!("                ASIN: {:?}", meta.asin);
             Ok(Some(meta))
         }
         Err(e) => {
             println!("             ⚠️  Parse error: {}", e);
-            println!("             📄 Raw response (first 500 chars): {}", &stdout[..stdout.len().min(500)]);
-            Ok(None)
+            println!("             📄 Raw response (first 500 chars): {}", crate::util::truncate_utf8(stdout, 500));
+            Err(e)
         }
     }
 }
 
-fn parse_response(json: &str) -> Result<AudibleMetadata> {
+/// The marketplace turned the profile's credentials away (HTTP 401/403).
+/// Anything else - a broken auth file, a bad flag - is a real failure.
+fn is_region_not_configured(stderr: &str) -> bool {
+    let lower = stderr.to_lowercase();
+    ["401 unauthorized", "403 forbidden", "unauthorized:", "forbidden:"].iter().any(|m| lower.contains(m))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudibleChapter {
+    pub title: String,
+    pub start_ms: u64,
+    pub length_ms: u64,
+}
+
+/// Fetch chapter titles and offsets for an ASIN via the audible CLI.
+pub async fn fetch_chapters(asin: &str, cli_path: &str) -> Result<Vec<AudibleChapter>> {
+    println!("          🎧 Audible: fetching chapters for {}...", asin);
+    
+    let mut cmd = tokio::process::Command::new(cli_path);
+    cmd.arg("api")
+        .arg(format!("1.0/content/{}/metadata", asin))
+        .arg("-p")
+        .arg("response_groups=chapter_info");
+    
+    let (slots, limit) = cli_settings();
+    let output = with_cli_slot(slots, run_cli(cmd, limit)).await??;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("audible CLI failed fetching chapters: {}", stderr.trim());
+    }
+    
+    parse_chapters(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Ask the audible CLI for the account's activation bytes (needed to decrypt
+/// .aax files the user owns). Shares the CLI slots and timeout with searches.
+pub async fn get_activation_bytes(cli_path: &str) -> Result<String> {
+    let (slots, limit) = cli_settings();
+    activation_bytes(cli_path, slots, limit).await
+}
+
+async fn activation_bytes(cli_path: &str, slots: Arc<Semaphore>, limit: Duration) -> Result<String> {
+    let mut cmd = tokio::process::Command::new(cli_path);
+    cmd.arg("activation-bytes");
+    
+    let output = match with_cli_slot(slots, run_cli(cmd, limit)).await? {
+        Ok(output) => output,
+        Err(AudibleCliError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => anyhow::bail!(
+            "audible CLI not found at {} — install audible-cli and run `audible quickstart`",
+            cli_path
+        ),
+        Err(e) => return Err(e.into()),
+    };
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("audible CLI could not fetch activation bytes: {}", stderr.trim());
+    }
+    
+    parse_activation_bytes(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| anyhow::anyhow!("audible CLI did not return activation bytes — is this profile authenticated?"))
+}
+
+fn parse_activation_bytes(stdout: &str) -> Option<String> {
+    stdout.split_whitespace()
+        .rev()
+        .find(|token| token.len() == 8 && token.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|token| token.to_lowercase())
+}
+
+/// Where the decrypted copy of an .aax is written: same folder and name, .m4b
+pub fn decrypted_path(aax_path: &Path) -> PathBuf {
+    aax_path.with_extension("m4b")
+}
+
+/// Decrypt an .aax into a new .m4b alongside it with ffmpeg. The original is
+/// never modified, and an existing .m4b is left alone and reused.
+pub fn decrypt_aax(aax_path: &Path, activation_bytes: &str) -> Result<PathBuf> {
+    let out_path = decrypted_path(aax_path);
+    if out_path.exists() {
+        println!("   🔓 {} already decrypted", out_path.display());
+        return Ok(out_path);
+    }
+    
+    println!("   🔓 Decrypting {}...", aax_path.display());
+    let output = Command::new("ffmpeg")
+        .args(["-nostdin", "-loglevel", "error", "-activation_bytes", activation_bytes, "-i"])
+        .arg(aax_path)
+        .args(["-c", "copy", "-n"])
+        .arg(&out_path)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow::anyhow!("ffmpeg not found — install ffmpeg to decrypt .aax files"),
+            _ => anyhow::anyhow!("Failed to run ffmpeg: {}", e),
+        })?;
+    
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&out_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("ffmpeg could not decrypt {}: {}", aax_path.display(), stderr.trim());
+    }
+    
+    Ok(out_path)
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecryptedFile {
+    pub source: String,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecryptFailure {
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DecryptReport {
+    pub decrypted: Vec<DecryptedFile>,
+    pub failed: Vec<DecryptFailure>,
+}
+
+/// Run `decrypt` over each file, collecting successes and failures rather
+/// than stopping at the first problem.
+pub fn decrypt_all(paths: &[String], decrypt: impl Fn(&Path) -> Result<PathBuf>) -> DecryptReport {
+    let mut report = DecryptReport::default();
+    for path in paths {
+        match decrypt(Path::new(path)) {
+            Ok(output) => report.decrypted.push(DecryptedFile {
+                source: path.clone(),
+                output: output.to_string_lossy().to_string(),
+            }),
+            Err(e) => {
+                println!("   ❌ {}: {}", path, e);
+                report.failed.push(DecryptFailure { source: path.clone(), error: e.to_string() });
+            }
+        }
+    }
+    report
+}
+
+fn parse_chapters(json: &str) -> Result<Vec<AudibleChapter>> {
+    #[derive(Deserialize)]
+    struct Response {
+        content_metadata: ContentMetadata,
+    }
+    
+    #[derive(Deserialize)]
+    struct ContentMetadata {
+        chapter_info: ChapterInfo,
+    }
+    
+    #[derive(Deserialize)]
+    struct ChapterInfo {
+        #[serde(default)]
+        chapters: Vec<Chapter>,
+    }
+    
+    #[derive(Deserialize)]
+    struct Chapter {
+        title: String,
+        start_offset_ms: u64,
+        length_ms: u64,
+    }
+    
+    let resp: Response = serde_json::from_str(json)?;
+    Ok(resp.content_metadata.chapter_info.chapters.into_iter()
+        .map(|c| AudibleChapter {
+            title: c.title,
+            start_ms: c.start_offset_ms,
+            length_ms: c.length_ms,
+        })
+        .collect())
+}
+
+/// Score how well a result matches the searched title/author (0-100).
+pub fn match_score(meta: &AudibleMetadata, title: &str, author: &str) -> u32 {
+    let title_score = meta.title.as_deref()
+        .map(|t| crate::metadata::similarity(t, title))
+        .unwrap_or(0.0);
+    let author_score = meta.authors.iter()
+        .map(|a| crate::metadata::similarity(a, author))
+        .fold(0.0, f64::max);
+    
+    ((title_score * 0.7 + author_score * 0.3) * 100.0).round() as u32
+}
+
+fn pick_best_match(
+    candidates: Vec<AudibleMetadata>,
+    title: &str,
+    author: &str,
+    min_confidence: u32,
+) -> Option<AudibleMetadata> {
+    candidates.into_iter()
+        .map(|mut meta| {
+            meta.match_score = match_score(&meta, title, author);
+            meta
+        })
+        .filter(|meta| meta.match_score >= min_confidence)
+        .max_by_key(|meta| meta.match_score)
+}
+
+fn parse_response(json: &str) -> Result<Vec<AudibleMetadata>> {
     #[derive(Deserialize)]
     struct Response {
         products: Vec<Product>,
@@ -117,6 +508,20 @@ fn parse_response(json: &str) -> Result<AudibleMetadata> {
         release_date: Option<String>,
         publisher_summary: Option<String>,
         asin: Option<String>,
+        #[serde(default)]
+        product_images: HashMap<String, String>,
+        #[serde(default)]
+        category_ladders: Vec<CategoryLadder>,
+    }
+    
+    #[derive(Deserialize)]
+    struct CategoryLadder {
+        ladder: Vec<Category>,
+    }
+    
+    #[derive(Deserialize)]
+    struct Category {
+        name: String,
     }
     
     #[derive(Deserialize)]
@@ -131,9 +536,8 @@ fn parse_response(json: &str) -> Result<AudibleMetadata> {
     }
     
     let resp: Response = serde_json::from_str(json)?;
-    let product = resp.products.first().ok_or_else(|| anyhow::anyhow!("No products"))?;
     
-    Ok(AudibleMetadata {
+    Ok(resp.products.iter().map(|product| AudibleMetadata {
         title: product.title.clone(),
         subtitle: product.subtitle.clone(),
         authors: product.authors.as_ref()
@@ -152,5 +556,234 @@ fn parse_response(json: &str) -> Result<AudibleMetadata> {
         release_date: product.release_date.clone(),
         description: product.publisher_summary.clone(),
         asin: product.asin.clone(),
-    })
+        region: None,
+        match_score: 0,
+        cover_url: product.product_images.iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .map(|(_, url)| url.clone()),
+        genres: product.category_ladders.iter()
+            .flat_map(|l| l.ladder.iter().map(|c| c.name.clone()))
+            .fold(Vec::new(), |mut genres, name| {
+                if !genres.contains(&name) {
+                    genres.push(name);
+                }
+                genres
+            }),
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    
+    #[tokio::test]
+    async fn test_cli_slots_cap_concurrent_invocations() {
+        let slots = Arc::new(Semaphore::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        
+        let mut tasks = Vec::new();
+        for i in 0..6 {
+            let (slots, running, peak) = (Arc::clone(&slots), Arc::clone(&running), Arc::clone(&peak));
+            tasks.push(tokio::spawn(with_cli_slot(slots, async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(40)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                // Every other invocation fails the way a CLI error would
+                if i % 2 == 0 { Ok(()) } else { Err("exit status 1") }
+            })));
+        }
+        let mut failures = 0;
+        for task in tasks {
+            failures += task.await.unwrap().unwrap().is_err() as usize;
+        }
+        
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(failures, 3);
+        assert_eq!(slots.available_permits(), 2);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_stalled_cli_is_killed_and_reaped_on_timeout() {
+        let dir = crate::test_fixtures::temp_dir("cli-pid");
+        let pid_file = dir.join("pid");
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(format!("echo $$ > '{}'; exec sleep 30", pid_file.display()));
+        let slots = Arc::new(Semaphore::new(1));
+        
+        let started = std::time::Instant::now();
+        let result = with_cli_slot(Arc::clone(&slots), run_cli(cmd, Duration::from_millis(300))).await.unwrap();
+        let pid = std::fs::read_to_string(&pid_file).unwrap_or_default().trim().to_string();
+        
+        assert!(matches!(result, Err(AudibleCliError::TimedOut(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(slots.available_permits(), 1);
+        // Killed and waited on: no process, not even a zombie, under that pid
+        assert!(!pid.is_empty());
+        assert!(!Path::new(&format!("/proc/{}", pid)).exists());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_activation_bytes_use_the_cli_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let dir = crate::test_fixtures::temp_dir("cli-activation");
+        let script = |name: &str, body: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let working = script("working", "echo 'Activation bytes: 1A2B3C4D'");
+        let stalled = script("stalled", "exec sleep 30");
+        let slots = Arc::new(Semaphore::new(1));
+        
+        let bytes = activation_bytes(&working, Arc::clone(&slots), Duration::from_secs(5)).await.unwrap();
+        let started = std::time::Instant::now();
+        let err = activation_bytes(&stalled, Arc::clone(&slots), Duration::from_millis(300)).await.unwrap_err();
+        let missing = activation_bytes(&dir.join("missing").to_string_lossy(), Arc::clone(&slots), Duration::from_secs(5)).await.unwrap_err();
+        
+        assert_eq!(bytes, "1a2b3c4d");
+        assert!(matches!(err.downcast_ref::<AudibleCliError>(), Some(AudibleCliError::TimedOut(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(missing.to_string().contains("audible CLI not found"));
+        assert_eq!(slots.available_permits(), 1);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cli_output_is_collected_before_timeout() {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg("echo '{}'; echo oops >&2; exit 3");
+        
+        let output = run_cli(cmd, Duration::from_secs(5)).await.unwrap();
+        
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "{}");
+        assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "oops");
+        assert_eq!(output.status.code(), Some(3));
+    }
+    
+    #[test]
+    fn test_shared_cli_slots_follow_configured_size() {
+        let first = shared_cli_slots(3);
+        assert!(Arc::ptr_eq(&first, &shared_cli_slots(3)));
+        assert_eq!(shared_cli_slots(0).available_permits(), 1);
+    }
+    
+    #[test]
+    fn test_parse_activation_bytes() {
+        assert_eq!(parse_activation_bytes("Activation bytes: 1A2B3C4D\n").as_deref(), Some("1a2b3c4d"));
+        assert_eq!(parse_activation_bytes("Error: not logged in"), None);
+    }
+    
+    #[test]
+    fn test_decrypt_all_reports_each_file() {
+        let paths = vec!["/books/One.aax".to_string(), "/books/Two.aax".to_string()];
+        
+        let report = decrypt_all(&paths, |path| {
+            if path.ends_with("Two.aax") {
+                anyhow::bail!("ffmpeg not found — install ffmpeg to decrypt .aax files");
+            }
+            Ok(decrypted_path(path))
+        });
+        
+        assert_eq!(report.decrypted.len(), 1);
+        assert_eq!(report.decrypted[0].output, "/books/One.m4b");
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].source, "/books/Two.aax");
+        assert!(report.failed[0].error.contains("ffmpeg"));
+    }
+    
+    fn candidate(title: &str, author: &str) -> AudibleMetadata {
+        AudibleMetadata {
+            title: Some(title.to_string()),
+            subtitle: None,
+            authors: vec![author.to_string()],
+            narrators: vec![],
+            series: vec![],
+            publisher: None,
+            release_date: None,
+            description: None,
+            asin: None,
+            region: None,
+            match_score: 0,
+            cover_url: None,
+            genres: vec![],
+        }
+    }
+    
+    #[test]
+    fn test_picks_confident_match_over_first_result() {
+        let candidates = vec![
+            candidate("Words of Radiance", "Brandon Sanderson"),
+            candidate("The Way of Kings", "Brandon Sanderson"),
+        ];
+        
+        let best = pick_best_match(candidates, "The Way of Kings", "Brandon Sanderson", 70).unwrap();
+        assert_eq!(best.title.as_deref(), Some("The Way of Kings"));
+        assert_eq!(best.match_score, 100);
+    }
+    
+    #[test]
+    fn test_parse_chapters() {
+        let json = r#"{"content_metadata":{"chapter_info":{"chapters":[
+            {"title":"Opening Credits","start_offset_ms":0,"length_ms":15000},
+            {"title":"Chapter 1","start_offset_ms":15000,"length_ms":600000}
+        ]}}}"#;
+        
+        let chapters = parse_chapters(json).unwrap();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "Chapter 1");
+        assert_eq!(chapters[1].start_ms, 15000);
+    }
+    
+    #[test]
+    fn test_rejects_near_miss_titles() {
+        let candidates = vec![
+            candidate("The Way of Shadows", "Brent Weeks"),
+            candidate("Words of Radiance", "Brandon Sanderson"),
+        ];
+        
+        assert!(pick_best_match(candidates, "The Way of Kings", "Brandon Sanderson", 70).is_none());
+    }
+    
+    #[test]
+    fn test_region_not_configured_needs_a_rejected_login() {
+        assert!(is_region_not_configured("Error: 401 Unauthorized: Invalid token"));
+        assert!(is_region_not_configured("audible.exceptions.Forbidden: 403 Forbidden"));
+        assert!(!is_region_not_configured("Error: No auth file found for profile audible"));
+        assert!(!is_region_not_configured("Error: Invalid value for '--country-code': 'xx'"));
+        assert!(!is_region_not_configured("Error: marketplace request timed out"));
+    }
+    
+    #[tokio::test]
+    async fn test_timed_out_region_falls_through_to_the_next() {
+        let regions = vec!["us".to_string(), "uk".to_string()];
+        let result = first_region_match(&regions, |region| async move {
+            match region.as_str() {
+                "us" => Err(AudibleCliError::TimedOut(Duration::from_secs(30)).into()),
+                _ => Ok(Some(candidate("Mort", "Terry Pratchett"))),
+            }
+        }).await.unwrap();
+        
+        assert_eq!(result.unwrap().title.as_deref(), Some("Mort"));
+    }
+    
+    #[tokio::test]
+    async fn test_failed_region_is_not_reported_as_a_miss() {
+        let regions = vec!["us".to_string(), "uk".to_string()];
+        let result = first_region_match(&regions, |region| async move {
+            match region.as_str() {
+                "us" => Err(AudibleCliError::TimedOut(Duration::from_secs(30)).into()),
+                _ => Ok(None),
+            }
+        }).await;
+        
+        assert!(result.is_err());
+    }
 }
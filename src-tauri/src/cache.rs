@@ -1,6 +1,15 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+// Negative entries expire sooner so newly listed books get picked up
+const NEGATIVE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Bumped whenever a cached struct changes shape. bincode isn't
+/// self-describing, so an entry from another version can't be trusted.
+pub const CACHE_FORMAT_VERSION: u32 = 2;
+// No colon, so it can never collide with a "title:author" key
+const FORMAT_VERSION_KEY: &str = "format_version";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedMetadata {
@@ -9,9 +18,33 @@ pub struct CachedMetadata {
     pub timestamp: u64,
 }
 
+/// Records that Audible/Google returned nothing for a title/author.
+/// Stored under a separate "miss:" key so it never reads as a positive hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NegativeCacheEntry {
+    pub timestamp: u64,
+}
+
+/// Case-insensitive title/author key shared by the cache and duplicate detection
+pub fn cache_key(title: &str, author: &str) -> String {
+    format!("{}:{}", title.to_lowercase(), author.to_lowercase())
+}
+
+/// What opening the cache did about its on-disk format
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CacheMigration {
+    Current,
+    /// Entries from an older (or unversioned) format were dropped
+    Cleared { from: u32 },
+    /// Written by a newer app; left untouched and not used
+    NewerVersion { found: u32 },
+}
+
 #[derive(Clone)]
 pub struct MetadataCache {
     db: sled::Db,
+    migration: CacheMigration,
 }
 
 impl MetadataCache {
@@ -21,18 +54,38 @@ impl MetadataCache {
             .join("audiobook-tagger");
         std::fs::create_dir_all(&cache_dir)?;
         
-        let db = sled::open(cache_dir.join("metadata_cache"))?;
-        Ok(Self { db })
+        Self::open(&cache_dir.join("metadata_cache"))
+    }
+    
+    pub fn open(path: &Path) -> Result<Self> {
+        let db = sled::open(path)?;
+        let migration = migrate(&db)?;
+        Ok(Self { db, migration })
+    }
+    
+    pub fn migration(&self) -> CacheMigration {
+        self.migration
+    }
+    
+    /// A newer app's cache is never read or written, so it survives a downgrade
+    fn usable(&self) -> bool {
+        !matches!(self.migration, CacheMigration::NewerVersion { .. })
     }
     
     pub fn get(&self, title: &str, author: &str) -> Option<CachedMetadata> {
-        let key = format!("{}:{}", title.to_lowercase(), author.to_lowercase());
+        if !self.usable() {
+            return None;
+        }
+        let key = cache_key(title, author);
         let value = self.db.get(key).ok()??;
         bincode::deserialize(&value).ok()
     }
     
     pub fn set(&self, title: &str, author: &str, metadata: CachedMetadata) -> Result<()> {
-        let key = format!("{}:{}", title.to_lowercase(), author.to_lowercase());
+        if !self.usable() {
+            return Ok(());
+        }
+        let key = cache_key(title, author);
         let value = bincode::serialize(&metadata)
             .map_err(|e| anyhow::anyhow!("{}", e))?;
         self.db.insert(key, value)?;
@@ -40,8 +93,207 @@ impl MetadataCache {
         Ok(())
     }
     
+    pub fn is_known_missing(&self, title: &str, author: &str) -> bool {
+        if !self.usable() {
+            return false;
+        }
+        let key = format!("miss:{}", cache_key(title, author));
+        let entry: Option<NegativeCacheEntry> = self.db.get(key).ok()
+            .flatten()
+            .and_then(|v| bincode::deserialize(&v).ok());
+        
+        match entry {
+            Some(e) => now_secs().saturating_sub(e.timestamp) < NEGATIVE_TTL_SECS,
+            None => false,
+        }
+    }
+    
+    pub fn set_missing(&self, title: &str, author: &str) -> Result<()> {
+        self.set_missing_at(title, author, now_secs())
+    }
+    
+    fn set_missing_at(&self, title: &str, author: &str, timestamp: u64) -> Result<()> {
+        if !self.usable() {
+            return Ok(());
+        }
+        let key = format!("miss:{}", cache_key(title, author));
+        let value = bincode::serialize(&NegativeCacheEntry { timestamp })
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        self.db.insert(key, value)?;
+        self.db.flush()?;
+        Ok(())
+    }
+    
     pub fn clear(&self) -> Result<()> {
+        if !self.usable() {
+            anyhow::bail!("Cache was written by a newer version of the app; not clearing it");
+        }
         self.db.clear()?;
+        stamp_version(&self.db)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn stored_version(db: &sled::Db) -> Result<Option<u32>> {
+    let Some(bytes) = db.get(FORMAT_VERSION_KEY)? else {
+        return Ok(None);
+    };
+    let bytes: [u8; 4] = bytes.as_ref().try_into()
+        .map_err(|_| anyhow::anyhow!("Corrupt cache format version"))?;
+    Ok(Some(u32::from_be_bytes(bytes)))
+}
+
+fn stamp_version(db: &sled::Db) -> Result<()> {
+    db.insert(FORMAT_VERSION_KEY, &CACHE_FORMAT_VERSION.to_be_bytes())?;
+    db.flush()?;
+    Ok(())
+}
+
+/// Bring the cache to `CACHE_FORMAT_VERSION`. Cached lookups are cheap to
+/// redo, so older formats are cleared rather than converted. Caches from
+/// before versioning count as version 1.
+fn migrate(db: &sled::Db) -> Result<CacheMigration> {
+    let found = match stored_version(db) {
+        Ok(Some(version)) => version,
+        Ok(None) if db.is_empty() => {
+            stamp_version(db)?;
+            return Ok(CacheMigration::Current);
+        }
+        Ok(None) => 1,
+        Err(e) => {
+            println!("   ⚠️  {}; treating the cache as outdated", e);
+            0
+        }
+    };
+    
+    if found == CACHE_FORMAT_VERSION {
+        return Ok(CacheMigration::Current);
+    }
+    if found > CACHE_FORMAT_VERSION {
+        println!("⚠️  Metadata cache is format v{} (this app uses v{}); it won't be used", found, CACHE_FORMAT_VERSION);
+        return Ok(CacheMigration::NewerVersion { found });
+    }
+    
+    println!("🧹 Metadata cache is format v{}, clearing it for v{}", found, CACHE_FORMAT_VERSION);
+    db.clear()?;
+    stamp_version(db)?;
+    Ok(CacheMigration::Cleared { from: found })
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_dir, TempDir};
+    
+    fn temp_cache(name: &str) -> (TempDir, MetadataCache) {
+        let dir = temp_dir(&format!("cache-{}", name));
+        let cache = MetadataCache::open(&dir).unwrap();
+        (dir, cache)
+    }
+    
+    #[test]
+    fn test_negative_entry_short_circuits_second_lookup() {
+        let (_dir, cache) = temp_cache("negative");
+        assert!(!cache.is_known_missing("Unknown Book", "Nobody"));
+        
+        cache.set_missing("Unknown Book", "Nobody").unwrap();
+        
+        assert!(cache.is_known_missing("unknown book", "NOBODY"));
+        assert!(cache.get("Unknown Book", "Nobody").is_none());
+    }
+    
+    fn test_metadata() -> CachedMetadata {
+        CachedMetadata {
+            final_metadata: crate::scanner::BookMetadata {
+                title: "Dune".to_string(),
+                subtitle: None,
+                author: "Frank Herbert".to_string(),
+                narrator: None,
+                narrators: vec![],
+                series: None,
+                sequence: None,
+                genres: vec![],
+                publisher: None,
+                year: None,
+                description: None,
+                isbn: None,
+                asin: None,
+            },
+            timestamp: 1,
+        }
+    }
+    
+    /// The shape entries had before narrators and ASINs were cached
+    #[derive(Serialize)]
+    struct LegacyBookMetadata {
+        title: String,
+        author: String,
+        genres: Vec<String>,
+    }
+    
+    #[test]
+    fn test_unversioned_cache_is_cleared_on_open() {
+        let dir = temp_dir("cache-legacy");
+        {
+            let db = sled::open(&dir).unwrap();
+            let legacy = LegacyBookMetadata {
+                title: "Dune".to_string(),
+                author: "Frank Herbert".to_string(),
+                genres: vec!["Science Fiction".to_string()],
+            };
+            db.insert(cache_key("Dune", "Frank Herbert"), bincode::serialize(&legacy).unwrap()).unwrap();
+            db.flush().unwrap();
+        }
+        
+        let cache = MetadataCache::open(&dir).unwrap();
+        assert_eq!(cache.migration(), CacheMigration::Cleared { from: 1 });
+        assert!(cache.get("Dune", "Frank Herbert").is_none());
+        
+        cache.set("Dune", "Frank Herbert", test_metadata()).unwrap();
+        assert_eq!(cache.get("Dune", "Frank Herbert").unwrap().final_metadata.title, "Dune");
+        drop(cache);
+        
+        // Once stamped, reopening keeps the entries
+        let cache = MetadataCache::open(&dir).unwrap();
+        assert_eq!(cache.migration(), CacheMigration::Current);
+        assert!(cache.get("Dune", "Frank Herbert").is_some());
+    }
+    
+    #[test]
+    fn test_newer_cache_is_left_alone() {
+        let dir = temp_dir("cache-newer");
+        {
+            let db = sled::open(&dir).unwrap();
+            db.insert(FORMAT_VERSION_KEY, &(CACHE_FORMAT_VERSION + 1).to_be_bytes()).unwrap();
+            db.insert(cache_key("Dune", "Frank Herbert"), b"from the future".to_vec()).unwrap();
+            db.flush().unwrap();
+        }
+        
+        let cache = MetadataCache::open(&dir).unwrap();
+        assert_eq!(cache.migration(), CacheMigration::NewerVersion { found: CACHE_FORMAT_VERSION + 1 });
+        assert!(cache.get("Dune", "Frank Herbert").is_none());
+        cache.set("Dune", "Frank Herbert", test_metadata()).unwrap();
+        cache.set_missing("Dune", "Frank Herbert").unwrap();
+        assert!(!cache.is_known_missing("Dune", "Frank Herbert"));
+        assert!(cache.clear().is_err());
+        
+        let raw = cache.db.get(cache_key("Dune", "Frank Herbert")).unwrap().unwrap();
+        assert_eq!(raw.as_ref(), b"from the future");
+    }
+    
+    #[test]
+    fn test_stale_negative_entry_is_ignored() {
+        let (_dir, cache) = temp_cache("negative-stale");
+        let stale = now_secs() - NEGATIVE_TTL_SECS - 1;
+        cache.set_missing_at("Old Miss", "Author", stale).unwrap();
+        
+        assert!(!cache.is_known_missing("Old Miss", "Author"));
+    }
+}
@@ -0,0 +1,458 @@
+// src-tauri/src/chapter_writer.rs
+// Writes chapter markers into m4b/m4a (Nero chpl list) and mp3 (ID3v2 CHAP/CTOC)
+use anyhow::Result;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use crate::tag_inspector::{self, ChapterInfo};
+use crate::util::truncate_utf8;
+
+pub fn validate_chapters(chapters: &[ChapterInfo]) -> Result<()> {
+    if chapters.is_empty() {
+        anyhow::bail!("No chapters to write");
+    }
+    if chapters.len() > 255 {
+        anyhow::bail!("Too many chapters ({}); at most 255 are supported", chapters.len());
+    }
+
+    let mut previous_end = 0.0;
+    for (idx, chapter) in chapters.iter().enumerate() {
+        if chapter.start_seconds < 0.0 || chapter.end_seconds < chapter.start_seconds {
+            anyhow::bail!("Chapter {} ({}) ends before it starts", idx + 1, chapter.title);
+        }
+        // Allow a millisecond of slack for rounded boundaries
+        if chapter.start_seconds + 0.001 < previous_end {
+            anyhow::bail!("Chapter {} ({}) overlaps or is out of order with the previous chapter", idx + 1, chapter.title);
+        }
+        previous_end = chapter.end_seconds;
+    }
+
+    Ok(())
+}
+
+/// m4b/m4a get a Nero `chpl` list, which ffmpeg-based readers (including ABS)
+/// pick up; a QuickTime chapter text track would need a full remux. mp3 gets
+/// ID3v2 CHAP frames plus a CTOC table of contents.
+pub fn write_chapters(file_path: &str, chapters: &[ChapterInfo], backup: bool) -> Result<()> {
+    validate_chapters(chapters)?;
+
+    let path = Path::new(file_path);
+    if !path.exists() {
+        anyhow::bail!("File does not exist: {}", file_path);
+    }
+
+    let ext = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    if !matches!(ext.as_str(), "m4b" | "m4a" | "mp4" | "mp3") {
+        anyhow::bail!("Chapter writing is not supported for .{} files", ext);
+    }
+
+    if backup {
+        crate::tags::backup_file(path)?;
+    }
+
+    match ext.as_str() {
+        "mp3" => write_id3_chapters(path, chapters),
+        _ => write_mp4_chapters(path, chapters),
+    }
+}
+
+/// Replace bytes [copy_until, resume_at) of the file with `insert`, via a temp file
+fn splice_file(path: &Path, copy_until: u64, insert: &[u8], resume_at: u64) -> Result<()> {
+    let tmp_path = path.with_extension(format!(
+        "{}.chapters-tmp",
+        path.extension().unwrap_or_default().to_string_lossy()
+    ));
+
+    let written = (|| -> Result<()> {
+        let mut src = File::open(path)?;
+        let mut dst = File::create(&tmp_path)?;
+        std::io::copy(&mut (&mut src).take(copy_until), &mut dst)?;
+        dst.write_all(insert)?;
+        src.seek(SeekFrom::Start(resume_at))?;
+        std::io::copy(&mut src, &mut dst)?;
+        dst.sync_all()?;
+        Ok(())
+    })();
+
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// MP4
+// ---------------------------------------------------------------------------
+
+struct MemAtom {
+    name: [u8; 4],
+    start: usize,
+    payload: usize,
+    end: usize,
+}
+
+fn child_atoms(data: &[u8]) -> Result<Vec<MemAtom>> {
+    let mut atoms = Vec::new();
+    let mut pos = 0;
+
+    while pos + 8 <= data.len() {
+        let mut size = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let mut header_len = 8;
+        if size == 1 {
+            let large = data.get(pos + 8..pos + 16)
+                .ok_or_else(|| anyhow::anyhow!("Truncated MP4 atom header"))?;
+            size = u64::from_be_bytes(large.try_into()?) as usize;
+            header_len = 16;
+        } else if size == 0 {
+            size = data.len() - pos;
+        }
+
+        if size < header_len || pos + size > data.len() {
+            anyhow::bail!("Malformed MP4 atom at offset {}", pos);
+        }
+
+        atoms.push(MemAtom {
+            name: [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]],
+            start: pos,
+            payload: pos + header_len,
+            end: pos + size,
+        });
+        pos += size;
+    }
+
+    Ok(atoms)
+}
+
+fn wrap_atom(name: &[u8; 4], payload: &[u8]) -> Result<Vec<u8>> {
+    let size = u32::try_from(payload.len() + 8)
+        .map_err(|_| anyhow::anyhow!("{} atom too large", String::from_utf8_lossy(name)))?;
+    let mut atom = size.to_be_bytes().to_vec();
+    atom.extend_from_slice(name);
+    atom.extend_from_slice(payload);
+    Ok(atom)
+}
+
+fn build_chpl(chapters: &[ChapterInfo]) -> Vec<u8> {
+    // version 1, flags, 4 reserved bytes, chapter count
+    let mut payload = vec![1, 0, 0, 0, 0, 0, 0, 0, chapters.len() as u8];
+
+    for chapter in chapters {
+        let start = (chapter.start_seconds * 10_000_000.0).round() as u64;
+        payload.extend_from_slice(&start.to_be_bytes());
+        let title = truncate_utf8(&chapter.title, 255);
+        payload.push(title.len() as u8);
+        payload.extend_from_slice(title.as_bytes());
+    }
+
+    payload
+}
+
+/// Rebuild moov with a fresh udta/chpl, keeping every other atom untouched
+fn rebuild_moov(moov_payload: &[u8], chpl_payload: &[u8]) -> Result<Vec<u8>> {
+    let chpl = wrap_atom(b"chpl", chpl_payload)?;
+    let mut new_payload = Vec::new();
+    let mut found_udta = false;
+
+    for atom in child_atoms(moov_payload)? {
+        if &atom.name == b"udta" {
+            found_udta = true;
+            let udta = &moov_payload[atom.payload..atom.end];
+            let mut udta_payload = Vec::new();
+            for child in child_atoms(udta)? {
+                if &child.name != b"chpl" {
+                    udta_payload.extend_from_slice(&udta[child.start..child.end]);
+                }
+            }
+            udta_payload.extend_from_slice(&chpl);
+            new_payload.extend(wrap_atom(b"udta", &udta_payload)?);
+        } else {
+            new_payload.extend_from_slice(&moov_payload[atom.start..atom.end]);
+        }
+    }
+
+    if !found_udta {
+        new_payload.extend(wrap_atom(b"udta", &chpl)?);
+    }
+
+    wrap_atom(b"moov", &new_payload)
+}
+
+/// Growing a moov that sits before mdat moves the audio, so every chunk
+/// offset in stco/co64 has to shift by the same amount.
+fn adjust_chunk_offsets(data: &mut [u8], delta: i64) -> Result<()> {
+    for atom in child_atoms(data)? {
+        let payload = &mut data[atom.payload..atom.end];
+        match &atom.name {
+            b"trak" | b"mdia" | b"minf" | b"stbl" => adjust_chunk_offsets(payload, delta)?,
+            b"stco" => shift_offsets(payload, 4, delta)?,
+            b"co64" => shift_offsets(payload, 8, delta)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn shift_offsets(payload: &mut [u8], width: usize, delta: i64) -> Result<()> {
+    if payload.len() < 8 {
+        anyhow::bail!("Truncated chunk offset table");
+    }
+    let count = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]) as usize;
+
+    for i in 0..count {
+        let at = 8 + i * width;
+        let entry = payload.get_mut(at..at + width)
+            .ok_or_else(|| anyhow::anyhow!("Truncated chunk offset table"))?;
+
+        if width == 4 {
+            let bytes: [u8; 4] = (&entry[..]).try_into()?;
+            let shifted = u32::try_from(u32::from_be_bytes(bytes) as i64 + delta)
+                .map_err(|_| anyhow::anyhow!("Chunk offset out of range after resizing moov"))?;
+            entry.copy_from_slice(&shifted.to_be_bytes());
+        } else {
+            let bytes: [u8; 8] = (&entry[..]).try_into()?;
+            let shifted = u64::try_from(u64::from_be_bytes(bytes) as i64 + delta)
+                .map_err(|_| anyhow::anyhow!("Chunk offset out of range after resizing moov"))?;
+            entry.copy_from_slice(&shifted.to_be_bytes());
+        }
+    }
+
+    Ok(())
+}
+
+fn write_mp4_chapters(path: &Path, chapters: &[ChapterInfo]) -> Result<()> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let moov = tag_inspector::find_atom(&mut file, 0, file_len, b"moov")?
+        .ok_or_else(|| anyhow::anyhow!("No moov atom found - not a valid MP4 file"))?;
+    let mdat = tag_inspector::find_atom(&mut file, 0, file_len, b"mdat")?;
+
+    let mut moov_payload = vec![0u8; (moov.end - moov.payload) as usize];
+    file.seek(SeekFrom::Start(moov.payload))?;
+    file.read_exact(&mut moov_payload)?;
+    drop(file);
+
+    let mut new_moov = rebuild_moov(&moov_payload, &build_chpl(chapters))?;
+
+    if mdat.map(|m| moov.start < m.start).unwrap_or(false) {
+        let delta = new_moov.len() as i64 - (moov.end - moov.start) as i64;
+        adjust_chunk_offsets(&mut new_moov[8..], delta)?;
+    }
+
+    splice_file(path, moov.start, &new_moov, moov.end)
+}
+
+// ---------------------------------------------------------------------------
+// ID3v2
+// ---------------------------------------------------------------------------
+
+fn syncsafe_bytes(n: u32) -> [u8; 4] {
+    [
+        ((n >> 21) & 0x7f) as u8,
+        ((n >> 14) & 0x7f) as u8,
+        ((n >> 7) & 0x7f) as u8,
+        (n & 0x7f) as u8,
+    ]
+}
+
+fn id3_frame(version: u8, id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let size = payload.len() as u32;
+    let mut frame = id.to_vec();
+    if version == 4 {
+        frame.extend_from_slice(&syncsafe_bytes(size));
+    } else {
+        frame.extend_from_slice(&size.to_be_bytes());
+    }
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn text_payload(version: u8, text: &str) -> Vec<u8> {
+    // v2.4 allows UTF-8; v2.3 needs UTF-16 with a BOM
+    if version == 4 {
+        let mut payload = vec![3];
+        payload.extend_from_slice(text.as_bytes());
+        payload
+    } else {
+        let mut payload = vec![1, 0xFF, 0xFE];
+        for unit in text.encode_utf16() {
+            payload.extend_from_slice(&unit.to_le_bytes());
+        }
+        payload
+    }
+}
+
+fn build_id3_chapter_frames(version: u8, chapters: &[ChapterInfo]) -> Vec<u8> {
+    let element_ids: Vec<String> = (0..chapters.len()).map(|i| format!("chp{}", i)).collect();
+
+    // Top-level, ordered table of contents
+    let mut ctoc = b"toc\0".to_vec();
+    ctoc.push(0x03);
+    ctoc.push(chapters.len() as u8);
+    for id in &element_ids {
+        ctoc.extend_from_slice(id.as_bytes());
+        ctoc.push(0);
+    }
+
+    let mut frames = id3_frame(version, b"CTOC", &ctoc);
+
+    for (chapter, id) in chapters.iter().zip(&element_ids) {
+        let mut chap = id.as_bytes().to_vec();
+        chap.push(0);
+        chap.extend_from_slice(&((chapter.start_seconds * 1000.0).round() as u32).to_be_bytes());
+        chap.extend_from_slice(&((chapter.end_seconds * 1000.0).round() as u32).to_be_bytes());
+        // Byte offsets unused
+        chap.extend_from_slice(&[0xFF; 8]);
+        chap.extend(id3_frame(version, b"TIT2", &text_payload(version, &chapter.title)));
+        frames.extend(id3_frame(version, b"CHAP", &chap));
+    }
+
+    frames
+}
+
+fn write_id3_chapters(path: &Path, chapters: &[ChapterInfo]) -> Result<()> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; 10];
+    let has_tag = file.read_exact(&mut header).is_ok() && &header[..3] == b"ID3";
+
+    let (version, flags, mut body, audio_start) = if has_tag {
+        let version = header[3];
+        let flags = header[5];
+        if !(3..=4).contains(&version) {
+            anyhow::bail!("Unsupported ID3v2.{} tag", version);
+        }
+        if flags & 0x80 != 0 {
+            anyhow::bail!("Unsynchronised ID3 tags are not supported");
+        }
+
+        let size = tag_inspector::syncsafe(&header[6..10]) as usize;
+        let mut old_body = vec![0u8; size];
+        file.read_exact(&mut old_body)?;
+        let footer = if version == 4 && flags & 0x10 != 0 { 10 } else { 0 };
+
+        // Keep every frame except old chapters; the extended header and footer are dropped
+        let start = tag_inspector::id3_frames_start(version, flags, &old_body);
+        let kept: Vec<u8> = tag_inspector::id3_frames(version, old_body.get(start..).unwrap_or(&[]))
+            .into_iter()
+            .filter(|f| f.id != b"CHAP" && f.id != b"CTOC")
+            .flat_map(|f| f.raw.to_vec())
+            .collect();
+
+        (version, flags & !(0x40 | 0x10), kept, 10 + size as u64 + footer)
+    } else {
+        (3, 0, Vec::new(), 0)
+    };
+    drop(file);
+
+    body.extend(build_id3_chapter_frames(version, chapters));
+    if body.len() >= 1 << 28 {
+        anyhow::bail!("ID3 tag too large");
+    }
+
+    let mut tag = b"ID3".to_vec();
+    tag.extend_from_slice(&[version, 0, flags]);
+    tag.extend_from_slice(&syncsafe_bytes(body.len() as u32));
+    tag.extend(body);
+
+    splice_file(path, 0, &tag, audio_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::temp_dir;
+
+    fn chapter(start: f64, end: f64, title: &str) -> ChapterInfo {
+        ChapterInfo {
+            index: 0,
+            start_seconds: start,
+            end_seconds: end,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rejects_overlapping_chapters() {
+        let chapters = vec![chapter(0.0, 60.0, "One"), chapter(30.0, 90.0, "Two")];
+        assert!(validate_chapters(&chapters).is_err());
+
+        let chapters = vec![chapter(0.0, 60.0, "One"), chapter(60.0, 90.0, "Two")];
+        assert!(validate_chapters(&chapters).is_ok());
+    }
+
+    #[test]
+    fn test_mp3_chapters_round_trip() {
+        let dir = temp_dir("chapters-mp3");
+        let path = dir.join("book.mp3");
+        let mut bytes = b"ID3".to_vec();
+        let title = id3_frame(3, b"TIT2", &[0, b'B', b'o', b'o', b'k']);
+        bytes.extend_from_slice(&[3, 0, 0]);
+        bytes.extend_from_slice(&syncsafe_bytes(title.len() as u32));
+        bytes.extend(title);
+        bytes.extend_from_slice(b"fake mpeg audio");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let chapters = vec![chapter(0.0, 61.5, "Opening Crédits"), chapter(61.5, 300.0, "Chapter 1")];
+        write_chapters(path.to_str().unwrap(), &chapters, false).unwrap();
+        // Writing twice replaces rather than duplicates
+        write_chapters(path.to_str().unwrap(), &chapters, false).unwrap();
+
+        let read = tag_inspector::read_chapters(&path, 0.0);
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].title, "Opening Crédits");
+        assert_eq!(read[1].start_seconds, 61.5);
+        assert_eq!(read[1].end_seconds, 300.0);
+
+        let written = std::fs::read(&path).unwrap();
+        assert!(written.ends_with(b"fake mpeg audio"));
+        assert!(written.windows(4).any(|w| w == b"TIT2"));
+    }
+
+    #[test]
+    fn test_m4b_chapters_round_trip_shifts_chunk_offsets() {
+        let dir = temp_dir("chapters-m4b");
+        let path = dir.join("book.m4b");
+        let ftyp = wrap_atom(b"ftyp", b"M4B \0\0\0\0").unwrap();
+
+        // moov comes before mdat, so its stco must point past it
+        let build = |offset: u32| {
+            let mut stco = vec![0, 0, 0, 0, 0, 0, 0, 1];
+            stco.extend_from_slice(&offset.to_be_bytes());
+            let stbl = wrap_atom(b"stbl", &wrap_atom(b"stco", &stco).unwrap()).unwrap();
+            let minf = wrap_atom(b"minf", &stbl).unwrap();
+            let mdia = wrap_atom(b"mdia", &minf).unwrap();
+            let trak = wrap_atom(b"trak", &mdia).unwrap();
+            wrap_atom(b"moov", &trak).unwrap()
+        };
+        let moov_len = build(0).len();
+        let audio_offset = (ftyp.len() + moov_len + 8) as u32;
+
+        let mut bytes = ftyp.clone();
+        bytes.extend(build(audio_offset));
+        bytes.extend(wrap_atom(b"mdat", b"AUDIO").unwrap());
+        std::fs::write(&path, &bytes).unwrap();
+
+        let chapters = vec![chapter(0.0, 90.0, "Prologue"), chapter(90.0, 200.0, "Part One")];
+        write_chapters(path.to_str().unwrap(), &chapters, false).unwrap();
+
+        let read = tag_inspector::read_chapters(&path, 200.0);
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].title, "Prologue");
+        assert_eq!(read[0].end_seconds, 90.0);
+        assert_eq!(read[1].title, "Part One");
+        assert_eq!(read[1].start_seconds, 90.0);
+
+        let written = std::fs::read(&path).unwrap();
+        let stco_at = written.windows(4).position(|w| w == b"stco").unwrap();
+        let offset = u32::from_be_bytes(written[stco_at + 12..stco_at + 16].try_into().unwrap()) as usize;
+        assert_eq!(&written[offset..offset + 5], b"AUDIO");
+    }
+}
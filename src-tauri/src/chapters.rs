@@ -0,0 +1,120 @@
+use anyhow::Result;
+use lofty::file::AudioFile;
+use lofty::probe::Probe;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::audible::AudibleChapter;
+
+/// A chapter in AudiobookShelf's format (times in seconds).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: usize,
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+/// Build chapters for a book's files from Audible's chapter list.
+///
+/// A single file takes Audible's offsets directly. Multi-file books use one
+/// chapter per file; Audible titles are applied only when the counts match,
+/// otherwise the split falls back to file durations and filenames.
+pub fn build_chapters(files: &[(String, f64)], audible: &[AudibleChapter]) -> Vec<Chapter> {
+    if files.len() == 1 && !audible.is_empty() {
+        let total = files[0].1;
+        return audible.iter()
+            .enumerate()
+            .map(|(idx, c)| {
+                let start = c.start_ms as f64 / 1000.0;
+                let end = (c.start_ms + c.length_ms) as f64 / 1000.0;
+                Chapter {
+                    id: idx,
+                    start: start.min(total),
+                    end: if total > 0.0 { end.min(total) } else { end },
+                    title: c.title.clone(),
+                }
+            })
+            .collect();
+    }
+    
+    let use_audible_titles = audible.len() == files.len();
+    if !use_audible_titles && !audible.is_empty() {
+        println!("   ⚠️  Audible has {} chapters but book has {} files - splitting by file duration",
+            audible.len(), files.len());
+    }
+    
+    let mut start = 0.0;
+    files.iter()
+        .enumerate()
+        .map(|(idx, (path, duration))| {
+            let title = if use_audible_titles {
+                audible[idx].title.clone()
+            } else {
+                Path::new(path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("Chapter")
+                    .to_string()
+            };
+            let chapter = Chapter {
+                id: idx,
+                start,
+                end: start + duration,
+                title,
+            };
+            start += duration;
+            chapter
+        })
+        .collect()
+}
+
+pub fn file_duration_seconds(path: &str) -> Result<f64> {
+    let tagged_file = Probe::open(path)?.read()?;
+    Ok(tagged_file.properties().duration().as_secs_f64())
+}
+
+/// Write chapters as an AudiobookShelf chapters JSON file.
+pub fn export_abs_chapters(chapters: &[Chapter], out_path: &str) -> Result<()> {
+    let contents = serde_json::to_string_pretty(&serde_json::json!({ "chapters": chapters }))?;
+    std::fs::write(out_path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn audible_chapter(title: &str, start_ms: u64, length_ms: u64) -> AudibleChapter {
+        AudibleChapter { title: title.to_string(), start_ms, length_ms }
+    }
+    
+    #[test]
+    fn test_single_file_uses_audible_offsets() {
+        let files = vec![("/books/Dune.m4b".to_string(), 100.0)];
+        let audible = vec![
+            audible_chapter("Opening", 0, 10_000),
+            audible_chapter("Chapter 1", 10_000, 95_000),
+        ];
+        
+        let chapters = build_chapters(&files, &audible);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].start, 10.0);
+        assert_eq!(chapters[1].end, 100.0);
+        assert_eq!(chapters[1].title, "Chapter 1");
+    }
+    
+    #[test]
+    fn test_mismatched_count_falls_back_to_file_durations() {
+        let files = vec![
+            ("/books/Dune/01.mp3".to_string(), 60.0),
+            ("/books/Dune/02.mp3".to_string(), 30.0),
+        ];
+        let audible = vec![audible_chapter("Only Chapter", 0, 90_000)];
+        
+        let chapters = build_chapters(&files, &audible);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "02");
+        assert_eq!((chapters[1].start, chapters[1].end), (60.0, 90.0));
+    }
+}
@@ -1,9 +1,92 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmBackend {
+    #[default]
+    OpenAi,
+    Ollama,
+}
+
+/// How scanned files are bucketed into books
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupingStrategy {
+    /// Parent folder name, minus any part marker after a `series_patterns` marker
+    #[default]
+    Folder,
+    /// Album tag, falling back to folder
+    AlbumTag,
+    /// Similar album/title text regardless of folder
+    FuzzyTitle,
+}
+
+/// How a book's genres are written to file tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenreWriteStyle {
+    /// One genre item per genre
+    #[default]
+    Multi,
+    /// A single comma-joined genre for MP3 and MP4, as ABS and most players
+    /// there expect; FLAC/Ogg keep separate GENRE fields
+    Joined,
+}
+
+/// Where a book's candidate genres are drawn from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenreSource {
+    /// The merge result: GPT's pick, or Google's genres when GPT was skipped
+    Gpt,
+    Audible,
+    Google,
+    /// Looked up for every book when listed, not just on a retry
+    OpenLibrary,
+    /// Genre tags already on the files
+    FileTags,
+}
+
+/// Points validate_metadata_quality awards per field. The score is the share
+/// of the total earned, so weights needn't add up to 100.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityWeights {
+    pub title: u32,
+    pub narrator: u32,
+    pub description: u32,
+    pub genres: u32,
+    pub series: u32,
+    /// Publisher or year
+    pub publication: u32,
+}
+
+impl Default for QualityWeights {
+    fn default() -> Self {
+        Self {
+            title: 30,
+            narrator: 20,
+            description: 20,
+            genres: 15,
+            series: 10,
+            publication: 5,
+        }
+    }
+}
+
+impl QualityWeights {
+    pub fn total(&self) -> u32 {
+        self.title + self.narrator + self.description + self.genres + self.series + self.publication
+    }
+}
+
+/// Fields missing from a saved profile fall back to `Config::default()`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub abs_base_url: String,
     pub abs_api_token: String,
@@ -14,10 +97,136 @@ pub struct Config {
     pub genre_enforcement: bool,
     pub audible_enabled: bool,
     pub audible_cli_path: String,
+    /// Audible marketplaces to search in order (e.g. ["us", "uk", "de"])
+    #[serde(default)]
+    pub audible_regions: Vec<String>,
+    /// Minimum title/author match score (0-100) to accept an Audible result
+    #[serde(default = "default_audible_min_confidence")]
+    pub audible_min_confidence: u32,
+    /// audible CLI processes allowed to run at once across all scan workers
+    #[serde(default = "default_audible_concurrency")]
+    pub audible_concurrency: usize,
+    /// Seconds an audible CLI call may run before it's killed
+    #[serde(default = "default_audible_timeout_secs")]
+    pub audible_timeout_secs: u64,
     pub max_workers: usize,
+    /// Tag writes allowed on disk at once, apart from max_workers
+    #[serde(default = "default_io_workers")]
+    pub io_workers: usize,
     pub skip_unchanged: bool,
+    /// Extra genre spellings mapped onto approved genres (e.g. "Sci-Fi" -> "Science Fiction")
+    #[serde(default)]
+    pub genre_aliases: HashMap<String, String>,
+    /// Replaces the built-in GPT merge prompt. Supports {folder}, {title},
+    /// {author}, {google}, {audible}, {comments}, {genres} and {year_instruction}.
+    #[serde(default)]
+    pub merge_prompt_template: Option<String>,
+    /// Which LLM handles book extraction and metadata merging
+    #[serde(default)]
+    pub llm_backend: LlmBackend,
+    #[serde(default = "default_ollama_url")]
+    pub ollama_url: String,
+    #[serde(default = "default_ollama_model")]
+    pub ollama_model: String,
+    /// Groups scoring below this (0-100) are flagged needs_review
+    #[serde(default = "default_review_threshold")]
+    pub review_threshold: u32,
+    #[serde(default)]
+    pub grouping_strategy: GroupingStrategy,
+    /// Comment text for the narrator; {narrator} is replaced with the name
+    #[serde(default = "default_narrator_comment_template")]
+    pub narrator_comment_template: String,
+    /// Comment text for the description; {description} is replaced with the text
+    #[serde(default = "default_description_template")]
+    pub description_template: String,
+    /// Tag fields the narrator is written to; see tags::NARRATOR_TARGETS
+    #[serde(default = "default_narrator_targets")]
+    pub narrator_targets: Vec<String>,
+    /// Files shorter than this are flagged as possibly truncated
+    #[serde(default = "default_min_duration_secs")]
+    pub min_duration_secs: u32,
+    /// OpenAI requests per minute shared by all scan workers; 0 disables
+    #[serde(default = "default_openai_rpm")]
+    pub openai_rpm: u32,
+    /// Sent as the OpenAI-Organization / OpenAI-Project headers when set;
+    /// enterprise accounts reject requests without them
+    #[serde(default)]
+    pub openai_org: String,
+    #[serde(default)]
+    pub openai_project: String,
+    /// Decrypt owned .aax files to a new .m4b alongside them before tagging
+    #[serde(default)]
+    pub decrypt_aax: bool,
+    /// Folder patterns to leave out of scans - globs ("*/Bonus/*") or plain
+    /// substrings ("Sample"); absolute patterns match the full path,
+    /// relative ones the path under the scanned folder
+    #[serde(default)]
+    pub skip_folders: Vec<String>,
+    /// Also write series/sequence to the movement frames (ID3 MVNM/MVIN,
+    /// MP4 ©mvn/©mvi) that some players read instead of SERIES
+    #[serde(default)]
+    pub write_movement_frames: bool,
+    /// Comment prefixes that mark a narrator line this app (or the user's own
+    /// convention) wrote; used to recognize already-processed books
+    #[serde(default = "default_processed_narrator_prefixes")]
+    pub processed_narrator_prefixes: Vec<String>,
+    /// Genre count range a processed book's genre tag must fall in
+    #[serde(default = "default_processed_min_genres")]
+    pub processed_min_genres: usize,
+    #[serde(default = "default_processed_max_genres")]
+    pub processed_max_genres: usize,
+    /// Ignore the already-processed check and send every book through lookup
+    #[serde(default)]
+    pub force_reprocess: bool,
+    /// Genre for books no source could classify; must be an approved genre
+    #[serde(default)]
+    pub default_genre: Option<String>,
+    /// Treat "Disc 1"/"CD 2" subfolders as parts of their parent book
+    /// instead of separate books
+    #[serde(default = "default_true")]
+    pub merge_disc_folders: bool,
+    /// Regexes tried in order against folder names to find a series and
+    /// position; use named groups `series` and `sequence`
+    #[serde(default = "default_series_patterns")]
+    pub series_patterns: Vec<String>,
+    /// Merge results scoring below this (0-100) are retried with wider sources
+    #[serde(default = "default_quality_threshold")]
+    pub quality_threshold: u32,
+    #[serde(default)]
+    pub quality_weights: QualityWeights,
+    /// OpenAI model for the final merge attempt once the default model's
+    /// results have failed the quality check; None never switches
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    /// Record the app version and write time in TAGGER_VERSION /
+    /// TAGGER_TIMESTAMP fields on every file written
+    #[serde(default)]
+    pub stamp_provenance: bool,
+    /// Read tags with ffprobe when lofty finds none (WMA). Such files are
+    /// shown but never written.
+    #[serde(default)]
+    pub ffprobe_fallback: bool,
+    /// ISO 639-1 code (e.g. "de") sent to Google Books as langRestrict, so
+    /// editions in that language are found first
+    #[serde(default)]
+    pub metadata_language: Option<String>,
+    #[serde(default = "default_ffprobe_path")]
+    pub ffprobe_path: String,
+    #[serde(default)]
+    pub genre_write_style: GenreWriteStyle,
+    /// Genre sources in priority order. Candidates are merged in this order
+    /// and only approved genres are kept, up to three.
+    #[serde(default = "default_genre_sources")]
+    pub genre_sources: Vec<GenreSource>,
 }
 
+pub const DEFAULT_NARRATOR_COMMENT_TEMPLATE: &str = "Narrated by {narrator}";
+pub const DEFAULT_DESCRIPTION_TEMPLATE: &str = "{description}";
+
+pub const DEFAULT_REVIEW_THRESHOLD: u32 = 80;
+pub const DEFAULT_MIN_DURATION_SECS: u32 = 30;
+pub const DEFAULT_QUALITY_THRESHOLD: u32 = 80;
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -30,12 +239,136 @@ impl Default for Config {
             genre_enforcement: true,
             audible_enabled: false,
             audible_cli_path: String::from("/Users/philip/.local/bin/audible"),
+            audible_regions: Vec::new(),
+            audible_min_confidence: default_audible_min_confidence(),
+            audible_concurrency: default_audible_concurrency(),
+            audible_timeout_secs: default_audible_timeout_secs(),
             max_workers: 10,
+            io_workers: default_io_workers(),
             skip_unchanged: false,
+            genre_aliases: HashMap::new(),
+            merge_prompt_template: None,
+            llm_backend: LlmBackend::default(),
+            ollama_url: default_ollama_url(),
+            ollama_model: default_ollama_model(),
+            review_threshold: default_review_threshold(),
+            grouping_strategy: GroupingStrategy::default(),
+            narrator_comment_template: default_narrator_comment_template(),
+            description_template: default_description_template(),
+            narrator_targets: default_narrator_targets(),
+            min_duration_secs: default_min_duration_secs(),
+            openai_rpm: default_openai_rpm(),
+            openai_org: String::new(),
+            openai_project: String::new(),
+            decrypt_aax: false,
+            skip_folders: Vec::new(),
+            write_movement_frames: false,
+            processed_narrator_prefixes: default_processed_narrator_prefixes(),
+            processed_min_genres: default_processed_min_genres(),
+            processed_max_genres: default_processed_max_genres(),
+            force_reprocess: false,
+            default_genre: None,
+            merge_disc_folders: true,
+            series_patterns: default_series_patterns(),
+            quality_threshold: default_quality_threshold(),
+            quality_weights: QualityWeights::default(),
+            fallback_model: None,
+            stamp_provenance: false,
+            ffprobe_fallback: false,
+            metadata_language: None,
+            ffprobe_path: default_ffprobe_path(),
+            genre_write_style: GenreWriteStyle::default(),
+            genre_sources: default_genre_sources(),
         }
     }
 }
 
+fn default_audible_min_confidence() -> u32 {
+    70
+}
+
+fn default_audible_concurrency() -> usize {
+    crate::audible::DEFAULT_AUDIBLE_CONCURRENCY
+}
+
+fn default_audible_timeout_secs() -> u64 {
+    crate::audible::DEFAULT_AUDIBLE_TIMEOUT_SECS
+}
+
+fn default_io_workers() -> usize {
+    crate::tags::DEFAULT_IO_WORKERS
+}
+
+fn default_ffprobe_path() -> String {
+    String::from("ffprobe")
+}
+
+fn default_ollama_url() -> String {
+    String::from(crate::llm::DEFAULT_OLLAMA_URL)
+}
+
+fn default_ollama_model() -> String {
+    String::from("llama3.1")
+}
+
+fn default_review_threshold() -> u32 {
+    DEFAULT_REVIEW_THRESHOLD
+}
+
+fn default_quality_threshold() -> u32 {
+    DEFAULT_QUALITY_THRESHOLD
+}
+
+fn default_narrator_comment_template() -> String {
+    String::from(DEFAULT_NARRATOR_COMMENT_TEMPLATE)
+}
+
+fn default_description_template() -> String {
+    String::from(DEFAULT_DESCRIPTION_TEMPLATE)
+}
+
+fn default_narrator_targets() -> Vec<String> {
+    vec![String::from("composer")]
+}
+
+fn default_min_duration_secs() -> u32 {
+    DEFAULT_MIN_DURATION_SECS
+}
+
+fn default_openai_rpm() -> u32 {
+    crate::llm::DEFAULT_OPENAI_RPM
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_processed_narrator_prefixes() -> Vec<String> {
+    vec![String::from("Narrated by "), String::from("Read by ")]
+}
+
+fn default_processed_min_genres() -> usize {
+    1
+}
+
+fn default_processed_max_genres() -> usize {
+    3
+}
+
+fn default_genre_sources() -> Vec<GenreSource> {
+    vec![GenreSource::Gpt]
+}
+
+/// "Series (Book #3)", "Series Book 01", "Series Vol. 2", "Series #3"
+fn default_series_patterns() -> Vec<String> {
+    vec![
+        String::from(r"(?i)^(?:(?P<series>.+?)\s*)?[(\[]\s*Book\s*#?\s*(?P<sequence>\d+)\s*[)\]]"),
+        String::from(r"(?i)^(?:(?P<series>.+?)[\s,:-]*)?\bBook\s*#?\s*(?P<sequence>\d+)\b"),
+        String::from(r"(?i)^(?:(?P<series>.+?)[\s,:-]*)?\bVol(?:ume)?\.?\s*(?P<sequence>\d+)\b"),
+        String::from(r"^(?:(?P<series>.+?)\s*)?#(?P<sequence>\d+)\b"),
+    ]
+}
+
 pub fn get_config_path() -> Result<PathBuf> {
     let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("No home directory"))?;
     let config_dir = home
@@ -47,19 +380,405 @@ pub fn get_config_path() -> Result<PathBuf> {
     Ok(config_dir.join("config.json"))
 }
 
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// On-disk layout: one Config per named profile (e.g. "kids", "adult"),
+/// with the active one returned by load_config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileStore {
+    pub active_profile: String,
+    pub profiles: HashMap<String, Config>,
+}
+
+impl Default for ProfileStore {
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), Config::default());
+        Self { active_profile: DEFAULT_PROFILE.to_string(), profiles }
+    }
+}
+
+impl ProfileStore {
+    fn active(&self) -> Config {
+        self.profiles.get(&self.active_profile).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub names: Vec<String>,
+}
+
+pub const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+pub const ABS_API_TOKEN_ENV: &str = "ABS_API_TOKEN";
+
+/// Looks up a secret's environment variable; tests pass their own so they
+/// never touch the process environment
+type EnvLookup = fn(&str) -> Option<String>;
+
+fn env_secret(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Secrets resolve as: non-empty environment variable first, then the config
+/// file. Env values are never persisted — see save_config.
 pub fn load_config() -> Result<Config> {
-    let config_path = get_config_path()?;
+    load_config_from(&get_config_path()?, env_secret)
+}
+
+fn load_config_from(config_path: &Path, env: EnvLookup) -> Result<Config> {
+    let mut config = read_config_file(config_path)?;
+    if let Some(key) = env(OPENAI_API_KEY_ENV) {
+        config.openai_api_key = key;
+    }
+    if let Some(token) = env(ABS_API_TOKEN_ENV) {
+        config.abs_api_token = token;
+    }
+    Ok(config)
+}
+
+fn read_config_file(config_path: &Path) -> Result<Config> {
+    Ok(read_profile_store(config_path)?.active())
+}
+
+/// Older versions saved a single flat Config; those are moved into the
+/// default profile the first time they're read.
+fn read_profile_store(config_path: &Path) -> Result<ProfileStore> {
     if !config_path.exists() {
-        return Ok(Config::default());
+        return Ok(ProfileStore::default());
     }
     let contents = fs::read_to_string(config_path)?;
-    let config: Config = serde_json::from_str(&contents)?;
-    Ok(config)
+    let value: serde_json::Value = serde_json::from_str(&contents)?;
+    
+    if value.get("profiles").is_some() {
+        return Ok(serde_json::from_value(value)?);
+    }
+    
+    let flat: Config = serde_json::from_value(value)?;
+    let mut profiles = HashMap::new();
+    profiles.insert(DEFAULT_PROFILE.to_string(), flat);
+    let store = ProfileStore { active_profile: DEFAULT_PROFILE.to_string(), profiles };
+    write_profile_store(&store, config_path)?;
+    println!("📦 Migrated settings into the '{}' profile", DEFAULT_PROFILE);
+    Ok(store)
 }
 
-pub fn save_config(config: &Config) -> Result<()> {
-    let config_path = get_config_path()?;
-    let contents = serde_json::to_string_pretty(config)?;
+fn write_profile_store(store: &ProfileStore, config_path: &Path) -> Result<()> {
+    let contents = serde_json::to_string_pretty(store)?;
     fs::write(config_path, contents)?;
     Ok(())
 }
+
+pub fn save_config(config: &Config) -> Result<()> {
+    save_config_to(config, &get_config_path()?, env_secret)
+}
+
+fn save_config_to(config: &Config, config_path: &Path, env: EnvLookup) -> Result<()> {
+    let active = read_profile_store(config_path)?.active_profile;
+    save_profile_to(&active, config, config_path, env)
+}
+
+/// A secret that still equals its environment variable came from the
+/// environment, so the file keeps whatever value it already had.
+fn save_profile_to(name: &str, config: &Config, config_path: &Path, env: EnvLookup) -> Result<()> {
+    let mut store = read_profile_store(config_path)?;
+    let on_disk = store.profiles.get(name).cloned().unwrap_or_default();
+    let mut to_write = config.clone();
+    
+    if env(OPENAI_API_KEY_ENV).as_deref() == Some(config.openai_api_key.as_str()) {
+        to_write.openai_api_key = on_disk.openai_api_key;
+    }
+    if env(ABS_API_TOKEN_ENV).as_deref() == Some(config.abs_api_token.as_str()) {
+        to_write.abs_api_token = on_disk.abs_api_token;
+    }
+    
+    store.profiles.insert(name.to_string(), to_write);
+    write_profile_store(&store, config_path)
+}
+
+pub fn save_profile(name: &str, config: &Config) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Profile name cannot be empty");
+    }
+    save_profile_to(name, config, &get_config_path()?, env_secret)
+}
+
+pub fn list_profiles() -> Result<ProfileList> {
+    list_profiles_in(&get_config_path()?)
+}
+
+fn list_profiles_in(config_path: &Path) -> Result<ProfileList> {
+    let store = read_profile_store(config_path)?;
+    let mut names: Vec<String> = store.profiles.keys().cloned().collect();
+    names.sort();
+    Ok(ProfileList { active: store.active_profile, names })
+}
+
+/// Make `name` the active profile and return its settings.
+pub fn switch_profile(name: &str) -> Result<Config> {
+    switch_profile_in(name, &get_config_path()?)
+}
+
+fn switch_profile_in(name: &str, config_path: &Path) -> Result<Config> {
+    let mut store = read_profile_store(config_path)?;
+    if !store.profiles.contains_key(name) {
+        anyhow::bail!("No profile named '{}'", name);
+    }
+    store.active_profile = name.to_string();
+    write_profile_store(&store, config_path)?;
+    load_config_from(config_path, env_secret)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem with one config field, shown in the settings validation panel
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &str, severity: IssueSeverity, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), severity, message: message.into() }
+    }
+}
+
+/// Reject weightings no book could score against: all-zero weights, or a
+/// threshold above 100%
+pub fn check_quality_settings(config: &Config) -> Result<(), String> {
+    if config.quality_weights.total() == 0 {
+        return Err("Quality weights are all zero; give at least one field some weight".to_string());
+    }
+    if config.quality_threshold > 100 {
+        return Err(format!("Quality threshold {} is above 100", config.quality_threshold));
+    }
+    Ok(())
+}
+
+/// Check the settings that otherwise only fail deep inside a scan or push.
+pub fn validate(config: &Config, scan_paths: &[String]) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    
+    if config.abs_base_url.trim().is_empty() {
+        issues.push(ConfigIssue::new("abs_base_url", IssueSeverity::Warning, "AudiobookShelf URL is not set"));
+    } else {
+        match reqwest::Url::parse(config.abs_base_url.trim()) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+            Ok(url) => issues.push(ConfigIssue::new(
+                "abs_base_url",
+                IssueSeverity::Error,
+                format!("URL must use http or https, not {}", url.scheme()),
+            )),
+            Err(e) => issues.push(ConfigIssue::new(
+                "abs_base_url",
+                IssueSeverity::Error,
+                format!("Not a valid URL: {}", e),
+            )),
+        }
+    }
+    
+    if config.max_workers == 0 {
+        issues.push(ConfigIssue::new("max_workers", IssueSeverity::Error, "Must be at least 1"));
+    }
+    
+    if config.io_workers == 0 {
+        issues.push(ConfigIssue::new("io_workers", IssueSeverity::Error, "Must be at least 1"));
+    }
+    
+    if config.audible_enabled && config.audible_concurrency == 0 {
+        issues.push(ConfigIssue::new("audible_concurrency", IssueSeverity::Error, "Must be at least 1"));
+    }
+    
+    if config.audible_enabled && !std::path::Path::new(&config.audible_cli_path).exists() {
+        issues.push(ConfigIssue::new(
+            "audible_cli_path",
+            IssueSeverity::Error,
+            format!("Audible is enabled but {} does not exist", config.audible_cli_path),
+        ));
+    }
+    
+    if config.llm_backend == LlmBackend::OpenAi {
+        let key = config.openai_api_key.trim();
+        if key.is_empty() {
+            issues.push(ConfigIssue::new(
+                "openai_api_key",
+                IssueSeverity::Warning,
+                "No OpenAI key — GPT extraction and merging will be skipped",
+            ));
+        } else if !key.starts_with("sk-") || key.len() < 20 || key.contains(char::is_whitespace) {
+            issues.push(ConfigIssue::new(
+                "openai_api_key",
+                IssueSeverity::Warning,
+                "Doesn't look like an OpenAI key (expected \"sk-...\")",
+            ));
+        }
+    }
+    
+    if let Some(genre) = config.default_genre.as_deref().filter(|g| !g.trim().is_empty()) {
+        if crate::scanner::approved_genre(genre).is_none() {
+            issues.push(ConfigIssue::new(
+                "default_genre",
+                IssueSeverity::Warning,
+                format!("\"{}\" is not an approved genre and will be ignored", genre),
+            ));
+        }
+    }
+    
+    if config.genre_sources.is_empty() {
+        issues.push(ConfigIssue::new(
+            "genre_sources",
+            IssueSeverity::Warning,
+            "No genre sources — books will only get the default genre",
+        ));
+    }
+    
+    for pattern in &config.series_patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            issues.push(ConfigIssue::new(
+                "series_patterns",
+                IssueSeverity::Error,
+                format!("Invalid pattern {}: {}", pattern, e),
+            ));
+        }
+    }
+    
+    if let Err(message) = check_quality_settings(config) {
+        issues.push(ConfigIssue::new("quality_weights", IssueSeverity::Error, message));
+    }
+    
+    for path in scan_paths {
+        if !std::path::Path::new(path).is_dir() {
+            issues.push(ConfigIssue::new(
+                "scan_paths",
+                IssueSeverity::Error,
+                format!("Folder does not exist: {}", path),
+            ));
+        }
+    }
+    
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::temp_dir;
+    
+    fn fields(issues: &[ConfigIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.field.as_str()).collect()
+    }
+    
+    #[test]
+    fn test_env_secret_overrides_file_and_is_not_saved() {
+        let dir = temp_dir("config-env");
+        let path = dir.join("config.json");
+        let file_config = Config {
+            openai_api_key: "sk-from-file".to_string(),
+            ..Config::default()
+        };
+        fs::write(&path, serde_json::to_string(&file_config).unwrap()).unwrap();
+        
+        let env: EnvLookup = |name| (name == OPENAI_API_KEY_ENV).then(|| "sk-from-env".to_string());
+        let loaded = load_config_from(&path, env).unwrap();
+        save_config_to(&loaded, &path, env).unwrap();
+        
+        let saved = read_config_file(&path).unwrap();
+        
+        assert_eq!(loaded.openai_api_key, "sk-from-env");
+        assert_eq!(saved.openai_api_key, "sk-from-file");
+    }
+    
+    #[test]
+    fn test_flat_config_migrates_into_default_profile() {
+        let dir = temp_dir("config-migrate");
+        let path = dir.join("config.json");
+        fs::write(&path, r#"{"abs_base_url": "http://nas:13378", "max_workers": 4}"#).unwrap();
+        
+        let loaded = read_config_file(&path).unwrap();
+        let raw: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        
+        assert_eq!(loaded.abs_base_url, "http://nas:13378");
+        assert_eq!(loaded.max_workers, 4);
+        // Missing fields come from the defaults
+        assert!(loaded.backup_tags);
+        assert_eq!(raw["active_profile"], DEFAULT_PROFILE);
+        assert_eq!(raw["profiles"][DEFAULT_PROFILE]["max_workers"], 4);
+    }
+    
+    #[test]
+    fn test_switch_profile_changes_loaded_config() {
+        let dir = temp_dir("config-profiles");
+        let path = dir.join("config.json");
+        
+        let kids = Config { abs_library_id: "kids-lib".to_string(), ..Config::default() };
+        save_profile_to("kids", &kids, &path, |_| None).unwrap();
+        assert_eq!(read_config_file(&path).unwrap().abs_library_id, "");
+        
+        let switched = switch_profile_in("kids", &path).unwrap();
+        let list = list_profiles_in(&path).unwrap();
+        let missing = switch_profile_in("adult", &path);
+        
+        assert_eq!(switched.abs_library_id, "kids-lib");
+        assert_eq!(list.active, "kids");
+        assert_eq!(list.names, vec!["default", "kids"]);
+        assert!(missing.is_err());
+    }
+    
+    #[test]
+    fn test_validate_accepts_good_config() {
+        let config = Config {
+            abs_base_url: "http://localhost:13378".to_string(),
+            openai_api_key: "sk-abcdefghijklmnopqrstuvwxyz".to_string(),
+            ..Config::default()
+        };
+        let scan_dir = std::env::temp_dir().to_string_lossy().to_string();
+        assert!(validate(&config, &[scan_dir]).is_empty());
+    }
+    
+    #[test]
+    fn test_validate_reports_bad_url_and_workers() {
+        let config = Config {
+            abs_base_url: "localhost:13378/abs".to_string(),
+            max_workers: 0,
+            openai_api_key: "sk-abcdefghijklmnopqrstuvwxyz".to_string(),
+            ..Config::default()
+        };
+        let issues = validate(&config, &[]);
+        assert_eq!(fields(&issues), vec!["abs_base_url", "max_workers"]);
+        assert!(issues.iter().all(|i| i.severity == IssueSeverity::Error));
+    }
+    
+    #[test]
+    fn test_validate_reports_missing_paths_and_odd_key() {
+        let config = Config {
+            audible_enabled: true,
+            audible_cli_path: "/nonexistent/audible".to_string(),
+            openai_api_key: "my key".to_string(),
+            ..Config::default()
+        };
+        let issues = validate(&config, &["/nonexistent/library".to_string()]);
+        assert_eq!(fields(&issues), vec!["audible_cli_path", "openai_api_key", "scan_paths"]);
+        assert_eq!(issues[1].severity, IssueSeverity::Warning);
+    }
+    
+    #[test]
+    fn test_quality_settings_reject_unpassable_weighting() {
+        let zero = QualityWeights { title: 0, narrator: 0, description: 0, genres: 0, series: 0, publication: 0 };
+        let config = Config { quality_weights: zero, ..Config::default() };
+        assert!(check_quality_settings(&config).is_err());
+        assert_eq!(fields(&validate(&config, &[])), vec!["openai_api_key", "quality_weights"]);
+        
+        let config = Config { quality_threshold: 120, ..Config::default() };
+        assert!(check_quality_settings(&config).is_err());
+        assert!(check_quality_settings(&Config::default()).is_ok());
+    }
+}
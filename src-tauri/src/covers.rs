@@ -0,0 +1,270 @@
+use anyhow::Result;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A cover a source would provide, fetched for display only — nothing is embedded.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverPreview {
+    pub source: String,
+    pub url: String,
+    pub mime_type: String,
+    /// None when the format isn't PNG or JPEG
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub base64: String,
+}
+
+/// Where the HTTP sources live; swapped for a mock server in tests
+#[derive(Debug, Clone)]
+pub struct CoverEndpoints {
+    pub google_books: String,
+    pub open_library: String,
+    pub open_library_covers: String,
+}
+
+impl Default for CoverEndpoints {
+    fn default() -> Self {
+        Self {
+            google_books: "https://www.googleapis.com".to_string(),
+            open_library: "https://openlibrary.org".to_string(),
+            open_library_covers: "https://covers.openlibrary.org".to_string(),
+        }
+    }
+}
+
+// Previews are re-requested as the user clicks around; keep the downloads
+static COVER_CACHE: Lazy<Mutex<HashMap<String, CoverPreview>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Try Audible (when enabled), then Google Books, then Open Library, and
+/// download the first cover found.
+pub async fn fetch_cover_preview(
+    title: &str,
+    author: &str,
+    config: &crate::config::Config,
+    endpoints: &CoverEndpoints,
+) -> Result<Option<CoverPreview>> {
+    let key = crate::cache::cache_key(title, author);
+    if let Some(cached) = COVER_CACHE.lock().unwrap().get(&key) {
+        println!("   💾 Cover cache hit for '{}'", title);
+        return Ok(Some(cached.clone()));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+
+    let mut candidates: Vec<(&str, String)> = Vec::new();
+    if config.audible_enabled && !config.audible_cli_path.is_empty() {
+        if let Ok(Some(meta)) = crate::audible::search_audible_regions(
+            title, author, &config.audible_cli_path, &config.audible_regions, config.audible_min_confidence
+        ).await {
+            if let Some(url) = meta.cover_url {
+                candidates.push(("Audible", url));
+            }
+        }
+    }
+    if let Some(url) = google_cover_url(&client, endpoints, title, author).await {
+        candidates.push(("Google Books", url));
+    }
+    if let Some(url) = open_library_cover_url(&client, endpoints, title, author).await {
+        candidates.push(("Open Library", url));
+    }
+
+    for (source, url) in candidates {
+        match download_cover(&client, source, &url).await {
+            Ok(preview) => {
+                println!("   🖼️  Cover from {} ({:?}x{:?})", source, preview.width, preview.height);
+                COVER_CACHE.lock().unwrap().insert(key, preview.clone());
+                return Ok(Some(preview));
+            }
+            Err(e) => println!("   ⚠️  {} cover download failed: {}", source, e),
+        }
+    }
+
+    println!("   ⚠️  No cover found for '{}'", title);
+    Ok(None)
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleCoverResponse {
+    #[serde(default)]
+    items: Vec<GoogleCoverItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleCoverItem {
+    #[serde(rename = "volumeInfo")]
+    volume_info: GoogleCoverVolume,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleCoverVolume {
+    #[serde(rename = "imageLinks", default)]
+    image_links: HashMap<String, String>,
+}
+
+async fn google_cover_url(client: &reqwest::Client, endpoints: &CoverEndpoints, title: &str, author: &str) -> Option<String> {
+    let query = format!("intitle:{} inauthor:{}", title, author);
+    let url = format!("{}/books/v1/volumes?q={}", endpoints.google_books, urlencoding::encode(&query));
+    let response: GoogleCoverResponse = client.get(&url).send().await.ok()?.json().await.ok()?;
+
+    let links = &response.items.first()?.volume_info.image_links;
+    ["extraLarge", "large", "medium", "thumbnail", "smallThumbnail"].iter()
+        .find_map(|size| links.get(*size))
+        // Google hands out http links; the https variant serves the same image
+        .map(|link| link.replacen("http://", "https://", 1))
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibrarySearch {
+    #[serde(default)]
+    docs: Vec<OpenLibraryDoc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryDoc {
+    cover_i: Option<u64>,
+}
+
+async fn open_library_cover_url(client: &reqwest::Client, endpoints: &CoverEndpoints, title: &str, author: &str) -> Option<String> {
+    let url = format!(
+        "{}/search.json?title={}&author={}&limit=5",
+        endpoints.open_library,
+        urlencoding::encode(title),
+        urlencoding::encode(author)
+    );
+    let response: OpenLibrarySearch = client.get(&url).send().await.ok()?.json().await.ok()?;
+
+    let cover_id = response.docs.iter().find_map(|d| d.cover_i)?;
+    Some(format!("{}/b/id/{}-L.jpg", endpoints.open_library_covers, cover_id))
+}
+
+async fn download_cover(client: &reqwest::Client, source: &str, url: &str) -> Result<CoverPreview> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    let bytes = response.bytes().await?;
+
+    let (mime_type, dimensions) = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ("image/png", png_dimensions(&bytes))
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        ("image/jpeg", jpeg_dimensions(&bytes))
+    } else if bytes.starts_with(b"GIF8") {
+        // Displayable, but we only read sizes from PNG and JPEG headers
+        ("image/gif", None)
+    } else if bytes.get(8..12) == Some(b"WEBP".as_slice()) {
+        ("image/webp", None)
+    } else {
+        anyhow::bail!("Not an image");
+    };
+
+    Ok(CoverPreview {
+        source: source.to_string(),
+        url: url.to_string(),
+        mime_type: mime_type.to_string(),
+        width: dimensions.map(|(w, _)| w),
+        height: dimensions.map(|(_, h)| h),
+        base64: base64::engine::general_purpose::STANDARD.encode(&bytes),
+    })
+}
+
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+/// Size from the first start-of-frame marker
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let is_sof = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_sof {
+            let frame = bytes.get(pos + 5..pos + 9)?;
+            let height = u16::from_be_bytes([frame[0], frame[1]]) as u32;
+            let width = u16::from_be_bytes([frame[2], frame[3]]) as u32;
+            return Some((width, height));
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 2, 0, 0, 0]);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_cover_preview_falls_back_to_open_library_and_caches() {
+        let mut server = mockito::Server::new_async().await;
+        let endpoints = CoverEndpoints {
+            google_books: server.url(),
+            open_library: server.url(),
+            open_library_covers: server.url(),
+        };
+
+        server.mock("GET", "/books/v1/volumes")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"items": [{"volumeInfo": {"title": "Dune"}}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/search.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"docs": [{"title": "Dune"}, {"title": "Dune", "cover_i": 42}]}"#)
+            .create_async()
+            .await;
+        let image = server.mock("GET", "/b/id/42-L.jpg")
+            .with_status(200)
+            .with_header("content-type", "image/png")
+            .with_body(png_header(500, 800))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = crate::config::Config::default();
+        let title = format!("Dune {}", std::process::id());
+        let preview = fetch_cover_preview(&title, "Frank Herbert", &config, &endpoints).await.unwrap().unwrap();
+        let again = fetch_cover_preview(&title, "frank herbert", &config, &endpoints).await.unwrap().unwrap();
+
+        image.assert_async().await;
+        assert_eq!(preview.source, "Open Library");
+        assert_eq!(preview.mime_type, "image/png");
+        assert_eq!((preview.width, preview.height), (Some(500), Some(800)));
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD.decode(&preview.base64).unwrap(),
+            png_header(500, 800)
+        );
+        assert_eq!(again.url, preview.url);
+    }
+
+    #[test]
+    fn test_jpeg_dimensions_skip_to_frame_header() {
+        let jpeg = [
+            0xFF, 0xD8,
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00,
+            0xFF, 0xC0, 0x00, 0x11, 0x08, 0x02, 0x58, 0x01, 0x90,
+        ];
+        assert_eq!(jpeg_dimensions(&jpeg), Some((400, 600)));
+    }
+}
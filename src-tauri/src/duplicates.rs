@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::scanner::BookGroup;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMember {
+    pub group_id: String,
+    pub group_name: String,
+    pub title: String,
+    pub author: String,
+    pub paths: Vec<String>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCluster {
+    pub key: String,
+    pub members: Vec<DuplicateMember>,
+}
+
+/// Cache key built from punctuation- and spacing-insensitive title/author, so
+/// "The Hobbit" and "the hobbit." by "J.R.R. Tolkien" land together.
+fn duplicate_key(group: &BookGroup) -> String {
+    crate::cache::cache_key(
+        &crate::metadata::normalize_for_match(&group.metadata.title),
+        &crate::metadata::normalize_for_match(&group.metadata.author),
+    )
+}
+
+fn normalize_isbn(isbn: &str) -> Option<String> {
+    let digits: String = isbn.chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+        .collect::<String>()
+        .to_uppercase();
+    (digits.len() == 10 || digits.len() == 13).then_some(digits)
+}
+
+fn member(group: &BookGroup) -> DuplicateMember {
+    let size_bytes = group.files.iter()
+        .filter_map(|f| std::fs::metadata(&f.path).ok())
+        .map(|m| m.len())
+        .sum();
+    
+    DuplicateMember {
+        group_id: group.id.clone(),
+        group_name: group.group_name.clone(),
+        title: group.metadata.title.clone(),
+        author: group.metadata.author.clone(),
+        paths: group.files.iter().map(|f| f.path.clone()).collect(),
+        size_bytes,
+    }
+}
+
+/// Cluster groups by normalized title+author (and shared ISBN when
+/// `match_isbn` is set), returning only clusters with more than one book.
+pub fn find_duplicates(groups: &[BookGroup], match_isbn: bool) -> Vec<DuplicateCluster> {
+    let mut clusters: Vec<(String, Vec<usize>)> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    let mut by_isbn: HashMap<String, usize> = HashMap::new();
+    
+    for (idx, group) in groups.iter().enumerate() {
+        let key = duplicate_key(group);
+        let isbn = if match_isbn {
+            group.metadata.isbn.as_deref().and_then(normalize_isbn)
+        } else {
+            None
+        };
+        
+        let cluster = by_key.get(&key).copied()
+            .or_else(|| isbn.as_ref().and_then(|i| by_isbn.get(i).copied()))
+            .unwrap_or_else(|| {
+                clusters.push((key.clone(), Vec::new()));
+                clusters.len() - 1
+            });
+        
+        clusters[cluster].1.push(idx);
+        by_key.entry(key).or_insert(cluster);
+        if let Some(isbn) = isbn {
+            by_isbn.entry(isbn).or_insert(cluster);
+        }
+    }
+    
+    clusters.into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(key, members)| DuplicateCluster {
+            key,
+            members: members.into_iter().map(|idx| member(&groups[idx])).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::{AudioFile, BookMetadata, GroupType};
+    use std::collections::HashMap;
+    
+    fn group(id: &str, title: &str, author: &str, isbn: Option<&str>) -> BookGroup {
+        BookGroup {
+            id: id.to_string(),
+            group_name: title.to_string(),
+            group_type: GroupType::Single,
+            files: vec![AudioFile {
+                id: id.to_string(),
+                path: format!("/library/{}/book.m4b", id),
+                filename: "book.m4b".to_string(),
+                status: "unchanged".to_string(),
+                changes: HashMap::new(),
+            }],
+            metadata: BookMetadata {
+                title: title.to_string(),
+                subtitle: None,
+                author: author.to_string(),
+                narrator: None,
+                narrators: vec![],
+                series: None,
+                sequence: None,
+                genres: vec![],
+                publisher: None,
+                year: None,
+                description: None,
+                isbn: isbn.map(String::from),
+                asin: None,
+            },
+            total_changes: 0,
+            quality_score: 100,
+            needs_review: false,
+            warnings: Vec::new(),
+            cover_path: None,
+        }
+    }
+    
+    #[test]
+    fn test_exact_and_near_duplicates_cluster() {
+        let groups = vec![
+            group("a", "The Hobbit", "J.R.R. Tolkien", None),
+            group("b", "Dune", "Frank Herbert", None),
+            group("c", "the hobbit.", "J. R. R. Tolkien", None),
+            group("d", "THE HOBBIT", "j.r.r. tolkien", None),
+        ];
+        
+        let clusters = find_duplicates(&groups, true);
+        
+        assert_eq!(clusters.len(), 1);
+        let ids: Vec<&str> = clusters[0].members.iter().map(|m| m.group_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "d"]);
+        assert_eq!(clusters[0].members[0].paths, vec!["/library/a/book.m4b"]);
+    }
+    
+    #[test]
+    fn test_shared_isbn_clusters_different_titles() {
+        let groups = vec![
+            group("a", "The Hobbit", "J.R.R. Tolkien", Some("978-0-261-10221-7")),
+            group("b", "The Hobbit: Or There and Back Again", "Tolkien", Some("9780261102217")),
+        ];
+        
+        assert_eq!(find_duplicates(&groups, true).len(), 1);
+        assert!(find_duplicates(&groups, false).is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use lofty::file::TaggedFileExt;
+use lofty::probe::Probe;
+use lofty::tag::{ItemKey, ItemValue, TagExt, TagItem, TagType};
+use serde::Serialize;
+use std::path::Path;
+
+/// Windows-1252 characters in the 0x80-0x9F range, by byte. UTF-8 misread
+/// as cp1252 produces these where Latin-1 would give control characters.
+const CP1252_HIGH: [(char, u8); 27] = [
+    ('€', 0x80), ('‚', 0x82), ('ƒ', 0x83), ('„', 0x84), ('…', 0x85), ('†', 0x86),
+    ('‡', 0x87), ('ˆ', 0x88), ('‰', 0x89), ('Š', 0x8A), ('‹', 0x8B), ('Œ', 0x8C),
+    ('Ž', 0x8E), ('‘', 0x91), ('’', 0x92), ('“', 0x93), ('”', 0x94), ('•', 0x95),
+    ('–', 0x96), ('—', 0x97), ('˜', 0x98), ('™', 0x99), ('š', 0x9A), ('›', 0x9B),
+    ('œ', 0x9C), ('ž', 0x9E), ('Ÿ', 0x9F),
+];
+
+// Lead bytes of a multi-byte UTF-8 sequence, as they look when read as Latin-1
+const MOJIBAKE_LEADS: &[char] = &['Ã', 'Â', 'â', 'Å', 'Ä', 'Ð', 'Ñ'];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EncodingIssue {
+    pub file_path: String,
+    pub field: String,
+    pub value: String,
+    pub fixed: String,
+}
+
+fn to_single_byte(c: char) -> Option<u8> {
+    match c as u32 {
+        0..=0xFF => Some(c as u32 as u8),
+        _ => CP1252_HIGH.iter().find(|(ch, _)| *ch == c).map(|(_, b)| *b),
+    }
+}
+
+/// Undo UTF-8 that was decoded as Latin-1/cp1252 ("CafÃ©" → "Café").
+/// Only strings that turn back into valid UTF-8 byte-for-byte are fixed, so
+/// genuine accented text ("Café", "Ångström") is never touched.
+pub fn fix_mojibake(value: &str) -> Option<String> {
+    if !value.contains(MOJIBAKE_LEADS) {
+        return None;
+    }
+    
+    let bytes: Option<Vec<u8>> = value.chars().map(to_single_byte).collect();
+    let fixed = String::from_utf8(bytes?).ok()?;
+    
+    // A lead that isn't followed by continuation bytes fails the decode above
+    (fixed != value).then_some(fixed)
+}
+
+/// Text fields in the primary tag whose values look like mojibake
+fn tag_issues(file_path: &str) -> Result<Vec<EncodingIssue>> {
+    let tagged_file = Probe::open(file_path)?.read()?;
+    let Some(tag) = tagged_file.primary_tag() else {
+        return Ok(Vec::new());
+    };
+    
+    Ok(tag.items()
+        .filter_map(|item| {
+            let value = item.value().text()?;
+            let fixed = fix_mojibake(value)?;
+            Some(EncodingIssue {
+                file_path: file_path.to_string(),
+                field: format!("{:?}", item.key()),
+                value: value.to_string(),
+                fixed,
+            })
+        })
+        .collect())
+}
+
+/// Report every tag field under `dir_path` that `fix_encoding` would change
+pub fn detect_encoding_issues(dir_path: &str) -> Result<Vec<EncodingIssue>> {
+    if !Path::new(dir_path).is_dir() {
+        anyhow::bail!("Not a directory: {}", dir_path);
+    }
+    
+    let mut issues = Vec::new();
+    for path in crate::library_export::exportable_files(dir_path) {
+        match tag_issues(&path) {
+            Ok(found) => issues.extend(found),
+            Err(e) => println!("   ⚠️  Skipping {}: {}", path, e),
+        }
+    }
+    
+    println!("🔤 Found {} mojibake field(s) under {}", issues.len(), dir_path);
+    Ok(issues)
+}
+
+/// Re-decode the mojibake fields of one file in place, after backing it up.
+/// Returns the fields that were fixed; an empty list means nothing was written.
+pub fn fix_encoding(file_path: &str) -> Result<Vec<EncodingIssue>> {
+    let path = Path::new(file_path);
+    let issues = tag_issues(file_path)?;
+    if issues.is_empty() {
+        return Ok(issues);
+    }
+    
+    crate::tags::backup_file(path)?;
+    
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let Some(tag) = tagged_file.primary_tag_mut() else {
+        return Ok(Vec::new());
+    };
+    
+    // Rebuilt in order so multi-value fields keep their other values
+    let items: Vec<TagItem> = tag.items()
+        .map(|item| match item.value().text().and_then(fix_mojibake) {
+            Some(fixed) => TagItem::new(item.key().clone(), ItemValue::Text(fixed)),
+            None => item.clone(),
+        })
+        .collect();
+    
+    // The generic tag would save TXXX:ASIN as a bogus frame; put it back after
+    let id3_asin = if tag.tag_type() == TagType::Id3v2 {
+        let key = crate::tags::asin_item_key(TagType::Id3v2);
+        tag.get_string(&key).map(str::to_string)
+    } else {
+        None
+    };
+    
+    tag.retain(|_| false);
+    for item in items {
+        if id3_asin.is_some() && item.key() == &ItemKey::Unknown(crate::tags::ASIN_TAG_KEY.to_string()) {
+            continue;
+        }
+        tag.push_unchecked(item);
+    }
+    tag.save_to_path(path, lofty::config::WriteOptions::default())
+        .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
+    if let Some(asin) = id3_asin {
+        crate::tags::write_id3v2_asin(path, &asin)?;
+    }
+    
+    println!("🔤 Fixed {} field(s) in {}", issues.len(), file_path);
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::temp_dir;
+    
+    #[test]
+    fn test_fix_mojibake_known_strings() {
+        assert_eq!(fix_mojibake("CafÃ©").as_deref(), Some("Café"));
+        assert_eq!(fix_mojibake("Ã‰mile Zola").as_deref(), Some("Émile Zola"));
+        assert_eq!(fix_mojibake("Ender’s Game".replace('’', "â€™").as_str()).as_deref(), Some("Ender’s Game"));
+        assert_eq!(fix_mojibake("Ã…ngstrÃ¶m").as_deref(), Some("Ångström"));
+    }
+    
+    #[test]
+    fn test_fix_mojibake_leaves_real_text_alone() {
+        assert_eq!(fix_mojibake("Dune"), None);
+        assert_eq!(fix_mojibake("Café"), None);
+        assert_eq!(fix_mojibake("Ångström"), None);
+        // A lone Ã that isn't followed by a continuation byte
+        assert_eq!(fix_mojibake("SÃO PAULO"), None);
+    }
+    
+    #[test]
+    fn test_fix_encoding_rewrites_tag_and_backs_up() {
+        use lofty::tag::{Accessor, Tag};
+        
+        let dir = temp_dir("mojibake");
+        let path = dir.join("book.flac");
+        crate::test_fixtures::write_flac(&path, 1);
+        let mut tag = Tag::new(TagType::VorbisComments);
+        tag.set_title("Les MisÃ©rables".to_string());
+        tag.set_artist("Victor Hugo".to_string());
+        tag.save_to_path(&path, lofty::config::WriteOptions::default()).unwrap();
+        
+        let detected = detect_encoding_issues(dir.to_str().unwrap()).unwrap();
+        let fixed = fix_encoding(path.to_str().unwrap()).unwrap();
+        let again = detect_encoding_issues(dir.to_str().unwrap()).unwrap();
+        let reread = Probe::open(&path).unwrap().read().unwrap();
+        let title = reread.primary_tag().unwrap().title().map(|t| t.to_string());
+        let artist = reread.primary_tag().unwrap().artist().map(|a| a.to_string());
+        let backed_up = dir.join("book.flac.backup").exists();
+        
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].fixed, "Les Misérables");
+        assert_eq!(fixed, detected);
+        assert!(again.is_empty());
+        assert_eq!(title.as_deref(), Some("Les Misérables"));
+        assert_eq!(artist.as_deref(), Some("Victor Hugo"));
+        assert!(backed_up);
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::scanner::FileTags;
+
+#[derive(Debug, Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    format: ProbeFormat,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+/// Map ffprobe's `-show_format` JSON onto FileTags. Key case varies by
+/// container (ASF gives "WM/..." names for anything ffmpeg doesn't map), so
+/// keys are matched case-insensitively. The result is always read-only.
+pub fn tags_from_json(json: &str) -> Result<(FileTags, Option<f64>)> {
+    let output: ProbeOutput = serde_json::from_str(json)?;
+    let fields: HashMap<String, String> = output.format.tags.into_iter()
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .filter(|(_, v)| !v.is_empty())
+        .collect();
+    let get = |keys: &[&str]| keys.iter().find_map(|k| fields.get(*k).cloned());
+    
+    let tags = FileTags {
+        title: get(&["title"]),
+        artist: get(&["artist", "author"]),
+        album: get(&["album", "wm/albumtitle"]),
+        album_artist: get(&["album_artist", "wm/albumartist"]),
+        composer: get(&["composer", "wm/composer"]),
+        narrator: get(&["narrator", "composer", "wm/composer"]),
+        genre: get(&["genre", "wm/genre"]),
+        year: get(&["date", "year", "wm/year"]).map(|d| d.chars().take(4).collect()),
+        track: get(&["track", "wm/tracknumber"]),
+        comment: get(&["comment", "description"]),
+        publisher: get(&["publisher", "wm/publisher"]),
+        read_only: true,
+        ..FileTags::default()
+    };
+    let duration_secs = output.format.duration
+        .and_then(|d| d.parse::<f64>().ok())
+        .filter(|d| *d > 0.0);
+    
+    Ok((tags, duration_secs))
+}
+
+/// Read tags with ffprobe, for formats lofty can't parse (WMA/ASF)
+pub fn read_tags(ffprobe_path: &str, path: &Path) -> Result<(FileTags, Option<f64>)> {
+    let output = Command::new(ffprobe_path)
+        .args(["-v", "quiet", "-print_format", "json", "-show_format"])
+        .arg(path)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow::anyhow!("ffprobe not found at '{}'", ffprobe_path),
+            _ => anyhow::anyhow!("Failed to run ffprobe: {}", e),
+        })?;
+    
+    if !output.status.success() {
+        anyhow::bail!("ffprobe could not read {}", path.display());
+    }
+    
+    tags_from_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_tags_from_json_maps_asf_names() {
+        let json = r#"{"format": {"duration": "3600.5", "tags": {
+            "title": "The Hobbit",
+            "Author": "J.R.R. Tolkien",
+            "WM/AlbumTitle": "The Hobbit",
+            "WM/Year": "1937-09-21",
+            "genre": " Fantasy "
+        }}}"#;
+        
+        let (tags, duration) = tags_from_json(json).unwrap();
+        
+        assert_eq!(tags.title.as_deref(), Some("The Hobbit"));
+        assert_eq!(tags.artist.as_deref(), Some("J.R.R. Tolkien"));
+        assert_eq!(tags.album.as_deref(), Some("The Hobbit"));
+        assert_eq!(tags.year.as_deref(), Some("1937"));
+        assert_eq!(tags.genre.as_deref(), Some("Fantasy"));
+        assert!(tags.read_only);
+        assert_eq!(duration, Some(3600.5));
+    }
+}
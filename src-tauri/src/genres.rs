@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
+use std::collections::HashMap;
 
 pub const APPROVED_GENRES: &[&str] = &[
     "Action", "Adventure", "Anthology", "Arts", "Biography", "Business",
@@ -49,6 +50,7 @@ pub async fn clean_metadata_with_ai(
     genre: Option<&str>,
     comment: Option<&str>,
     api_key: &str,
+    config: Option<&crate::config::Config>,
 ) -> Result<CleanedMetadata> {
     let cache_key = format!("{}|{}|{}|{}|{}", 
         title.unwrap_or(""), artist.unwrap_or(""), album.unwrap_or(""),
@@ -64,7 +66,7 @@ pub async fn clean_metadata_with_ai(
     
     let comment_preview = comment.map(|c| {
         if c.len() > 500 {
-            format!("{}...", &c[..500])
+            format!("{}...", crate::util::truncate_utf8(c, 500))
         } else {
             c.to_string()
         }
@@ -101,13 +103,13 @@ JSON:"#,
     
     println!("          📤 Sending to OpenAI...");
     
+    crate::llm::throttle_openai(config).await?;
     let client = reqwest::Client::new();
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
+    let response = crate::llm::openai_account_headers(client.post("https://api.openai.com/v1/chat/completions"), config)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&serde_json::json!({
-            "model": "gpt-5-nano",
+            "model": crate::llm::OPENAI_MODEL,
             "messages": [{"role": "user", "content": prompt}],
             "verbosity": "low",
             "reasoning_effort": "minimal"
@@ -163,6 +165,32 @@ pub fn map_genre_basic(genre: &str) -> Option<String> {
     }
 }
 
+/// Rewrite genres through the user's alias map, returning the new list and a
+/// description of each mapping applied (e.g. "Sci-Fi → Science Fiction").
+pub fn apply_genre_aliases(genres: &[String], aliases: &HashMap<String, String>) -> (Vec<String>, Vec<String>) {
+    let lookup: HashMap<String, &String> = aliases.iter()
+        .map(|(alias, target)| (alias.trim().to_lowercase(), target))
+        .collect();
+    
+    let mut result = Vec::new();
+    let mut mappings = Vec::new();
+    
+    for genre in genres {
+        let mapped = match lookup.get(&genre.trim().to_lowercase()) {
+            Some(target) if *target != genre => {
+                mappings.push(format!("{} → {}", genre, target));
+                (*target).clone()
+            }
+            _ => genre.clone(),
+        };
+        if !result.contains(&mapped) {
+            result.push(mapped);
+        }
+    }
+    
+    (result, mappings)
+}
+
 pub fn enforce_genre_policy_basic(genres: &[String]) -> Vec<String> {
     let mut approved = Vec::new();
     for genre in genres {
@@ -173,4 +201,23 @@ pub fn enforce_genre_policy_basic(genres: &[String]) -> Vec<String> {
     }
     if approved.is_empty() { approved.push("Fiction".to_string()); }
     approved
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_apply_genre_aliases() {
+        let aliases = HashMap::from([
+            ("sci-fi".to_string(), "Science Fiction".to_string()),
+            ("Whodunit".to_string(), "Mystery".to_string()),
+        ]);
+        let genres = vec!["Sci-Fi".to_string(), "Fantasy".to_string(), "whodunit".to_string()];
+        
+        let (mapped, mappings) = apply_genre_aliases(&genres, &aliases);
+        assert_eq!(mapped, vec!["Science Fiction", "Fantasy", "Mystery"]);
+        assert_eq!(mappings, vec!["Sci-Fi → Science Fiction", "whodunit → Mystery"]);
+        assert_eq!(enforce_genre_policy_basic(&mapped), vec!["Science Fiction", "Fantasy", "Mystery"]);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::scanner::FileTags;
+
+/// What a file looked like the last time its tags were read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub modified_nanos: u64,
+    pub size: u64,
+    /// Hash of the whole file. Tags can sit anywhere (a faststart moov atom,
+    /// FLAC comments behind a picture block), so a partial hash isn't enough.
+    pub hash: u64,
+    pub tags: FileTags,
+    pub duration_secs: Option<f64>,
+}
+
+/// Persistent path → (mtime, size, hash, tags) index so rescans of an
+/// unchanged library don't re-read every file.
+#[derive(Clone)]
+pub struct FileIndex {
+    db: sled::Db,
+}
+
+impl FileIndex {
+    pub fn new() -> Result<Self> {
+        let cache_dir = dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("audiobook-tagger");
+        std::fs::create_dir_all(&cache_dir)?;
+        
+        Self::open(&cache_dir.join("file_index"))
+    }
+    
+    pub fn open(path: &Path) -> Result<Self> {
+        let db = sled::open(path)?;
+        Ok(Self { db })
+    }
+    
+    /// Cached tags for `path` if the file hasn't changed since it was indexed.
+    /// A file that was only touched (same size and hash, new mtime) still hits.
+    pub fn lookup(&self, path: &Path) -> Option<IndexEntry> {
+        let entry = self.get(path)?;
+        let (modified_nanos, size) = file_stamp(path)?;
+        if entry.size != size {
+            return None;
+        }
+        if entry.modified_nanos == modified_nanos {
+            return Some(entry);
+        }
+        
+        if content_hash(path).ok()? != entry.hash {
+            return None;
+        }
+        let entry = IndexEntry { modified_nanos, ..entry };
+        let _ = self.put(path, &entry);
+        Some(entry)
+    }
+    
+    /// Record freshly read tags, replacing whatever was indexed for `path`
+    pub fn update(&self, path: &Path, tags: &FileTags, duration_secs: Option<f64>) -> Result<()> {
+        let (modified_nanos, size) = file_stamp(path)
+            .ok_or_else(|| anyhow::anyhow!("Cannot stat {}", path.display()))?;
+        let entry = IndexEntry {
+            modified_nanos,
+            size,
+            hash: content_hash(path)?,
+            tags: tags.clone(),
+            duration_secs,
+        };
+        self.put(path, &entry)
+    }
+    
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+    
+    fn get(&self, path: &Path) -> Option<IndexEntry> {
+        let value = self.db.get(path.to_string_lossy().as_bytes()).ok()??;
+        bincode::deserialize(&value).ok()
+    }
+    
+    fn put(&self, path: &Path, entry: &IndexEntry) -> Result<()> {
+        let value = bincode::serialize(entry)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        self.db.insert(path.to_string_lossy().as_bytes(), value)?;
+        Ok(())
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?
+        .duration_since(std::time::UNIX_EPOCH).ok()?
+        .as_nanos() as u64;
+    Some((modified, metadata.len()))
+}
+
+/// FNV-1a over the whole file; stable across builds, unlike std's
+/// DefaultHasher. Only paid for files whose mtime changed.
+fn content_hash(path: &Path) -> Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut hash: u64 = 0xcbf29ce484222325;
+    
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        for byte in &buf[..read] {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::temp_dir;
+    
+    #[test]
+    fn test_touched_file_still_hits_but_edited_file_misses() {
+        let dir = temp_dir("index");
+        let index = FileIndex::open(&dir.join("index")).unwrap();
+        let path = dir.join("book.mp3");
+        std::fs::write(&path, vec![1u8; 4096]).unwrap();
+        
+        let tags = FileTags { title: Some("Dune".to_string()), ..FileTags::default() };
+        index.update(&path, &tags, Some(12.0)).unwrap();
+        assert_eq!(index.lookup(&path).unwrap().tags.title.as_deref(), Some("Dune"));
+        
+        let touched = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(touched).unwrap();
+        assert!(index.lookup(&path).is_some());
+        
+        let mut edited = vec![1u8; 4096];
+        edited[10] = 2;
+        std::fs::write(&path, edited).unwrap();
+        let missed = index.lookup(&path).is_none();
+        
+        // A same-size edit deep inside the file, e.g. comments in padding
+        let large = dir.join("book.m4b");
+        let mut content = vec![0u8; 512 * 1024];
+        std::fs::write(&large, &content).unwrap();
+        index.update(&large, &tags, Some(12.0)).unwrap();
+        content[256 * 1024] = 7;
+        std::fs::write(&large, &content).unwrap();
+        std::fs::File::options().write(true).open(&large).unwrap().set_modified(touched).unwrap();
+        let missed_middle = index.lookup(&large).is_none();
+        
+        assert!(missed);
+        assert!(missed_middle);
+    }
+}
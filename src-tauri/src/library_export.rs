@@ -0,0 +1,260 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::scanner::FieldChange;
+use crate::tag_inspector::{self, RawTags};
+use crate::tags::RESTORABLE_FIELDS;
+
+#[derive(Debug, Serialize)]
+pub struct ExportResult {
+    pub exported: usize,
+    /// Files that couldn't be read, with the reason
+    pub failed: Vec<String>,
+}
+
+/// Audio files under `dir_path` that lofty can read, in a stable order
+pub(crate) fn exportable_files(dir_path: &str) -> Vec<String> {
+    let mut paths: Vec<String> = walkdir::WalkDir::new(dir_path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().starts_with("._"))
+        .filter(|e| {
+            let ext = e.path().extension()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            crate::scanner::is_audio_extension(&ext) && !crate::scanner::is_drm_extension(&ext)
+        })
+        .map(|e| e.path().to_string_lossy().to_string())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// One file in an export: the inspector's dump, plus each restorable field by
+/// its write_file_tags name so an import doesn't depend on display labels
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportEntry {
+    #[serde(flatten)]
+    raw: RawTags,
+    #[serde(default)]
+    fields: Option<BTreeMap<String, String>>,
+}
+
+/// Dump every file's audio properties and tags as one JSON array. Entries are
+/// written as they're inspected, so memory stays flat for large libraries.
+pub fn export_library_metadata(dir_path: &str, out_path: &str, narrator_targets: &[String]) -> Result<ExportResult> {
+    if !Path::new(dir_path).is_dir() {
+        anyhow::bail!("Not a directory: {}", dir_path);
+    }
+    
+    let mut out = BufWriter::new(File::create(out_path)?);
+    let mut exported = 0;
+    let mut failed = Vec::new();
+    
+    out.write_all(b"[\n")?;
+    for path in exportable_files(dir_path) {
+        let inspected = tag_inspector::inspect_file_tags(&path)
+            .and_then(|raw| Ok((raw, crate::tags::read_restorable_fields(&path, narrator_targets)?)));
+        let (raw, fields) = match inspected {
+            Ok(inspected) => inspected,
+            Err(e) => {
+                println!("   ⚠️  Skipping {}: {}", path, e);
+                failed.push(format!("{}: {}", path, e));
+                continue;
+            }
+        };
+        
+        if exported > 0 {
+            out.write_all(b",\n")?;
+        }
+        serde_json::to_writer(&mut out, &ExportEntry { raw, fields: Some(fields) })?;
+        exported += 1;
+    }
+    out.write_all(b"\n]\n")?;
+    out.flush()?;
+    
+    println!("✅ Exported metadata for {} files to {}", exported, out_path);
+    Ok(ExportResult { exported, failed })
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub updated: usize,
+    /// Paths in the export that no longer exist on disk
+    pub missing: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Split an export's fields into the values to write and the fields that were
+/// empty, which get cleared. Fields the export doesn't list are left alone.
+fn plan_restore(fields: &BTreeMap<String, String>) -> (HashMap<String, FieldChange>, Vec<String>) {
+    let mut changes = HashMap::new();
+    let mut cleared = Vec::new();
+    
+    for field in RESTORABLE_FIELDS {
+        match fields.get(*field) {
+            Some(value) if value.is_empty() => cleared.push(field.to_string()),
+            Some(value) => {
+                changes.insert(field.to_string(), FieldChange { old: String::new(), new: value.clone() });
+            }
+            None => {}
+        }
+    }
+    (changes, cleared)
+}
+
+/// Apply a previous export back onto the files it lists, matched by path.
+pub async fn import_library_metadata(
+    json_path: &str,
+    backup: bool,
+    narrator_targets: &[String],
+) -> Result<ImportResult> {
+    let reader = BufReader::new(File::open(json_path)?);
+    let entries: Vec<ExportEntry> = serde_json::from_reader(reader)
+        .map_err(|e| anyhow::anyhow!("Not a metadata export: {}", e))?;
+    
+    let mut updated = 0;
+    let mut missing = Vec::new();
+    let mut failed = Vec::new();
+    
+    for entry in entries {
+        let path = entry.raw.file_path;
+        if !Path::new(&path).exists() {
+            missing.push(path);
+            continue;
+        }
+        
+        let Some(fields) = entry.fields else {
+            println!("   ⚠️  {}: export has no field values - export the library again", path);
+            failed.push(format!("{}: export has no field values", path));
+            continue;
+        };
+        let (changes, cleared) = plan_restore(&fields);
+        if changes.is_empty() && cleared.is_empty() {
+            continue;
+        }
+        
+        let restored = async {
+            if changes.is_empty() {
+                if backup {
+                    crate::tags::backup_file(Path::new(&path))?;
+                }
+            } else {
+                crate::tags::write_file_tags(&path, &changes, backup, false, narrator_targets, false, false).await?;
+            }
+            if !cleared.is_empty() {
+                crate::tags::clear_file_fields(&path, &cleared, narrator_targets)?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }.await;
+        
+        match restored {
+            Ok(()) => updated += 1,
+            Err(e) => {
+                println!("   ❌ {}: {}", path, e);
+                failed.push(format!("{}: {}", path, e));
+            }
+        }
+    }
+    
+    println!("✅ Imported metadata into {} files ({} missing)", updated, missing.len());
+    Ok(ImportResult { updated, missing, failed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_dir, write_flac, TempDir};
+    
+    /// A scratch library with one empty `Book` folder
+    fn temp_library(name: &str) -> TempDir {
+        let dir = temp_dir(&format!("export-{}", name));
+        std::fs::create_dir_all(dir.join("Book")).unwrap();
+        dir
+    }
+    
+    #[test]
+    fn test_export_writes_json_array_for_fixture_dir() {
+        let dir = temp_library("dump");
+        write_flac(&dir.join("Book").join("01.flac"), 3);
+        write_flac(&dir.join("Book").join("02.flac"), 4);
+        std::fs::write(dir.join("Book").join("cover.jpg"), b"not audio").unwrap();
+        std::fs::write(dir.join("Book").join("broken.mp3"), b"not really audio").unwrap();
+        let out_path = dir.join("export.json");
+        
+        let result = export_library_metadata(dir.to_str().unwrap(), out_path.to_str().unwrap(), &[]).unwrap();
+        let dumped: Vec<tag_inspector::RawTags> =
+            serde_json::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+        
+        assert_eq!(result.exported, 2);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(dumped.len(), 2);
+        assert!(dumped[0].file_path.ends_with("01.flac"));
+        assert_eq!(dumped[1].duration_seconds, Some(4));
+        assert_eq!(dumped[0].sample_rate, Some(8000));
+    }
+    
+    async fn set_tags(path: &Path, fields: &[(&str, &str)]) -> Result<()> {
+        let changes: HashMap<String, FieldChange> = fields.iter()
+            .map(|(k, v)| (k.to_string(), FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        crate::tags::write_file_tags(path.to_str().unwrap(), &changes, false, false, &["composer".to_string()], false, false).await
+            .map(|_| ())
+    }
+    
+    #[tokio::test]
+    async fn test_export_then_import_round_trips_tags() {
+        let dir = temp_library("roundtrip");
+        let file = dir.join("Book").join("01.flac");
+        write_flac(&file, 3);
+        set_tags(&file, &[
+            ("title", "The Hobbit"),
+            ("author", "J.R.R. Tolkien"),
+            ("genre", "Fantasy, Classic"),
+            ("narrator", "Andy Serkis"),
+        ]).await.unwrap();
+        
+        let targets = vec!["composer".to_string()];
+        let out_path = dir.join("export.json");
+        export_library_metadata(dir.join("Book").to_str().unwrap(), out_path.to_str().unwrap(), &targets).unwrap();
+        
+        // The experiment we want to undo, including a field the export didn't have
+        set_tags(&file, &[
+            ("title", "Wrong"),
+            ("author", "Someone Else"),
+            ("genre", "Horror"),
+            ("narrator", "Rob Inglis"),
+            ("publisher", "Nobody"),
+        ]).await.unwrap();
+        
+        // A file that has since been moved away
+        let mut export: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+        let mut gone = export[0].clone();
+        gone["file_path"] = serde_json::json!(dir.join("Gone.flac").to_string_lossy());
+        export.push(gone);
+        std::fs::write(&out_path, serde_json::to_string(&export).unwrap()).unwrap();
+        
+        let result = import_library_metadata(out_path.to_str().unwrap(), false, &targets).await.unwrap();
+        let restored = tag_inspector::inspect_file_tags(file.to_str().unwrap()).unwrap();
+        
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.missing.len(), 1);
+        assert!(result.missing[0].ends_with("Gone.flac"));
+        
+        let value = |key: &str| restored.tags.iter().find(|t| t.key == key).map(|t| t.value.clone());
+        assert_eq!(value("Title").as_deref(), Some("The Hobbit"));
+        assert_eq!(value("Artist/Author").as_deref(), Some("J.R.R. Tolkien"));
+        assert_eq!(value("Genre #1").as_deref(), Some("Fantasy"));
+        assert_eq!(value("Genre #2").as_deref(), Some("Classic"));
+        assert_eq!(value("Composer (Narrator?)").as_deref(), Some("Andy Serkis"));
+        assert_eq!(value("Publisher"), None);
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::Result;
+use lofty::config::ParseOptions;
+use lofty::file::TaggedFileExt;
+use lofty::probe::Probe;
+use lofty::tag::ItemKey;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::library_export::exportable_files;
+use crate::tag_inspector::{self, RawTags};
+
+#[derive(Debug, Serialize)]
+pub struct LibraryStats {
+    /// Folders holding at least one readable audio file
+    pub total_books: usize,
+    pub total_files: usize,
+    pub total_duration_seconds: u64,
+    pub total_size_bytes: u64,
+    /// Weighted by duration so one long file outweighs a short intro track
+    pub average_bitrate_kbps: Option<u32>,
+    pub author_count: usize,
+    /// Books per genre, most common first
+    pub genres: Vec<GenreCount>,
+    pub books_missing_narrator: usize,
+    pub books_missing_series: usize,
+    pub books_missing_cover: usize,
+    /// Files lofty couldn't read; not counted anywhere else
+    pub unreadable_files: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GenreCount {
+    pub genre: String,
+    pub books: usize,
+}
+
+/// What one book folder has, merged across its files
+#[derive(Default)]
+struct BookTally {
+    has_narrator: bool,
+    has_series: bool,
+    has_cover: bool,
+    authors: HashSet<String>,
+    genres: HashSet<String>,
+}
+
+impl BookTally {
+    fn add(&mut self, raw: &RawTags, genres: HashSet<String>) {
+        self.has_cover |= raw.picture_count > 0;
+        self.genres.extend(genres);
+        
+        for entry in raw.tags.iter().filter(|t| !t.value.trim().is_empty()) {
+            let key = entry.key.to_uppercase();
+            if key == "COMPOSER (NARRATOR?)" || key.contains("NARRATOR") {
+                self.has_narrator = true;
+            } else if key.ends_with("SERIES") {
+                self.has_series = true;
+            } else if key == "ARTIST/AUTHOR" {
+                self.authors.insert(entry.value.trim().to_string());
+            }
+        }
+    }
+}
+
+/// Aggregate counts for a dashboard. Files are inspected one at a time and
+/// only per-book flags are kept, never the tags themselves.
+pub fn library_stats(dir_path: &str) -> Result<LibraryStats> {
+    if !Path::new(dir_path).is_dir() {
+        anyhow::bail!("Not a directory: {}", dir_path);
+    }
+    
+    let mut books: HashMap<PathBuf, BookTally> = HashMap::new();
+    let mut total_files = 0;
+    let mut unreadable_files = 0;
+    let mut total_duration_seconds = 0;
+    let mut total_size_bytes = 0;
+    let mut weighted_bitrate: u64 = 0;
+    let mut bitrate_seconds: u64 = 0;
+    
+    for path in exportable_files(dir_path) {
+        let raw = match tag_inspector::inspect_file_tags(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                println!("   ⚠️  Skipping {}: {}", path, e);
+                unreadable_files += 1;
+                continue;
+            }
+        };
+        
+        total_files += 1;
+        total_size_bytes += std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let duration = raw.duration_seconds.unwrap_or(0);
+        total_duration_seconds += duration;
+        if let Some(bitrate) = raw.bitrate.filter(|b| *b > 0) {
+            weighted_bitrate += bitrate as u64 * duration;
+            bitrate_seconds += duration;
+        }
+        
+        let folder = Path::new(&path).parent().map(Path::to_path_buf).unwrap_or_default();
+        books.entry(folder.clone())
+            .or_insert_with(|| BookTally { has_cover: has_cover_image(&folder), ..BookTally::default() })
+            .add(&raw, file_genres(&path));
+    }
+    
+    let mut authors = HashSet::new();
+    let mut genre_books: HashMap<String, usize> = HashMap::new();
+    for tally in books.values() {
+        authors.extend(tally.authors.iter().map(|a| a.to_lowercase()));
+        for genre in &tally.genres {
+            *genre_books.entry(genre.clone()).or_insert(0) += 1;
+        }
+    }
+    let mut genres: Vec<GenreCount> = genre_books.into_iter()
+        .map(|(genre, books)| GenreCount { genre, books })
+        .collect();
+    genres.sort_by(|a, b| b.books.cmp(&a.books).then_with(|| a.genre.cmp(&b.genre)));
+    
+    let stats = LibraryStats {
+        total_books: books.len(),
+        total_files,
+        total_duration_seconds,
+        total_size_bytes,
+        average_bitrate_kbps: (bitrate_seconds > 0).then(|| (weighted_bitrate / bitrate_seconds) as u32),
+        author_count: authors.len(),
+        genres,
+        books_missing_narrator: books.values().filter(|b| !b.has_narrator).count(),
+        books_missing_series: books.values().filter(|b| !b.has_series).count(),
+        books_missing_cover: books.values().filter(|b| !b.has_cover).count(),
+        unreadable_files,
+    };
+    
+    println!("📊 {} books, {} files, {}h of audio", stats.total_books, stats.total_files, stats.total_duration_seconds / 3600);
+    Ok(stats)
+}
+
+/// Every distinct genre value in the files under `dir_path` with the number
+/// of files carrying it, most common first. Values are kept as written so
+/// near-duplicates ("Sci-Fi" vs "Science Fiction") show up for aliasing.
+pub fn collect_local_genres(dir_path: &str) -> Result<Vec<(String, usize)>> {
+    if !Path::new(dir_path).is_dir() {
+        anyhow::bail!("Not a directory: {}", dir_path);
+    }
+    
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for path in exportable_files(dir_path) {
+        for genre in file_genres(&path) {
+            *counts.entry(genre).or_insert(0) += 1;
+        }
+    }
+    
+    let mut genres: Vec<(String, usize)> = counts.into_iter().collect();
+    genres.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    println!("🏷️  {} distinct genres under {}", genres.len(), dir_path);
+    Ok(genres)
+}
+
+/// The distinct genres on a file's primary tag. Every genre item is read,
+/// and a joined value ("Fantasy, Classic" or "Mystery; Thriller") counts
+/// once per part. Unreadable files have none.
+fn file_genres(path: &str) -> HashSet<String> {
+    let tagged = match Probe::open(path).and_then(|p| p.options(ParseOptions::new().read_properties(false)).read()) {
+        Ok(tagged) => tagged,
+        Err(e) => {
+            println!("   ⚠️  Skipping {}: {}", path, e);
+            return HashSet::new();
+        }
+    };
+    
+    tagged.primary_tag()
+        .map(|tag| tag.get_strings(&ItemKey::Genre)
+            .flat_map(|g| g.split([',', ';']))
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(str::to_string)
+            .collect())
+        .unwrap_or_default()
+}
+
+/// A cover.jpg-style image sitting next to the audio counts as a cover
+fn has_cover_image(folder: &Path) -> bool {
+    std::fs::read_dir(folder)
+        .map(|entries| entries.filter_map(|e| e.ok()).any(|e| {
+            let ext = e.path().extension()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp")
+        }))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::FieldChange;
+    use crate::test_fixtures::{temp_dir, write_flac};
+    
+    async fn set_tags(path: &Path, fields: &[(&str, &str)]) {
+        let changes: HashMap<String, FieldChange> = fields.iter()
+            .map(|(k, v)| (k.to_string(), FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        crate::tags::write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+    }
+    
+    /// Store `value` as one genre item, the way other taggers join genres
+    fn set_raw_genre(path: &Path, value: &str) {
+        use lofty::file::AudioFile;
+        let mut tagged = lofty::read_from_path(path).unwrap();
+        tagged.primary_tag_mut().unwrap().insert_text(ItemKey::Genre, value.to_string());
+        tagged.save_to_path(path, lofty::config::WriteOptions::default()).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_stats_aggregate_fixture_library() {
+        let dir = temp_dir("stats");
+        std::fs::create_dir_all(dir.join("Dune")).unwrap();
+        std::fs::create_dir_all(dir.join("Hobbit")).unwrap();
+        
+        // Two-part book with a narrator, a series and a folder cover
+        for (name, secs) in [("01.flac", 60), ("02.flac", 40)] {
+            let file = dir.join("Dune").join(name);
+            write_flac(&file, secs);
+            set_tags(&file, &[
+                ("author", "Frank Herbert"),
+                ("narrator", "Scott Brick"),
+                ("series", "Dune"),
+                ("genre", "Science Fiction, Classic"),
+            ]).await;
+        }
+        std::fs::write(dir.join("Dune").join("cover.jpg"), b"jpeg").unwrap();
+        
+        // Bare single-file book
+        let hobbit = dir.join("Hobbit").join("hobbit.flac");
+        write_flac(&hobbit, 30);
+        set_tags(&hobbit, &[("author", "J.R.R. Tolkien"), ("genre", "Classic")]).await;
+        std::fs::write(dir.join("Hobbit").join("broken.mp3"), b"not really audio").unwrap();
+        
+        let stats = library_stats(dir.to_str().unwrap()).unwrap();
+        let expected_size: u64 = ["Dune/01.flac", "Dune/02.flac", "Hobbit/hobbit.flac"].iter()
+            .map(|f| std::fs::metadata(dir.join(f)).unwrap().len())
+            .sum();
+        
+        assert_eq!(stats.total_books, 2);
+        assert_eq!(stats.total_files, 3);
+        assert_eq!(stats.unreadable_files, 1);
+        assert_eq!(stats.total_duration_seconds, 130);
+        assert_eq!(stats.total_size_bytes, expected_size);
+        assert_eq!(stats.author_count, 2);
+        assert_eq!(stats.genres, vec![
+            GenreCount { genre: "Classic".to_string(), books: 2 },
+            GenreCount { genre: "Science Fiction".to_string(), books: 1 },
+        ]);
+        assert_eq!(stats.books_missing_narrator, 1);
+        assert_eq!(stats.books_missing_series, 1);
+        assert_eq!(stats.books_missing_cover, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_stats_split_joined_genre_values() {
+        let dir = temp_dir("stats-joined");
+        std::fs::create_dir_all(dir.join("Rebecca")).unwrap();
+        let file = dir.join("Rebecca").join("rebecca.flac");
+        write_flac(&file, 1);
+        set_tags(&file, &[("author", "Daphne du Maurier")]).await;
+        set_raw_genre(&file, "Mystery; Gothic, Classic");
+        
+        let stats = library_stats(dir.to_str().unwrap()).unwrap();
+        
+        assert_eq!(stats.genres, vec![
+            GenreCount { genre: "Classic".to_string(), books: 1 },
+            GenreCount { genre: "Gothic".to_string(), books: 1 },
+            GenreCount { genre: "Mystery".to_string(), books: 1 },
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn test_collect_local_genres_counts_files_per_genre() {
+        let dir = temp_dir("genres");
+        
+        for (name, genre) in [
+            ("a.flac", "Fantasy, Classic"),
+            ("b.flac", "Fantasy"),
+            ("c.flac", "Sci-Fi"),
+            ("d.flac", "Fantasy, Sci-Fi"),
+        ] {
+            let file = dir.join(name);
+            write_flac(&file, 1);
+            set_tags(&file, &[("genre", genre)]).await;
+        }
+        let joined = dir.join("e.flac");
+        write_flac(&joined, 1);
+        set_tags(&joined, &[("author", "Frank Herbert")]).await;
+        set_raw_genre(&joined, "Sci-Fi; Classic");
+        write_flac(&dir.join("untagged.flac"), 1);
+        
+        let genres = collect_local_genres(dir.to_str().unwrap()).unwrap();
+        
+        assert_eq!(genres, vec![
+            ("Fantasy".to_string(), 3),
+            ("Sci-Fi".to_string(), 3),
+            ("Classic".to_string(), 2),
+        ]);
+    }
+}
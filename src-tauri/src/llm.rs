@@ -0,0 +1,483 @@
+// src-tauri/src/llm.rs
+// The two LLM backends behind the GPT steps: OpenAI (rate limiting, account
+// headers, key check) and a local Ollama server, which needs no key
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OPENAI_RPM: u32 = 60;
+/// Model every OpenAI request is sent to
+pub const OPENAI_MODEL: &str = "gpt-5-nano";
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com";
+
+/// Spaces requests evenly so no more than `rpm` start in any minute. Shared by
+/// every GPT worker; waiting gives up as soon as the scan is cancelled.
+pub struct RateLimiter {
+    rpm: u32,
+    interval: Duration,
+    next_slot: tokio::sync::Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(rpm: u32) -> Self {
+        Self {
+            rpm,
+            interval: Duration::from_secs(60) / rpm.max(1),
+            next_slot: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+    
+    pub async fn acquire(&self) -> Result<()> {
+        let slot = {
+            let mut next = self.next_slot.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        
+        // Sleep in short steps so a cancelled scan stops waiting promptly
+        loop {
+            if crate::scanner::is_cancelled() {
+                anyhow::bail!("Scan cancelled");
+            }
+            let now = Instant::now();
+            if now >= slot {
+                return Ok(());
+            }
+            tokio::time::sleep((slot - now).min(Duration::from_millis(100))).await;
+        }
+    }
+}
+
+static OPENAI_LIMITER: Lazy<Mutex<Option<Arc<RateLimiter>>>> = Lazy::new(|| Mutex::new(None));
+
+fn shared_limiter(rpm: u32) -> Arc<RateLimiter> {
+    let mut limiter = OPENAI_LIMITER.lock().unwrap();
+    match limiter.as_ref() {
+        Some(existing) if existing.rpm == rpm => Arc::clone(existing),
+        _ => {
+            let created = Arc::new(RateLimiter::new(rpm));
+            *limiter = Some(Arc::clone(&created));
+            created
+        }
+    }
+}
+
+/// Wait for a slot under the configured `openai_rpm` before calling OpenAI.
+/// An rpm of 0 disables throttling.
+pub async fn throttle_openai(config: Option<&crate::config::Config>) -> Result<()> {
+    let rpm = config.map(|c| c.openai_rpm).unwrap_or(DEFAULT_OPENAI_RPM);
+    if rpm == 0 {
+        return Ok(());
+    }
+    shared_limiter(rpm).acquire().await
+}
+
+/// Add the configured organization/project headers to an OpenAI request.
+/// Empty settings add nothing.
+pub fn openai_account_headers(request: reqwest::RequestBuilder, config: Option<&crate::config::Config>) -> reqwest::RequestBuilder {
+    match config {
+        Some(config) => with_account_headers(request, &config.openai_org, &config.openai_project),
+        None => request,
+    }
+}
+
+fn with_account_headers(mut request: reqwest::RequestBuilder, org: &str, project: &str) -> reqwest::RequestBuilder {
+    if !org.trim().is_empty() {
+        request = request.header("OpenAI-Organization", org.trim());
+    }
+    if !project.trim().is_empty() {
+        request = request.header("OpenAI-Project", project.trim());
+    }
+    request
+}
+
+/// Why a key check failed, so the settings page can say what to fix
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyTestFailure {
+    MissingKey,
+    /// 401 - the key is wrong or revoked
+    Unauthorized,
+    /// OpenAI couldn't be reached at all
+    Network,
+    /// Any other non-success status
+    Http,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAiKeyTest {
+    pub success: bool,
+    pub failure: Option<KeyTestFailure>,
+    pub model: String,
+    /// Whether `model` is among the models the key can use
+    pub model_available: bool,
+    pub model_count: usize,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+/// List the key's models via /v1/models to confirm it works before a scan
+pub async fn test_openai_key(base_url: &str, api_key: &str, org: &str, project: &str) -> OpenAiKeyTest {
+    let failed = |failure, message: String| OpenAiKeyTest {
+        success: false,
+        failure: Some(failure),
+        model: OPENAI_MODEL.to_string(),
+        model_available: false,
+        model_count: 0,
+        message,
+    };
+    
+    if api_key.trim().is_empty() {
+        return failed(KeyTestFailure::MissingKey, "No OpenAI key configured".to_string());
+    }
+    
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(15)).build() {
+        Ok(client) => client,
+        Err(e) => return failed(KeyTestFailure::Network, e.to_string()),
+    };
+    let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+    let request = with_account_headers(client.get(&url).bearer_auth(api_key.trim()), org, project);
+    
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => return failed(KeyTestFailure::Network, format!("Could not reach OpenAI: {}", e)),
+    };
+    
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return failed(KeyTestFailure::Unauthorized, "OpenAI rejected the key (401)".to_string());
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return failed(KeyTestFailure::Http, format!("OpenAI returned {}: {}", status, body));
+    }
+    
+    let models = match response.json::<ModelList>().await {
+        Ok(list) => list.data,
+        Err(e) => return failed(KeyTestFailure::Http, format!("Unexpected /v1/models response: {}", e)),
+    };
+    let model_available = models.iter().any(|m| m.id == OPENAI_MODEL);
+    
+    OpenAiKeyTest {
+        success: true,
+        failure: None,
+        model: OPENAI_MODEL.to_string(),
+        model_available,
+        model_count: models.len(),
+        message: if model_available {
+            format!("Key works; {} is available", OPENAI_MODEL)
+        } else {
+            format!("Key works, but {} is not among its {} models", OPENAI_MODEL, models.len())
+        },
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OllamaSettings {
+    pub base_url: String,
+    pub model: String,
+}
+
+/// Returns the Ollama settings when the config selects the Ollama backend
+pub fn ollama_settings(config: &crate::config::Config) -> Option<OllamaSettings> {
+    match config.llm_backend {
+        crate::config::LlmBackend::Ollama => Some(OllamaSettings {
+            base_url: config.ollama_url.clone(),
+            model: config.ollama_model.clone(),
+        }),
+        crate::config::LlmBackend::OpenAi => None,
+    }
+}
+
+/// What the GPT steps call: OpenAI with a key, or Ollama, which needs none
+#[derive(Debug, Clone)]
+pub enum LlmAccess {
+    OpenAi { api_key: String },
+    Ollama(OllamaSettings),
+}
+
+impl LlmAccess {
+    /// None when OpenAI is selected but no key is set - the GPT steps are skipped
+    pub fn from_config(config: &crate::config::Config) -> Option<Self> {
+        if let Some(settings) = ollama_settings(config) {
+            return Some(Self::Ollama(settings));
+        }
+        if config.openai_api_key.is_empty() {
+            None
+        } else {
+            Some(Self::OpenAi { api_key: config.openai_api_key.clone() })
+        }
+    }
+}
+
+/// A reply with nothing usable in it, from either backend. Retrying the same
+/// prompt won't help, so callers go straight to their non-LLM fallback.
+#[derive(Debug, PartialEq)]
+pub enum GptError {
+    Refused(String),
+    EmptyContent { finish_reason: Option<String> },
+}
+
+impl std::fmt::Display for GptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GptError::Refused(message) => write!(f, "model refused: {}", message),
+            GptError::EmptyContent { finish_reason: Some(reason) } => {
+                write!(f, "empty content (finish_reason: {})", reason)
+            }
+            GptError::EmptyContent { finish_reason: None } => write!(f, "empty content"),
+        }
+    }
+}
+
+impl std::error::Error for GptError {}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    message: OllamaMessage,
+    /// Ollama's counterpart to OpenAI's finish_reason
+    #[serde(default)]
+    done_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OllamaMessage {
+    content: String,
+}
+
+/// Send a single-turn chat to Ollama's /api/chat and return the reply text,
+/// in the same shape the OpenAI path hands back.
+pub async fn ollama_chat(
+    settings: &OllamaSettings,
+    system: &str,
+    prompt: &str,
+    json_mode: bool,
+) -> Result<String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/chat", settings.base_url.trim_end_matches('/'));
+    
+    let mut body = serde_json::json!({
+        "model": settings.model,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": prompt }
+        ],
+        "stream": false
+    });
+    
+    if json_mode {
+        body["format"] = serde_json::json!("json");
+    }
+    
+    let response = client
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                anyhow::anyhow!("Could not reach Ollama at {} — is Ollama running?", settings.base_url)
+            } else {
+                anyhow::anyhow!(e)
+            }
+        })?;
+    
+    let status = response.status();
+    let response_text = response.text().await?;
+    
+    if !status.is_success() {
+        println!("             ❌ Ollama Error ({}): {}", status, response_text);
+        anyhow::bail!("Ollama returned status {}: {}", status, response_text);
+    }
+    
+    let parsed: OllamaResponse = serde_json::from_str(&response_text)?;
+    let content = parsed.message.content.trim().to_string();
+    
+    if content.is_empty() {
+        return Err(GptError::EmptyContent { finish_reason: parsed.done_reason }.into());
+    }
+    
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    
+    #[tokio::test]
+    async fn test_rate_limiter_throttles_concurrent_bursts() {
+        // 1200 rpm = one request every 50ms
+        let limiter = Arc::new(RateLimiter::new(1200));
+        let start = Instant::now();
+        
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                let limiter = Arc::clone(&limiter);
+                tokio::spawn(async move {
+                    limiter.acquire().await.unwrap();
+                    Instant::now()
+                })
+            })
+            .collect();
+        
+        let mut started = Vec::new();
+        for handle in handles {
+            started.push(handle.await.unwrap());
+        }
+        started.sort();
+        
+        // The first goes immediately, the other five are spaced out
+        assert!(start.elapsed() >= Duration::from_millis(250));
+        for pair in started.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(45));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_account_headers_sent_only_when_configured() {
+        let mut server = mockito::Server::new_async().await;
+        let with_org = server.mock("POST", "/v1/chat/completions")
+            .match_header("OpenAI-Organization", "org-123")
+            .match_header("OpenAI-Project", Matcher::Missing)
+            .with_status(200)
+            .create_async()
+            .await;
+        
+        let client = reqwest::Client::new();
+        let url = format!("{}/v1/chat/completions", server.url());
+        with_account_headers(client.post(&url), " org-123 ", "").send().await.unwrap();
+        with_org.assert_async().await;
+        
+        let with_both = server.mock("POST", "/v1/chat/completions")
+            .match_header("OpenAI-Organization", "org-123")
+            .match_header("OpenAI-Project", "proj_abc")
+            .with_status(200)
+            .create_async()
+            .await;
+        with_account_headers(client.post(&url), "org-123", "proj_abc").send().await.unwrap();
+        with_both.assert_async().await;
+        
+        let bare = server.mock("POST", "/v1/chat/completions")
+            .match_header("OpenAI-Organization", Matcher::Missing)
+            .match_header("OpenAI-Project", Matcher::Missing)
+            .with_status(200)
+            .create_async()
+            .await;
+        with_account_headers(client.post(&url), "", "  ").send().await.unwrap();
+        bare.assert_async().await;
+    }
+    
+    #[tokio::test]
+    async fn test_openai_key_lists_models() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/v1/models")
+            .match_header("authorization", "Bearer sk-test")
+            .with_status(200)
+            .with_body(format!(r#"{{"object":"list","data":[{{"id":"gpt-4o"}},{{"id":"{}"}}]}}"#, OPENAI_MODEL))
+            .create_async()
+            .await;
+        
+        let result = test_openai_key(&server.url(), "sk-test", "", "").await;
+        
+        mock.assert_async().await;
+        assert!(result.success);
+        assert!(result.model_available);
+        assert_eq!(result.model_count, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_openai_key_distinguishes_auth_and_network_failures() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/v1/models")
+            .with_status(401)
+            .with_body(r#"{"error":{"message":"Incorrect API key provided"}}"#)
+            .create_async()
+            .await;
+        
+        let rejected = test_openai_key(&server.url(), "sk-wrong", "", "").await;
+        let unreachable = test_openai_key("http://127.0.0.1:1", "sk-test", "", "").await;
+        
+        assert!(!rejected.success);
+        assert_eq!(rejected.failure, Some(KeyTestFailure::Unauthorized));
+        assert_eq!(unreachable.failure, Some(KeyTestFailure::Network));
+        assert_eq!(test_openai_key(&server.url(), " ", "", "").await.failure, Some(KeyTestFailure::MissingKey));
+    }
+    
+    #[tokio::test]
+    async fn test_ollama_chat_returns_message_content() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "model": "llama3.1",
+                "stream": false,
+                "format": "json"
+            })))
+            .with_status(200)
+            .with_body(r#"{"model":"llama3.1","message":{"role":"assistant","content":" {\"book_title\":\"Dune\"} "},"done":true}"#)
+            .create_async()
+            .await;
+        
+        let settings = OllamaSettings { base_url: server.url(), model: "llama3.1".to_string() };
+        let content = ollama_chat(&settings, "Extract book info.", "Dune.m4b", true).await.unwrap();
+        
+        assert_eq!(content, r#"{"book_title":"Dune"}"#);
+        mock.assert_async().await;
+    }
+    
+    #[test]
+    fn test_llm_access_follows_the_backend() {
+        let mut config = crate::config::Config::default();
+        config.openai_api_key = String::new();
+        assert!(LlmAccess::from_config(&config).is_none());
+        
+        config.openai_api_key = "sk-test".to_string();
+        assert!(matches!(LlmAccess::from_config(&config), Some(LlmAccess::OpenAi { api_key }) if api_key == "sk-test"));
+        
+        // Ollama runs without a key
+        config.openai_api_key = String::new();
+        config.llm_backend = crate::config::LlmBackend::Ollama;
+        config.ollama_model = "llama3.1".to_string();
+        assert!(matches!(LlmAccess::from_config(&config), Some(LlmAccess::Ollama(s)) if s.model == "llama3.1"));
+    }
+    
+    #[tokio::test]
+    async fn test_ollama_empty_content_is_a_gpt_error() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(r#"{"model":"llama3.1","message":{"role":"assistant","content":"  "},"done":true,"done_reason":"length"}"#)
+            .create_async()
+            .await;
+        
+        let settings = OllamaSettings { base_url: server.url(), model: "llama3.1".to_string() };
+        let err = ollama_chat(&settings, "system", "prompt", true).await.unwrap_err();
+        
+        assert_eq!(
+            err.downcast_ref::<GptError>(),
+            Some(&GptError::EmptyContent { finish_reason: Some("length".to_string()) })
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_ollama_unreachable_error() {
+        let settings = OllamaSettings { base_url: "http://127.0.0.1:1".to_string(), model: "llama3.1".to_string() };
+        let err = ollama_chat(&settings, "system", "prompt", false).await.unwrap_err();
+        assert!(err.to_string().contains("is Ollama running?"));
+    }
+}
@@ -13,6 +13,19 @@ mod progress;  // ADD THIS LINE
 mod tag_inspector;
 mod audible_auth;
 mod file_rename;
+mod chapters;
+mod chapter_writer;
+mod llm;
+mod duplicates;
+mod library_export;
+mod library_stats;
+mod encoding;
+mod ffprobe;
+mod covers;
+mod index;
+mod util;
+#[cfg(test)]
+mod test_fixtures;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -20,6 +33,7 @@ use std::collections::{HashMap, HashSet};
 use tauri::Emitter;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
+use futures::stream::{self, StreamExt};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct RenamePreview {
@@ -99,48 +113,153 @@ fn get_config() -> config::Config {
     config::load_config().unwrap_or_default()
 }
 
+fn check_config(config: &config::Config) -> Result<(), String> {
+    if let Some(ref template) = config.merge_prompt_template {
+        if !template.trim().is_empty() {
+            scanner::validate_merge_prompt_template(template)?;
+        }
+    }
+    config::check_quality_settings(config)?;
+    tags::validate_narrator_targets(&config.narrator_targets).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn save_config(config: config::Config) -> Result<(), String> {
+    check_config(&config)?;
     config::save_config(&config).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn list_profiles() -> Result<config::ProfileList, String> {
+    config::list_profiles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn switch_profile(name: String) -> Result<config::Config, String> {
+    config::switch_profile(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn save_profile(name: String, config: config::Config) -> Result<(), String> {
+    check_config(&config)?;
+    config::save_profile(&name, &config).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn scan_library(
     _window: tauri::Window,
     paths: Vec<String>,
+    overrides: Option<HashMap<String, scanner::BookOverride>>,
+    summary_only: Option<bool>,
+    changed_only: Option<bool>,
 ) -> Result<serde_json::Value, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
     
-    let api_key = if config.openai_api_key.is_empty() {
-        None
-    } else {
-        Some(config.openai_api_key)
-    };
-    
-    let groups = scanner::scan_directory(
-        &paths[0], 
-        api_key,
+    let (groups, warnings, path_errors) = scanner::scan_directory(
+        &paths,
+        llm::LlmAccess::from_config(&config),
+        overrides.unwrap_or_default(),
         config.skip_unchanged,
+        summary_only.unwrap_or(false),
         None
     )
     .await
     .map_err(|e| e.to_string())?;
+    scanner::remember_scan(&groups);
+    
+    // Counts only — the scan dropped the per-file change maps as it went
+    if summary_only.unwrap_or(false) {
+        return Ok(serde_json::json!({
+            "summary": scanner::summarize_groups(&groups),
+            "warnings": warnings,
+            "path_errors": path_errors,
+            "cached_hits": crate::progress::get_cached_hits()
+        }));
+    }
+    
+    // The held scan keeps every group so regrouping still finds them
+    let (groups, omitted_unchanged) = if changed_only.unwrap_or(false) {
+        scanner::changed_groups_only(groups)
+    } else {
+        (groups, 0)
+    };
     
     Ok(serde_json::json!({
-        "groups": groups
+        "groups": groups,
+        "omitted_unchanged": omitted_unchanged,
+        "warnings": warnings,
+        "path_errors": path_errors,
+        "cached_hits": crate::progress::get_cached_hits()
     }))
 }
+#[tauri::command]
+async fn set_group_field(
+    group: scanner::BookGroup,
+    field: String,
+    value: String,
+    backup: bool,
+) -> Result<tags::WriteResult, String> {
+    let config = config::load_config().unwrap_or_default();
+    let mut changes = tags::group_field_changes(&field, &value).map_err(|e| e.to_string())?;
+    if config.write_movement_frames {
+        tags::add_movement_changes(&mut changes);
+    }
+    if config.genre_write_style == config::GenreWriteStyle::Joined {
+        tags::use_joined_genres(&mut changes);
+    }
+    if config.stamp_provenance {
+        tags::add_provenance_changes(&mut changes);
+    }
+    
+    println!("✏️  Setting {} on {} file(s) in {}", field, group.files.len(), group.group_name);
+    Ok(tags::write_group_field(&group.files, &changes, backup, &config.narrator_targets).await)
+}
+
+#[tauri::command]
+fn validate_config(config: config::Config, paths: Option<Vec<String>>) -> Vec<config::ConfigIssue> {
+    config::validate(&config, &paths.unwrap_or_default())
+}
+
+#[tauri::command]
+fn recompute_changes(
+    files: Vec<scanner::RawFileData>,
+    metadata: scanner::BookMetadata,
+) -> Vec<scanner::AudioFile> {
+    let config = config::load_config().ok();
+    let templates = scanner::CommentTemplates::from_config(config.as_ref());
+    scanner::build_audio_files(&files, &metadata, &templates)
+}
+
 #[derive(Debug, Deserialize)]
 struct WriteRequest {
     file_ids: Vec<String>,
     files: HashMap<String, FileData>,
     backup: bool,
+    /// Stop at the first failed file instead of continuing the batch.
+    /// Files already written are not rolled back.
+    #[serde(default)]
+    stop_on_error: bool,
+    /// Re-probe each file after writing and fail if the audio no longer decodes
+    /// or its duration changed
+    #[serde(default)]
+    verify_audio: bool,
+    /// Tag a temp copy and rename it over the original, so a crash mid-write
+    /// can't leave a partially written file
+    #[serde(default)]
+    safe_write: bool,
+    /// Re-attach ID3 frames the tagger can't rewrite (chapters, PRIV...)
+    /// instead of dropping them with a warning
+    #[serde(default)]
+    preserve_unknown_frames: bool,
 }
 
 #[derive(Debug, Deserialize)]
 struct FileData {
     path: String,
     changes: HashMap<String, scanner::FieldChange>,
+    /// Fields whose changes were rejected in review; left untouched on write
+    #[serde(default)]
+    keep_original: HashSet<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -149,16 +268,40 @@ struct PushItem {
     metadata: scanner::BookMetadata,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 struct PushRequest {
     items: Vec<PushItem>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    collection_name: Option<String>,
+    /// Re-read each updated item and fail it if ABS didn't keep the values
+    #[serde(default)]
+    verify: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct PushFailure {
     path: String,
     reason: String,
     status: Option<u16>,
+    #[serde(default)]
+    kind: FailureKind,
+}
+
+/// The step that failed, which decides whether pushing again can help
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FailureKind {
+    /// A request to ABS; `status` tells a transient failure from a permanent one
+    #[default]
+    Request,
+    /// The undo snapshot couldn't be taken, so the item was left alone
+    Snapshot,
+    /// Updated, but the read-back failed or ABS didn't keep a value
+    Verify,
+    /// A local file couldn't be written
+    Write,
 }
 
 #[derive(Debug, Serialize)]
@@ -166,8 +309,70 @@ struct PushResult {
     updated: usize,
     unmatched: Vec<String>,
     failed: Vec<PushFailure>,
+    planned: Vec<PlannedUpdate>,
+    matched: Vec<MatchInfo>,
+}
+
+/// How one pushed path resolved to an ABS library item.
+#[derive(Debug, Serialize)]
+struct MatchInfo {
+    path: String,
+    normalized_path: String,
+    item_id: String,
+    abs_path: String,
+    method: MatchMethod,
+    confidence: f64,
+}
+
+/// Match coverage for a set of push items, without touching ABS.
+#[derive(Debug, Default, Serialize)]
+struct MatchPreview {
+    matched: Vec<MatchInfo>,
+    unmatched: Vec<String>,
+}
+
+/// Path matches are trusted; fuzzy title matches should be checked by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum MatchMethod {
+    Path,
+    Fuzzy,
+}
+
+/// Minimum title (and author, when known) similarity for a fuzzy ABS match.
+const FUZZY_MATCH_THRESHOLD: f64 = 0.85;
+
+/// A PATCH that a dry-run push would have sent.
+#[derive(Debug, Serialize)]
+struct PlannedUpdate {
+    path: String,
+    item_id: String,
+    payload: Value,
+}
+
+/// ABS metadata for one item as it was just before a push overwrote it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PushSnapshot {
+    item_id: String,
+    path: String,
+    previous: scanner::BookMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PushHistoryEntry {
+    timestamp: u64,
+    items: Vec<PushSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+struct UndoResult {
+    reverted: Vec<String>,
+    failed: Vec<PushFailure>,
 }
 
+/// Number of pushes kept in the undo history; older entries are dropped.
+const PUSH_HISTORY_LIMIT: usize = 10;
+
 #[derive(Debug, Deserialize, Clone)]
 struct AbsLibraryItem {
     id: String,
@@ -189,37 +394,156 @@ struct AbsItemsResponse {
 struct UpdateMediaResponse {
     updated: bool,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AbsCollection {
+    id: String,
+    name: String,
+    #[serde(default)]
+    library_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsSearchResponse {
+    #[serde(default)]
+    book: Vec<AbsSearchBook>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AbsSearchBook {
+    library_item: AbsSearchItem,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsSearchItem {
+    id: String,
+    path: String,
+    media: AbsItemMedia,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsCollectionsResponse {
+    collections: Vec<AbsCollection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsItemDetail {
+    media: AbsItemMedia,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsItemMedia {
+    metadata: AbsBookMetadata,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AbsBookMetadata {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    subtitle: Option<String>,
+    #[serde(default)]
+    authors: Vec<AbsNamed>,
+    #[serde(default)]
+    narrators: Vec<String>,
+    #[serde(default)]
+    series: Vec<AbsSeries>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    published_year: Option<String>,
+    #[serde(default)]
+    publisher: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    isbn: Option<String>,
+    #[serde(default)]
+    asin: Option<String>,
+    #[serde(default)]
+    author_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsNamed {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbsSeries {
+    name: String,
+    #[serde(default)]
+    sequence: Option<String>,
+}
 #[tauri::command]
 async fn write_tags(window: tauri::Window, request: WriteRequest) -> Result<tags::WriteResult, String> {
     let total = request.file_ids.len();
     let config = config::load_config().unwrap_or_default();
     let max_workers = config.max_workers.max(1);
     let backup = request.backup;  // EXTRACT THIS BEFORE THE LOOP
+    let verify_audio = request.verify_audio;
+    let safe_write = request.safe_write;
+    let preserve_unknown = request.preserve_unknown_frames;
+    let narrator_targets = Arc::new(config.narrator_targets.clone());
     
-    println!("🚀 Writing {} files with {} parallel workers", total, max_workers);
+    println!("🚀 Writing {} files with {} parallel workers ({} on disk at once)", total, max_workers, config.io_workers.max(1));
+    crate::progress::set_phase(crate::progress::ScanPhase::Writing, "");
     
     let files_to_write: Vec<_> = request.file_ids.iter()
         .filter_map(|file_id| {
             request.files.get(file_id).map(|file_data| {
-                (file_id.clone(), file_data.path.clone(), file_data.changes.clone())
+                let mut changes = file_data.changes.clone();
+                tags::drop_kept_fields(&mut changes, &file_data.keep_original);
+                if config.write_movement_frames {
+                    tags::add_movement_changes(&mut changes);
+                }
+                if config.genre_write_style == config::GenreWriteStyle::Joined {
+                    tags::use_joined_genres(&mut changes);
+                }
+                if config.stamp_provenance && !changes.is_empty() {
+                    tags::add_provenance_changes(&mut changes);
+                }
+                (file_id.clone(), file_data.path.clone(), changes)
             })
         })
         .collect();
     
     let start_time = std::time::Instant::now();
+    
+    if request.stop_on_error {
+        let window_clone = window.clone();
+        let result = tags::write_files_stop_on_error(files_to_write, backup, verify_audio, &narrator_targets, safe_write, preserve_unknown, move |current| {
+            let _ = window_clone.emit("write_progress", serde_json::json!({
+                "current": current,
+                "total": total
+            }));
+        }).await;
+        
+        println!("⚡ Write finished in {:?} ({} written, {} skipped)", start_time.elapsed(), result.success, result.skipped);
+        crate::progress::set_phase(crate::progress::ScanPhase::Complete, "");
+        return Ok(result);
+    }
+    
     let semaphore = Arc::new(tokio::sync::Semaphore::new(max_workers));
+    let io_slots = Arc::new(tokio::sync::Semaphore::new(config.io_workers.max(1)));
     let completed = Arc::new(AtomicUsize::new(0));
 
     let mut handles = Vec::new();
 
     for (file_id, path, changes) in files_to_write {
         let sem = Arc::clone(&semaphore);
+        let io = Arc::clone(&io_slots);
         let completed_clone = Arc::clone(&completed);
         let window_clone = window.clone();
+        let targets = Arc::clone(&narrator_targets);
         
         let handle = tokio::spawn(async move {
             let _permit = sem.acquire().await.unwrap();
-            let result = tags::write_file_tags(&path, &changes, backup).await;
+            let write = tags::write_file_tags(&path, &changes, backup, verify_audio, &targets, safe_write, preserve_unknown);
+            let result = tags::with_io_slot(io, write).await.and_then(|r| r);
             
             // Emit progress after each file completes
             let current = completed_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
@@ -237,12 +561,22 @@ async fn write_tags(window: tauri::Window, request: WriteRequest) -> Result<tags
     let mut success = 0;
     let mut failed = 0;
     let mut errors = Vec::new();
+    let mut warnings = Vec::new();
     
     for handle in handles {
         let (file_id, result) = handle.await.unwrap();
         
         match result {
-            Ok(_) => success += 1,
+            Ok(warning) => {
+                success += 1;
+                if let (Some(message), Some(file_data)) = (warning, request.files.get(&file_id)) {
+                    warnings.push(tags::WriteWarning {
+                        file_id,
+                        path: file_data.path.clone(),
+                        message,
+                    });
+                }
+            }
             Err(e) => {
                 failed += 1;
                 if let Some(file_data) = request.files.get(&file_id) {
@@ -259,8 +593,9 @@ async fn write_tags(window: tauri::Window, request: WriteRequest) -> Result<tags
     let elapsed = start_time.elapsed();
     let rate = total as f64 / elapsed.as_secs_f64();
     println!("⚡ Write performance: {:.1} files/sec, total time: {:?}", rate, elapsed);
+    crate::progress::set_phase(crate::progress::ScanPhase::Complete, "");
     
-    Ok(tags::WriteResult { success, failed, errors })
+    Ok(tags::WriteResult { success, failed, errors, skipped: 0, warnings })
 }
 #[tauri::command]
 async fn test_abs_connection(config: config::Config) -> Result<ConnectionTest, String> {
@@ -277,6 +612,33 @@ async fn test_abs_connection(config: config::Config) -> Result<ConnectionTest, S
     })
 }
 
+#[tauri::command]
+fn clean_backups(dir_path: String, dry_run: bool) -> Result<tags::BackupCleanup, String> {
+    tags::clean_backups(&dir_path, dry_run).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn estimate_scan(paths: Vec<String>) -> Result<scanner::ScanEstimate, String> {
+    let config = config::load_config().ok();
+    let cache = cache::MetadataCache::new().ok();
+    scanner::estimate_scan(&paths, cache.as_ref(), config.as_ref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn test_openai_key(config: config::Config) -> Result<llm::OpenAiKeyTest, String> {
+    Ok(run_test_openai_key(llm::OPENAI_BASE_URL, &config).await)
+}
+
+/// Check the key in the settings being edited, sent with their account headers
+async fn run_test_openai_key(base_url: &str, config: &config::Config) -> llm::OpenAiKeyTest {
+    llm::test_openai_key(
+        base_url,
+        &config.openai_api_key,
+        &config.openai_org,
+        &config.openai_project,
+    ).await
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ConnectionTest {
     success: bool,
@@ -289,11 +651,142 @@ struct PushError {
     status: Option<u16>,
 }
 
+#[tauri::command]
+async fn preview_merge_prompt(file: scanner::RawFileData, folder_name: String) -> Result<String, String> {
+    Ok(scanner::preview_merge_prompt(&file, &folder_name).await)
+}
+
+#[tauri::command]
+fn preview_extract_prompt(file: scanner::RawFileData, folder_name: String) -> Result<String, String> {
+    let config = config::load_config().ok();
+    Ok(scanner::preview_extract_prompt(&file, &folder_name, config.as_ref()))
+}
+
+#[tauri::command]
+async fn fetch_cover_preview(title: String, author: String) -> Result<Option<covers::CoverPreview>, String> {
+    let config = config::load_config().unwrap_or_default();
+    covers::fetch_cover_preview(&title, &author, &config, &covers::CoverEndpoints::default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_group_type(group_id: String, group_type: scanner::GroupType) -> Result<Vec<scanner::BookGroup>, String> {
+    let config = config::load_config().ok();
+    let templates = scanner::CommentTemplates::from_config(config.as_ref());
+    scanner::set_group_type(&group_id, group_type, &templates).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn find_duplicates(
+    groups: Vec<scanner::BookGroup>,
+    match_isbn: Option<bool>,
+) -> Vec<duplicates::DuplicateCluster> {
+    duplicates::find_duplicates(&groups, match_isbn.unwrap_or(true))
+}
+
 #[tauri::command]
 async fn inspect_file_tags(file_path: String) -> Result<tag_inspector::RawTags, String> {
     tag_inspector::inspect_file_tags(&file_path).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn export_library_metadata(
+    dir_path: String,
+    out_path: String,
+) -> Result<library_export::ExportResult, String> {
+    let config = config::load_config().unwrap_or_default();
+    library_export::export_library_metadata(&dir_path, &out_path, &config.narrator_targets).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn library_stats(dir_path: String) -> Result<library_stats::LibraryStats, String> {
+    library_stats::library_stats(&dir_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn collect_local_genres(dir_path: String) -> Result<Vec<(String, usize)>, String> {
+    library_stats::collect_local_genres(&dir_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn detect_encoding_issues(dir_path: String) -> Result<Vec<encoding::EncodingIssue>, String> {
+    encoding::detect_encoding_issues(&dir_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn fix_encoding(file_path: String) -> Result<Vec<encoding::EncodingIssue>, String> {
+    encoding::fix_encoding(&file_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_library_metadata(
+    json_path: String,
+    backup: bool,
+) -> Result<library_export::ImportResult, String> {
+    let config = config::load_config().unwrap_or_default();
+    library_export::import_library_metadata(&json_path, backup, &config.narrator_targets)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn write_chapters(
+    file_path: String,
+    chapters: Vec<tag_inspector::ChapterInfo>,
+    backup: bool,
+) -> Result<(), String> {
+    chapter_writer::write_chapters(&file_path, &chapters, backup).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn rewrite_clean(
+    file_path: String,
+    metadata: scanner::BookMetadata,
+    backup: bool,
+    drop_cover: Option<bool>,
+) -> Result<(), String> {
+    let keep_cover = !drop_cover.unwrap_or(false);
+    let narrator_targets = config::load_config().map(|c| c.narrator_targets).unwrap_or_default();
+    tags::rewrite_clean(&file_path, &metadata, backup, keep_cover, &narrator_targets)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove the app's narrator comment and merged genres from one file
+#[tauri::command]
+async fn strip_app_formatting(file_path: String, backup: bool) -> Result<bool, String> {
+    let config = config::load_config().ok();
+    let rules = scanner::ProcessedRules::from_config(config.as_ref());
+    let narrator_targets = config.map(|c| c.narrator_targets).unwrap_or_default();
+    tags::strip_app_formatting(&file_path, backup, &rules, &narrator_targets).map_err(|e| e.to_string())
+}
+
+/// Embed each book's folder cover into its files under `dir_path`; `force`
+/// replaces covers the files already have
+#[tauri::command]
+async fn embed_folder_covers(dir_path: String, backup: bool, force: Option<bool>) -> Result<tags::CoverEmbedResult, String> {
+    let config = config::load_config().unwrap_or_default();
+    let books = scanner::local_book_files(&dir_path, Some(&config)).map_err(|e| e.to_string())?;
+    Ok(tags::embed_folder_covers(&books, backup, force.unwrap_or(false), &config.narrator_targets).await)
+}
+
+#[tauri::command]
+async fn lock_book(file_paths: Vec<String>) -> Result<(), String> {
+    for path in &file_paths {
+        tags::set_locked(path, true).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn unlock_book(file_paths: Vec<String>) -> Result<(), String> {
+    for path in &file_paths {
+        tags::set_locked(path, false).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn clear_cache() -> Result<String, String> {
     cache::MetadataCache::new()
@@ -303,6 +796,14 @@ async fn clear_cache() -> Result<String, String> {
     Ok("Cache cleared successfully".to_string())
 }
 
+/// Opening the cache migrates it; this reports what that did
+#[tauri::command]
+async fn migrate_cache() -> Result<cache::CacheMigration, String> {
+    cache::MetadataCache::new()
+        .map(|cache| cache.migration())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn restart_abs_docker() -> Result<String, String> {
     use std::process::Command;
@@ -337,7 +838,7 @@ async fn force_abs_rescan() -> Result<String, String> {
     if response.status().is_success() {
         Ok("Library rescan triggered".to_string())
     } else {
-        Err(format!("Failed to trigger rescan: {}", response.status()))
+        Err(format!("Failed to trigger rescan: {}", abs_status_message(response.status().as_u16())))
     }
 }
 
@@ -376,9 +877,65 @@ struct Media {
 
 #[derive(Debug, Deserialize)]
 struct ItemMetadata {
+    #[serde(default)]
+    title: Option<String>,
     genres: Option<Vec<String>>,
 }
 
+#[derive(Debug, Serialize)]
+struct GenreNormalizeSummary {
+    message: String,
+    dry_run: bool,
+    updated: usize,
+    skipped: usize,
+    items: Vec<GenreItemReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct AuthorOrderSummary {
+    message: String,
+    dry_run: bool,
+    updated: usize,
+    changes: Vec<AuthorChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct AuthorChange {
+    id: String,
+    title: Option<String>,
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesCanonicalizeSummary {
+    message: String,
+    /// False for a proposal only; nothing was patched
+    confirmed: bool,
+    updated: usize,
+    /// Items ABS wouldn't update
+    failed: Vec<PushFailure>,
+    clusters: Vec<metadata::SeriesCluster>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenreClearSummary {
+    message: String,
+    dry_run: bool,
+    deleted: usize,
+    genres: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenreItemReport {
+    id: String,
+    title: Option<String>,
+    mappings: Vec<String>,
+    /// Before and after, so a dry run can be reviewed item by item
+    old_genres: Vec<String>,
+    new_genres: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct LibraryItemsResponse {
     results: Vec<LibraryItem>,
@@ -390,7 +947,7 @@ async fn cancel_scan() -> Result<(), String> {
     Ok(())
 }
 #[tauri::command]
-async fn clear_all_genres() -> Result<String, String> {
+async fn clear_all_genres(dry_run: Option<bool>) -> Result<GenreClearSummary, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
     
     if config.abs_base_url.is_empty() || config.abs_api_token.is_empty() || config.abs_library_id.is_empty() {
@@ -398,6 +955,14 @@ async fn clear_all_genres() -> Result<String, String> {
     }
     
     let client = reqwest::Client::new();
+    clear_unused_abs_genres(&client, &config, dry_run.unwrap_or(false)).await
+}
+
+async fn clear_unused_abs_genres(
+    client: &reqwest::Client,
+    config: &config::Config,
+    dry_run: bool,
+) -> Result<GenreClearSummary, String> {
     let filter_url = format!("{}/api/libraries/{}/filterdata", config.abs_base_url, config.abs_library_id);
     
     let filter_response = client
@@ -408,7 +973,7 @@ async fn clear_all_genres() -> Result<String, String> {
         .map_err(|e| format!("Failed to fetch filter data: {}", e))?;
     
     if !filter_response.status().is_success() {
-        return Err(format!("Failed to fetch filter data: {}", filter_response.status()));
+        return Err(format!("Failed to fetch filter data: {}", abs_status_message(filter_response.status().as_u16())));
     }
     
     let filter_data: LibraryFilterData = filter_response.json().await.map_err(|e| e.to_string())?;
@@ -422,6 +987,10 @@ async fn clear_all_genres() -> Result<String, String> {
         .await
         .map_err(|e| e.to_string())?;
     
+    if !items_response.status().is_success() {
+        return Err(format!("Failed to fetch library items: {}", abs_status_message(items_response.status().as_u16())));
+    }
+    
     let items: LibraryItemsResponse = items_response.json().await.map_err(|e| e.to_string())?;
     
     let mut used_genres: HashSet<String> = HashSet::new();
@@ -437,7 +1006,21 @@ async fn clear_all_genres() -> Result<String, String> {
         .collect();
     
     if unused_genres.is_empty() {
-        return Ok("No unused genres found".to_string());
+        return Ok(GenreClearSummary {
+            message: "No unused genres found".to_string(),
+            dry_run,
+            deleted: 0,
+            genres: unused_genres,
+        });
+    }
+    
+    if dry_run {
+        return Ok(GenreClearSummary {
+            message: format!("Would remove {} unused genres", unused_genres.len()),
+            dry_run,
+            deleted: unused_genres.len(),
+            genres: unused_genres,
+        });
     }
     
     let mut deleted_count = 0;
@@ -453,14 +1036,30 @@ async fn clear_all_genres() -> Result<String, String> {
         }
     }
     
-    Ok(format!("Removed {} unused genres", deleted_count))
+    Ok(GenreClearSummary {
+        message: format!("Removed {} unused genres", deleted_count),
+        dry_run,
+        deleted: deleted_count,
+        genres: unused_genres,
+    })
 }
 
 #[tauri::command]
-async fn normalize_genres() -> Result<String, String> {
+async fn normalize_genres(
+    use_aliases: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<GenreNormalizeSummary, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
     let client = reqwest::Client::new();
-    
+    normalize_abs_genres(&client, &config, use_aliases.unwrap_or(true), dry_run.unwrap_or(false)).await
+}
+
+async fn normalize_abs_genres(
+    client: &reqwest::Client,
+    config: &config::Config,
+    use_aliases: bool,
+    dry_run: bool,
+) -> Result<GenreNormalizeSummary, String> {
     let url = format!("{}/api/libraries/{}/items?limit=1000", config.abs_base_url, config.abs_library_id);
     let response = client
         .get(&url)
@@ -469,10 +1068,15 @@ async fn normalize_genres() -> Result<String, String> {
         .await
         .map_err(|e| e.to_string())?;
     
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch library items: {}", abs_status_message(response.status().as_u16())));
+    }
+    
     let items: LibraryItemsResponse = response.json().await.map_err(|e| e.to_string())?;
     
     let mut updated_count = 0;
     let mut skipped_count = 0;
+    let mut reports = Vec::new();
     
     for item in items.results {
         if let Some(current_genres) = &item.media.metadata.genres {
@@ -481,9 +1085,26 @@ async fn normalize_genres() -> Result<String, String> {
                 continue;
             }
             
-            let normalized_genres = genres::enforce_genre_policy_basic(current_genres);
+            let (aliased, mappings) = if use_aliases {
+                genres::apply_genre_aliases(current_genres, &config.genre_aliases)
+            } else {
+                (current_genres.clone(), Vec::new())
+            };
+            let normalized_genres = genres::enforce_genre_policy_basic(&aliased);
             
             if normalized_genres != *current_genres {
+                if dry_run {
+                    updated_count += 1;
+                    reports.push(GenreItemReport {
+                        id: item.id.clone(),
+                        title: item.media.metadata.title.clone(),
+                        mappings,
+                        old_genres: current_genres.clone(),
+                        new_genres: normalized_genres,
+                    });
+                    continue;
+                }
+                
                 let update_url = format!("{}/api/items/{}/media", config.abs_base_url, item.id);
                 if let Ok(resp) = client
                     .patch(&update_url)
@@ -493,6 +1114,13 @@ async fn normalize_genres() -> Result<String, String> {
                     .await {
                     if resp.status().is_success() {
                         updated_count += 1;
+                        reports.push(GenreItemReport {
+                            id: item.id.clone(),
+                            title: item.media.metadata.title.clone(),
+                            mappings,
+                            old_genres: current_genres.clone(),
+                            new_genres: normalized_genres,
+                        });
                     }
                 }
             } else {
@@ -501,14 +1129,289 @@ async fn normalize_genres() -> Result<String, String> {
         }
     }
     
-    Ok(format!("Normalized {} items, skipped {}", updated_count, skipped_count))
+    let verb = if dry_run { "Would normalize" } else { "Normalized" };
+    Ok(GenreNormalizeSummary {
+        message: format!("{} {} items, skipped {}", verb, updated_count, skipped_count),
+        dry_run,
+        updated: updated_count,
+        skipped: skipped_count,
+        items: reports,
+    })
 }
 
 #[tauri::command]
-async fn push_abs_updates(request: PushRequest) -> Result<PushResult, String> {
+async fn normalize_author_order(style: String, dry_run: Option<bool>) -> Result<AuthorOrderSummary, String> {
+    let order = metadata::AuthorOrder::parse(&style).map_err(|e| e.to_string())?;
     let config = config::load_config().map_err(|e| e.to_string())?;
     let client = reqwest::Client::new();
-    let library_items = fetch_abs_library_items(&client, &config).await?;
+    normalize_abs_author_order(&client, &config, order, dry_run.unwrap_or(false)).await
+}
+
+async fn normalize_abs_author_order(
+    client: &reqwest::Client,
+    config: &config::Config,
+    order: metadata::AuthorOrder,
+    dry_run: bool,
+) -> Result<AuthorOrderSummary, String> {
+    let items = fetch_expanded_library_items(client, config).await?;
+    
+    let mut updated_count = 0;
+    let mut changes = Vec::new();
+    
+    for item in items {
+        let meta = &item.metadata;
+        let item_changes: Vec<AuthorChange> = meta.authors.iter()
+            .filter_map(|author| {
+                metadata::reorder_author(&author.name, order).map(|to| AuthorChange {
+                    id: item.id.clone(),
+                    title: meta.title.clone(),
+                    from: author.name.clone(),
+                    to,
+                })
+            })
+            .collect();
+        if item_changes.is_empty() {
+            continue;
+        }
+        
+        if !dry_run {
+            // Same "new-N" author ids as a push, so ABS links or creates by name
+            let authors: Vec<Value> = meta.authors.iter()
+                .enumerate()
+                .map(|(i, author)| {
+                    let name = metadata::reorder_author(&author.name, order).unwrap_or_else(|| author.name.clone());
+                    json!({"id": format!("new-{}", i + 1), "name": name})
+                })
+                .collect();
+            let update_url = format!("{}/api/items/{}/media", config.abs_base_url, item.id);
+            let updated = client
+                .patch(&update_url)
+                .header("Authorization", format!("Bearer {}", config.abs_api_token))
+                .json(&json!({"metadata": {"authors": authors}}))
+                .send()
+                .await
+                .map(|resp| resp.status().is_success())
+                .unwrap_or(false);
+            if !updated {
+                println!("   ⚠️  Failed to update authors for {}", item.id);
+                continue;
+            }
+        }
+        
+        for change in &item_changes {
+            println!("   ✍️  {} → {}", change.from, change.to);
+        }
+        updated_count += 1;
+        changes.extend(item_changes);
+    }
+    
+    let verb = if dry_run { "Would reorder" } else { "Reordered" };
+    Ok(AuthorOrderSummary {
+        message: format!("{} authors on {} items", verb, updated_count),
+        dry_run,
+        updated: updated_count,
+        changes,
+    })
+}
+
+/// Propose one name per cluster of similar series names ("The Expanse",
+/// "Expanse", "Expanse Series"), and with `confirm` rename them in ABS.
+/// `mapping` (spelling → canonical) overrides the automatic clustering.
+#[tauri::command]
+async fn canonicalize_series(
+    mapping: Option<HashMap<String, String>>,
+    confirm: Option<bool>,
+) -> Result<SeriesCanonicalizeSummary, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    canonicalize_abs_series(&client, &config, &mapping.unwrap_or_default(), confirm.unwrap_or(false)).await
+}
+
+async fn canonicalize_abs_series(
+    client: &reqwest::Client,
+    config: &config::Config,
+    mapping: &HashMap<String, String>,
+    confirm: bool,
+) -> Result<SeriesCanonicalizeSummary, String> {
+    let items = fetch_expanded_library_items(client, config).await?;
+    
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for item in &items {
+        for series in &item.metadata.series {
+            *counts.entry(series.name.clone()).or_insert(0) += 1;
+        }
+    }
+    let clusters = metadata::cluster_series(&counts.into_iter().collect::<Vec<_>>(), mapping);
+    
+    let renames: HashMap<&str, &str> = clusters.iter()
+        .flat_map(|cluster| cluster.variants.iter()
+            .filter(|(name, _)| *name != cluster.canonical)
+            .map(|(name, _)| (name.as_str(), cluster.canonical.as_str())))
+        .collect();
+    
+    let mut updated_count = 0;
+    let mut failed = Vec::new();
+    if confirm {
+        for item in &items {
+            let series = &item.metadata.series;
+            if !series.iter().any(|s| renames.contains_key(s.name.as_str())) {
+                continue;
+            }
+            
+            // Same "new-N" series ids as a push, so ABS links or creates by name
+            let series: Vec<Value> = series.iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let mut entry = serde_json::Map::new();
+                    entry.insert("id".to_string(), json!(format!("new-{}", i + 1)));
+                    entry.insert("name".to_string(), json!(renames.get(s.name.as_str()).copied().unwrap_or(s.name.as_str())));
+                    if let Some(ref seq) = s.sequence {
+                        entry.insert("sequence".to_string(), json!(seq));
+                    }
+                    Value::Object(entry)
+                })
+                .collect();
+            let update_url = format!("{}/api/items/{}/media", config.abs_base_url, item.id);
+            let failure = match client
+                .patch(&update_url)
+                .header("Authorization", format!("Bearer {}", config.abs_api_token))
+                .json(&json!({"metadata": {"series": series}}))
+                .send()
+                .await
+            {
+                Ok(resp) if resp.status().is_success() => None,
+                Ok(resp) => Some((abs_status_message(resp.status().as_u16()), Some(resp.status().as_u16()))),
+                Err(e) => Some((e.to_string(), None)),
+            };
+            match failure {
+                None => updated_count += 1,
+                Some((reason, status)) => {
+                    println!("   ⚠️  Failed to update series for {}: {}", item.id, reason);
+                    failed.push(PushFailure { path: item.path.clone(), reason, status, kind: FailureKind::Request });
+                }
+            }
+        }
+    }
+    
+    for cluster in &clusters {
+        let names: Vec<&str> = cluster.variants.iter().map(|(name, _)| name.as_str()).collect();
+        println!("   📚 {} → {}", names.join(" / "), cluster.canonical);
+    }
+    let message = if confirm {
+        format!("Renamed series on {} items ({} series), {} failed", updated_count, clusters.len(), failed.len())
+    } else {
+        format!("Found {} series with variant names", clusters.len())
+    };
+    Ok(SeriesCanonicalizeSummary {
+        message,
+        confirmed: confirm,
+        updated: updated_count,
+        failed,
+        clusters,
+    })
+}
+
+/// Emits a `push-progress` event (payload: `progress::PushProgress`) after each
+/// matched item is processed.
+#[tauri::command]
+async fn push_abs_updates(window: tauri::Window, request: PushRequest) -> Result<PushResult, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    let history_path = push_history_path()?;
+    run_push(&client, &config, &request, &history_path, |progress| {
+        // A closed window shouldn't abort the push
+        let _ = window.emit("push-progress", progress);
+    }).await
+}
+
+/// Push again only the items behind earlier transient failures, backing off
+/// between rounds. Permanent failures are reported again as they were.
+#[tauri::command]
+async fn retry_failed_pushes(failures: Vec<PushFailure>, items: Vec<PushItem>, verify: bool) -> Result<PushResult, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    let history_path = push_history_path()?;
+    run_retry(&client, &config, failures, items, verify, &history_path, RETRY_PUSH_BASE_DELAY).await
+}
+
+const RETRY_PUSH_ROUNDS: u32 = 3;
+const RETRY_PUSH_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Worth pushing again: requests that hit a network error or timeout (no
+/// status), a rate limit or a server error. A 404, a rejected token, a
+/// missing undo snapshot or ABS not keeping a value fails the same way
+/// every time.
+fn is_transient_failure(failure: &PushFailure) -> bool {
+    if failure.kind != FailureKind::Request {
+        return false;
+    }
+    match failure.status {
+        Some(status) => matches!(status, 408 | 429 | 500..=599),
+        None => true,
+    }
+}
+
+async fn run_retry(
+    client: &reqwest::Client,
+    config: &config::Config,
+    failures: Vec<PushFailure>,
+    items: Vec<PushItem>,
+    verify: bool,
+    history_path: &std::path::Path,
+    base_delay: std::time::Duration,
+) -> Result<PushResult, String> {
+    let (transient, mut failed): (Vec<PushFailure>, Vec<PushFailure>) = failures.into_iter().partition(is_transient_failure);
+    let retry_paths: HashSet<String> = transient.iter().map(|f| f.path.clone()).collect();
+    let mut pending: Vec<PushItem> = items.into_iter().filter(|i| retry_paths.contains(&i.path)).collect();
+    println!("🔁 Retrying {} push(es); {} failure(s) won't change on retry", pending.len(), failed.len());
+    
+    let mut result = PushResult { updated: 0, unmatched: Vec::new(), failed: Vec::new(), planned: Vec::new(), matched: Vec::new() };
+    for round in 0..RETRY_PUSH_ROUNDS {
+        if pending.is_empty() {
+            break;
+        }
+        if round > 0 {
+            tokio::time::sleep(base_delay * 2u32.pow(round - 1)).await;
+        }
+        
+        let request = PushRequest { items: std::mem::take(&mut pending), verify, ..PushRequest::default() };
+        let attempt = run_push(client, config, &request, history_path, |_| {}).await?;
+        result.updated += attempt.updated;
+        result.unmatched.extend(attempt.unmatched);
+        result.matched.extend(attempt.matched);
+        
+        let last_round = round + 1 == RETRY_PUSH_ROUNDS;
+        for failure in attempt.failed {
+            match request.items.iter().find(|i| i.path == failure.path) {
+                Some(item) if !last_round && is_transient_failure(&failure) => pending.push(item.clone()),
+                _ => failed.push(failure),
+            }
+        }
+    }
+    
+    result.failed = failed;
+    println!("🔁 Retry finished: {} updated, {} still failed", result.updated, result.failed.len());
+    Ok(result)
+}
+
+/// Which items a push would match in ABS, without sending any updates.
+#[tauri::command]
+async fn preview_abs_matches(items: Vec<PushItem>) -> Result<MatchPreview, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    let (preview, _) = match_push_items(&client, &config, &items).await?;
+    println!("🔎 Match preview: {} matched, {} unmatched", preview.matched.len(), preview.unmatched.len());
+    Ok(preview)
+}
+
+/// The matching half of a push: resolve each item to an ABS library item by
+/// path, then by fuzzy title. Returns the report plus one target per item id.
+async fn match_push_items(
+    client: &reqwest::Client,
+    config: &config::Config,
+    items: &[PushItem],
+) -> Result<(MatchPreview, Vec<(String, PushItem)>), String> {
+    let library_items = fetch_abs_library_items(client, config).await?;
     
     println!("📊 AudiobookShelf has {} items", library_items.len());
     println!("📋 Sample paths from AudiobookShelf (first 10):");
@@ -518,17 +1421,24 @@ async fn push_abs_updates(request: PushRequest) -> Result<PushResult, String> {
     println!();
     
     let mut unmatched = Vec::new();
+    let mut matched = Vec::new();
     let mut targets = Vec::new();
     let mut seen_ids = HashSet::new();
     
-    for item in &request.items {
-        let normalized_path = normalize_path(&item.path);
-        println!("🔍 Looking for: '{}'", normalized_path);
+    for item in items {
+        let resolved = resolve_abs_item(client, config, &item.path, &item.metadata, &library_items).await;
         
-        if let Some(library_item) = find_matching_item(&normalized_path, &library_items) {
-            println!("   ✅ Found match: [{}] {}", library_item.id, library_item.path);
-            if seen_ids.insert(library_item.id.clone()) {
-                targets.push((library_item.id.clone(), item.clone()));
+        if let Some((item_id, abs_path, method, confidence)) = resolved {
+            matched.push(MatchInfo {
+                path: item.path.clone(),
+                normalized_path: normalize_path(&item.path),
+                item_id: item_id.clone(),
+                abs_path,
+                method,
+                confidence,
+            });
+            if seen_ids.insert(item_id.clone()) {
+                targets.push((item_id, item.clone()));
             }
         } else {
             println!("   ❌ No match found");
@@ -536,24 +1446,399 @@ async fn push_abs_updates(request: PushRequest) -> Result<PushResult, String> {
         }
     }
     
+    Ok((MatchPreview { matched, unmatched }, targets))
+}
+
+/// One book through push's matching chain: its path against the library,
+/// then an ABS title search. A failed search counts as no match.
+async fn resolve_abs_item(
+    client: &reqwest::Client,
+    config: &config::Config,
+    path: &str,
+    metadata: &scanner::BookMetadata,
+    library_items: &HashMap<String, AbsLibraryItem>,
+) -> Option<(String, String, MatchMethod, f64)> {
+    let normalized_path = normalize_path(path);
+    println!("🔍 Looking for: '{}'", normalized_path);
+    
+    if let Some(library_item) = find_matching_item(&normalized_path, library_items) {
+        println!("   ✅ Found match: [{}] {}", library_item.id, library_item.path);
+        return Some((library_item.id.clone(), library_item.path.clone(), MatchMethod::Path, 1.0));
+    }
+    match find_item_by_title(client, config, metadata).await {
+        Ok(Some((id, abs_path, confidence))) => {
+            println!("   🔤 Fuzzy title match ({:.2}): [{}] {}", confidence, id, abs_path);
+            Some((id, abs_path, MatchMethod::Fuzzy, confidence))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            println!("   ⚠️  Title search failed: {}", e);
+            None
+        }
+    }
+}
+
+async fn run_push(
+    client: &reqwest::Client,
+    config: &config::Config,
+    request: &PushRequest,
+    history_path: &std::path::Path,
+    on_progress: impl Fn(progress::PushProgress),
+) -> Result<PushResult, String> {
+    let (MatchPreview { matched, unmatched }, targets) = match_push_items(client, config, &request.items).await?;
+    
     let mut failed = Vec::new();
     let mut updated = 0;
+    let mut snapshots = Vec::new();
+    let mut pushed = Vec::new();
+    
+    if request.dry_run {
+        let planned: Vec<PlannedUpdate> = targets
+            .into_iter()
+            .map(|(item_id, push_item)| PlannedUpdate {
+                path: push_item.path,
+                item_id,
+                payload: build_update_payload(&push_item.metadata),
+            })
+            .collect();
+        println!("🧪 Dry run: {} items would be updated", planned.len());
+        return Ok(PushResult { updated: planned.len(), unmatched, failed, planned, matched });
+    }
+    
+    let concurrency = config.max_workers.max(1);
+    println!("🚀 Pushing {} items with {} parallel workers", targets.len(), concurrency);
+    
+    // Each update is its own future, so one failure never cancels its siblings
+    let total = targets.len();
+    let mut outcomes = stream::iter(targets)
+        .map(|(item_id, push_item)| push_one_item(client, config, item_id, push_item, request.verify))
+        .buffer_unordered(concurrency);
+    
+    let mut processed = 0;
+    while let Some(outcome) = outcomes.next().await {
+        processed += 1;
+        let current_path = match outcome {
+            PushOutcome::Updated(snapshot) => {
+                updated += 1;
+                let path = snapshot.path.clone();
+                pushed.push((snapshot.item_id.clone(), path.clone()));
+                snapshots.push(snapshot);
+                path
+            }
+            // Still undoable, so keep the snapshot alongside the failure
+            PushOutcome::Diverged(snapshot, failure) => {
+                let path = snapshot.path.clone();
+                pushed.push((snapshot.item_id.clone(), path.clone()));
+                snapshots.push(snapshot);
+                failed.push(failure);
+                path
+            }
+            PushOutcome::Unchanged { item_id, path } => {
+                pushed.push((item_id, path.clone()));
+                path
+            }
+            PushOutcome::Failed(failure) => {
+                let path = failure.path.clone();
+                failed.push(failure);
+                path
+            }
+        };
+        
+        on_progress(progress::PushProgress {
+            processed,
+            total,
+            updated,
+            failed: failed.len(),
+            current_path,
+        });
+    }
+    
+    if let Err(e) = record_push_history(history_path, snapshots) {
+        println!("⚠️  Could not save push history: {}", e);
+    }
+    
+    if let Some(name) = request.collection_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        failed.extend(add_to_abs_collection(client, config, name, &pushed).await);
+    }
+    
+    Ok(PushResult { updated, unmatched, failed, planned: Vec::new(), matched })
+}
+
+enum PushOutcome {
+    Updated(PushSnapshot),
+    /// Updated, but verification found ABS holding different values
+    Diverged(PushSnapshot, PushFailure),
+    Unchanged { item_id: String, path: String },
+    Failed(PushFailure),
+}
+
+async fn push_one_item(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: String,
+    push_item: PushItem,
+    verify: bool,
+) -> PushOutcome {
+    // Never overwrite an item we couldn't snapshot — it could not be undone
+    let previous = match fetch_abs_item_metadata(client, config, &item_id).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            return PushOutcome::Failed(PushFailure {
+                path: push_item.path,
+                reason: format!("Could not save undo snapshot: {}", e),
+                status: None,
+                kind: FailureKind::Snapshot,
+            });
+        }
+    };
+    
+    match update_abs_item(client, config, &item_id, &push_item.metadata).await {
+        Ok(true) => {
+            let failure = if verify {
+                verify_abs_item(client, config, &item_id, &push_item).await
+            } else {
+                None
+            };
+            let snapshot = PushSnapshot { item_id, path: push_item.path, previous };
+            match failure {
+                Some(failure) => PushOutcome::Diverged(snapshot, failure),
+                None => PushOutcome::Updated(snapshot),
+            }
+        }
+        Ok(false) => PushOutcome::Unchanged { item_id, path: push_item.path },
+        Err(err) => PushOutcome::Failed(PushFailure {
+            path: push_item.path,
+            reason: err.reason,
+            status: err.status,
+            kind: FailureKind::Request,
+        }),
+    }
+}
+
+/// Read the item back and report any key field ABS didn't keep as sent
+async fn verify_abs_item(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: &str,
+    push_item: &PushItem,
+) -> Option<PushFailure> {
+    let reason = match fetch_abs_item_metadata(client, config, item_id).await {
+        Ok(echoed) => {
+            let diverged = push_divergences(&push_item.metadata, &echoed);
+            if diverged.is_empty() {
+                return None;
+            }
+            println!("⚠️  ABS did not keep {} for {}", diverged.join(", "), push_item.path);
+            format!("ABS did not keep: {}", diverged.join("; "))
+        }
+        Err(e) => format!("Could not verify update: {}", e),
+    };
+    Some(PushFailure { path: push_item.path.clone(), reason, status: None, kind: FailureKind::Verify })
+}
+
+/// Title, author and series differences between what was pushed and what ABS returned
+fn push_divergences(sent: &scanner::BookMetadata, echoed: &scanner::BookMetadata) -> Vec<String> {
+    fn names(authors: &str) -> Vec<String> {
+        authors.split(&[',', '&'][..])
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect()
+    }
+    
+    let mut diverged = Vec::new();
+    if sent.title.trim() != echoed.title.trim() {
+        diverged.push(format!("title (sent {:?}, got {:?})", sent.title, echoed.title));
+    }
+    if names(&sent.author) != names(&echoed.author) {
+        diverged.push(format!("author (sent {:?}, got {:?})", sent.author, echoed.author));
+    }
+    // Series is only in the payload when set, so an absent one isn't a divergence
+    if sent.series.is_some() && sent.series != echoed.series {
+        diverged.push(format!("series (sent {:?}, got {:?})", sent.series, echoed.series));
+    } else if sent.series.is_some() && sent.sequence.is_some() && sent.sequence != echoed.sequence {
+        diverged.push(format!("sequence (sent {:?}, got {:?})", sent.sequence, echoed.sequence));
+    }
+    diverged
+}
+
+/// Add pushed items to the named collection, creating it if needed. Failures
+/// are reported per item but never undo the metadata update.
+async fn add_to_abs_collection(
+    client: &reqwest::Client,
+    config: &config::Config,
+    name: &str,
+    items: &[(String, String)],
+) -> Vec<PushFailure> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+    
+    let failure = |path: &str, err: &PushError| PushFailure {
+        path: path.to_string(),
+        reason: format!("Metadata updated, but adding to collection '{}' failed: {}", name, err.reason),
+        status: err.status,
+        kind: FailureKind::Request,
+    };
+    
+    let collection_id = match find_or_create_abs_collection(client, config, name).await {
+        Ok(id) => id,
+        Err(err) => return items.iter().map(|(_, path)| failure(path.as_str(), &err)).collect(),
+    };
+    
+    let mut failed = Vec::new();
+    for (item_id, path) in items {
+        let url = format!("{}/api/collections/{}/book", config.abs_base_url, collection_id);
+        let result = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", config.abs_api_token))
+            .json(&json!({"id": item_id}))
+            .send()
+            .await;
+        
+        let err = match result {
+            Ok(resp) if resp.status().is_success() => continue,
+            Ok(resp) => PushError {
+                reason: abs_status_message(resp.status().as_u16()),
+                status: Some(resp.status().as_u16()),
+            },
+            Err(e) => PushError { reason: e.to_string(), status: None },
+        };
+        failed.push(failure(path.as_str(), &err));
+    }
+    
+    println!("📚 Added {} items to collection '{}'", items.len() - failed.len(), name);
+    failed
+}
+
+async fn find_or_create_abs_collection(
+    client: &reqwest::Client,
+    config: &config::Config,
+    name: &str,
+) -> Result<String, PushError> {
+    let url = format!("{}/api/collections", config.abs_base_url);
+    let to_push_error = |e: reqwest::Error| PushError { reason: e.to_string(), status: None };
+    
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", config.abs_api_token))
+        .send()
+        .await
+        .map_err(to_push_error)?;
+    
+    let status = response.status();
+    if !status.is_success() {
+        return Err(PushError { reason: abs_status_message(status.as_u16()), status: Some(status.as_u16()) });
+    }
+    
+    let existing: AbsCollectionsResponse = response.json().await.map_err(to_push_error)?;
+    if let Some(collection) = existing.collections.into_iter().find(|c| {
+        c.name.eq_ignore_ascii_case(name)
+            && c.library_id.as_deref().map_or(true, |id| id == config.abs_library_id)
+    }) {
+        return Ok(collection.id);
+    }
+    
+    println!("📚 Creating collection '{}'", name);
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", config.abs_api_token))
+        .json(&json!({"libraryId": config.abs_library_id, "name": name}))
+        .send()
+        .await
+        .map_err(to_push_error)?;
+    
+    let status = response.status();
+    if !status.is_success() {
+        return Err(PushError { reason: abs_status_message(status.as_u16()), status: Some(status.as_u16()) });
+    }
+    
+    let created: AbsCollection = response.json().await.map_err(to_push_error)?;
+    Ok(created.id)
+}
+
+fn push_history_path() -> Result<std::path::PathBuf, String> {
+    config::get_config_path()
+        .map(|path| path.with_file_name("push_history.json"))
+        .map_err(|e| e.to_string())
+}
+
+fn load_push_history(path: &std::path::Path) -> Vec<PushHistoryEntry> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_push_history(path: &std::path::Path, history: &[PushHistoryEntry]) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(history).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| e.to_string())
+}
+
+fn record_push_history(path: &std::path::Path, items: Vec<PushSnapshot>) -> Result<(), String> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    
+    let mut history = load_push_history(path);
+    history.push(PushHistoryEntry { timestamp, items });
+    if history.len() > PUSH_HISTORY_LIMIT {
+        let excess = history.len() - PUSH_HISTORY_LIMIT;
+        history.drain(..excess);
+    }
+    save_push_history(path, &history)
+}
+
+#[tauri::command]
+async fn undo_last_push() -> Result<UndoResult, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    let history_path = push_history_path()?;
+    undo_push(&client, &config, &history_path).await
+}
+
+async fn undo_push(
+    client: &reqwest::Client,
+    config: &config::Config,
+    history_path: &std::path::Path,
+) -> Result<UndoResult, String> {
+    let mut history = load_push_history(history_path);
+    let entry = history.pop().ok_or_else(|| "No pushes to undo".to_string())?;
     
-    for (item_id, push_item) in targets {
-        match update_abs_item(&client, &config, &item_id, &push_item.metadata).await {
-            Ok(true) => updated += 1,
-            Ok(false) => {},
+    let mut reverted = Vec::new();
+    let mut failed = Vec::new();
+    let mut remaining = Vec::new();
+    
+    for snapshot in entry.items {
+        let payload = build_restore_payload(&snapshot.previous);
+        match patch_abs_media(client, config, &snapshot.item_id, &payload).await {
+            Ok(_) => {
+                println!("↩️  Restored [{}] {}", snapshot.item_id, snapshot.path);
+                reverted.push(snapshot.path);
+            }
             Err(err) => {
                 failed.push(PushFailure {
-                    path: push_item.path.clone(),
+                    path: snapshot.path.clone(),
                     reason: err.reason,
                     status: err.status,
+                    kind: FailureKind::Request,
                 });
+                remaining.push(snapshot);
             }
         }
     }
     
-    Ok(PushResult { updated, unmatched, failed })
+    // Keep anything that couldn't be restored so the undo can be retried
+    if !remaining.is_empty() {
+        history.push(PushHistoryEntry { timestamp: entry.timestamp, items: remaining });
+    }
+    save_push_history(history_path, &history)?;
+    
+    Ok(UndoResult { reverted, failed })
 }
 
 async fn fetch_abs_library_items(
@@ -575,6 +1860,10 @@ async fn fetch_abs_library_items(
             .await
             .map_err(|e| e.to_string())?;
         
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch library items: {}", abs_status_message(response.status().as_u16())));
+        }
+        
         let payload: AbsItemsResponse = response.json().await.map_err(|e| e.to_string())?;
         let result_count = payload.results.len();
         
@@ -653,30 +1942,303 @@ fn find_matching_item<'a>(
     None
 }
 
-async fn update_abs_item(
+#[tauri::command]
+async fn find_missing_in_abs(dir_path: String) -> Result<Vec<scanner::LocalBook>, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    run_find_missing(&client, &config, &dir_path).await
+}
+
+/// Local books none of whose files match an ABS item, usually because ABS
+/// hasn't scanned them in yet
+async fn run_find_missing(
     client: &reqwest::Client,
     config: &config::Config,
-    item_id: &str,
-    metadata: &scanner::BookMetadata,
-) -> Result<bool, PushError> {
-    let url = format!("{}/api/items/{}/media", config.abs_base_url, item_id);
-    let payload = build_update_payload(metadata);
+    dir_path: &str,
+) -> Result<Vec<scanner::LocalBook>, String> {
+    let books = scanner::local_book_files(dir_path, Some(config)).map_err(|e| e.to_string())?;
+    let library_items = fetch_abs_library_items(client, config).await?;
+    let templates = scanner::CommentTemplates::from_config(Some(config));
+    
+    // Same matching as a push, so a book push would find isn't reported
+    let mut missing = Vec::new();
+    for (name, files) in books {
+        let Some(first) = files.first() else { continue };
+        let metadata = scanner::metadata_from_processed_tags(&first.tags, &name, &templates);
+        if resolve_abs_item(client, config, &first.path, &metadata, &library_items).await.is_none() {
+            missing.push(scanner::LocalBook { name, files: files.into_iter().map(|f| f.path).collect() });
+        }
+    }
+    println!("🔎 {} local book(s) under {} have no ABS item", missing.len(), dir_path);
+    Ok(missing)
+}
+
+/// Fallback for libraries whose paths don't line up with ABS (e.g. different
+/// Docker mount points): search ABS by title and accept the best hit whose
+/// title and author are similar enough.
+async fn find_item_by_title(
+    client: &reqwest::Client,
+    config: &config::Config,
+    book: &scanner::BookMetadata,
+) -> Result<Option<(String, String, f64)>, String> {
+    if book.title.trim().is_empty() {
+        return Ok(None);
+    }
     
+    let url = format!("{}/api/libraries/{}/search?q={}&limit=10",
+        config.abs_base_url, config.abs_library_id, urlencoding::encode(book.title.trim()));
     let response = client
-        .patch(&url)
+        .get(&url)
         .header("Authorization", format!("Bearer {}", config.abs_api_token))
-        .json(&payload)
         .send()
         .await
-        .map_err(|e| PushError {
-            reason: e.to_string(),
-            status: None,
-        })?;
+        .map_err(|e| e.to_string())?;
+    
+    if !response.status().is_success() {
+        return Err(abs_status_message(response.status().as_u16()));
+    }
+    
+    let results: AbsSearchResponse = response.json().await.map_err(|e| e.to_string())?;
+    
+    let best = results.book.into_iter()
+        .filter_map(|hit| {
+            let item = hit.library_item;
+            let meta = &item.media.metadata;
+            let title_score = metadata::similarity(&book.title, meta.title.as_deref().unwrap_or(""));
+            
+            let abs_author = if meta.authors.is_empty() {
+                meta.author_name.clone().unwrap_or_default()
+            } else {
+                meta.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")
+            };
+            let confidence = if book.author.trim().is_empty() || abs_author.is_empty() {
+                title_score
+            } else {
+                title_score.min(metadata::similarity(&book.author, &abs_author))
+            };
+            
+            (confidence >= FUZZY_MATCH_THRESHOLD).then_some((item.id, item.path, confidence))
+        })
+        .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+    
+    Ok(best)
+}
+
+/// Outcome of copying ABS metadata back into local files
+#[derive(Debug, Default, Serialize)]
+struct PullResult {
+    /// Files rewritten with the ABS metadata
+    written: usize,
+    /// Files whose tags already matched ABS
+    unchanged: usize,
+    unmatched: Vec<String>,
+    failed: Vec<PushFailure>,
+    /// Written files that lost tag data along the way
+    warnings: Vec<tags::WriteWarning>,
+}
+
+/// Inverse of push_abs_updates: write each path's ABS metadata into its
+/// files. A folder path pulls into every audio file under it.
+#[tauri::command]
+async fn pull_from_abs(paths: Vec<String>, backup: bool) -> Result<PullResult, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    run_pull(&client, &config, &paths, backup).await
+}
+
+async fn run_pull(
+    client: &reqwest::Client,
+    config: &config::Config,
+    paths: &[String],
+    backup: bool,
+) -> Result<PullResult, String> {
+    let library_items = fetch_abs_library_items(client, config).await?;
+    let templates = scanner::CommentTemplates::from_config(Some(config));
+    let mut fetched: HashMap<String, scanner::BookMetadata> = HashMap::new();
+    let mut result = PullResult::default();
+    
+    for path in paths {
+        let Some(item) = find_matching_item(&normalize_path(path), &library_items) else {
+            println!("   ❓ No ABS match for {}", path);
+            result.unmatched.push(path.clone());
+            continue;
+        };
+        
+        let metadata = match fetched.get(&item.id) {
+            Some(metadata) => metadata.clone(),
+            None => match fetch_abs_item_metadata(client, config, &item.id).await {
+                Ok(metadata) => {
+                    fetched.insert(item.id.clone(), metadata.clone());
+                    metadata
+                }
+                Err(reason) => {
+                    result.failed.push(PushFailure { path: path.clone(), reason, status: None, kind: FailureKind::Request });
+                    continue;
+                }
+            },
+        };
+        
+        let files = if std::path::Path::new(path).is_dir() {
+            library_export::exportable_files(path)
+        } else {
+            vec![path.clone()]
+        };
+        for file in files {
+            let raw = scanner::read_raw_file(std::path::Path::new(&file));
+            let mut changes = scanner::build_changes(&raw, &metadata, &templates);
+            if changes.is_empty() {
+                result.unchanged += 1;
+                continue;
+            }
+            if config.write_movement_frames {
+                tags::add_movement_changes(&mut changes);
+            }
+            if config.genre_write_style == config::GenreWriteStyle::Joined {
+                tags::use_joined_genres(&mut changes);
+            }
+            if config.stamp_provenance {
+                tags::add_provenance_changes(&mut changes);
+            }
+            
+            match tags::write_file_tags(&file, &changes, backup, false, &config.narrator_targets, false, true).await {
+                Ok(warning) => {
+                    result.written += 1;
+                    if let Some(message) = warning {
+                        result.warnings.push(tags::WriteWarning { file_id: raw.id.clone(), path: file, message });
+                    }
+                }
+                Err(e) => result.failed.push(PushFailure { path: file, reason: e.to_string(), status: None, kind: FailureKind::Write }),
+            }
+        }
+    }
+    
+    println!("⬇️  Pulled from ABS: {} written, {} unchanged, {} unmatched, {} failed, {} warning(s)",
+        result.written, result.unchanged, result.unmatched.len(), result.failed.len(), result.warnings.len());
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get_abs_item(path: String) -> Result<scanner::BookMetadata, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    let library_items = fetch_abs_library_items(&client, &config).await?;
+    
+    let normalized_path = normalize_path(&path);
+    let item = find_matching_item(&normalized_path, &library_items)
+        .ok_or_else(|| format!("No match in AudiobookShelf for {}", path))?;
+    
+    fetch_abs_item_metadata(&client, &config, &item.id).await
+}
+
+async fn fetch_abs_item_metadata(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: &str,
+) -> Result<scanner::BookMetadata, String> {
+    fetch_abs_item_detail(client, config, item_id).await.map(abs_metadata_to_book)
+}
+
+async fn fetch_abs_item_detail(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: &str,
+) -> Result<AbsBookMetadata, String> {
+    let url = format!("{}/api/items/{}?expanded=1", config.abs_base_url, item_id);
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", config.abs_api_token))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch item {}: {}", item_id, abs_status_message(response.status().as_u16())));
+    }
+    
+    let detail: AbsItemDetail = response.json().await.map_err(|e| e.to_string())?;
+    Ok(detail.media.metadata)
+}
+
+/// A library item with its full metadata
+struct ExpandedItem {
+    id: String,
+    path: String,
+    metadata: AbsBookMetadata,
+}
+
+/// Every item in the library with authors and series filled in. The paged
+/// listing is minified (only `authorName`/`seriesName`), so each item is
+/// fetched expanded as well.
+async fn fetch_expanded_library_items(
+    client: &reqwest::Client,
+    config: &config::Config,
+) -> Result<Vec<ExpandedItem>, String> {
+    let mut listed: Vec<AbsLibraryItem> = fetch_abs_library_items(client, config).await?.into_values().collect();
+    listed.sort_by(|a, b| a.path.cmp(&b.path));
+    
+    let fetched: Vec<Result<ExpandedItem, String>> = stream::iter(listed)
+        .map(|item| async move {
+            let metadata = fetch_abs_item_detail(client, config, &item.id).await?;
+            Ok(ExpandedItem { id: item.id, path: item.path, metadata })
+        })
+        .buffered(config.max_workers.max(1))
+        .collect()
+        .await;
+    fetched.into_iter().collect()
+}
+
+fn abs_metadata_to_book(meta: AbsBookMetadata) -> scanner::BookMetadata {
+    let first_series = meta.series.into_iter().next();
+    
+    scanner::BookMetadata {
+        title: meta.title.unwrap_or_default(),
+        subtitle: meta.subtitle,
+        author: meta.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", "),
+        narrator: meta.narrators.first().cloned(),
+        narrators: meta.narrators,
+        series: first_series.as_ref().map(|s| s.name.clone()),
+        sequence: first_series.and_then(|s| s.sequence),
+        genres: meta.genres,
+        publisher: meta.publisher,
+        year: meta.published_year,
+        description: meta.description,
+        isbn: meta.isbn,
+        asin: meta.asin,
+    }
+}
+
+async fn update_abs_item(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: &str,
+    metadata: &scanner::BookMetadata,
+) -> Result<bool, PushError> {
+    patch_abs_media(client, config, item_id, &build_update_payload(metadata)).await
+}
+
+async fn patch_abs_media(
+    client: &reqwest::Client,
+    config: &config::Config,
+    item_id: &str,
+    payload: &Value,
+) -> Result<bool, PushError> {
+    let url = format!("{}/api/items/{}/media", config.abs_base_url, item_id);
+    
+    let response = client
+        .patch(&url)
+        .header("Authorization", format!("Bearer {}", config.abs_api_token))
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| PushError {
+            reason: e.to_string(),
+            status: None,
+        })?;
     
     let status = response.status();
     if !status.is_success() {
         return Err(PushError {
-            reason: format!("Status {}", status),
+            reason: abs_status_message(status.as_u16()),
             status: Some(status.as_u16()),
         });
     }
@@ -689,16 +2251,33 @@ async fn update_abs_item(
     Ok(body.updated)
 }
 
+/// Map an AudiobookShelf HTTP status to a message the user can act on.
+fn abs_status_message(status: u16) -> String {
+    match status {
+        401 => "Authentication failed — your ABS API token may be expired or invalid; regenerate it in ABS settings.".to_string(),
+        403 => "Permission denied — the ABS user for this API token lacks access to this library or action; check the user's permissions in ABS settings.".to_string(),
+        404 => "Not found — check the ABS URL and library ID in settings.".to_string(),
+        429 => "Rate limited by AudiobookShelf — wait a moment and try again.".to_string(),
+        500..=599 => format!("AudiobookShelf server error ({}) — check the ABS server logs.", status),
+        _ => format!("AudiobookShelf responded with {}", status),
+    }
+}
+
 fn build_update_payload(metadata: &scanner::BookMetadata) -> Value {
     let mut map = serde_json::Map::new();
     map.insert("title".to_string(), json!(metadata.title));
+    // ABS derives author sorting from the names; the title one is sent along
+    let title_sort = crate::metadata::sort_title(&metadata.title);
+    if title_sort != metadata.title { map.insert("titleIgnorePrefix".to_string(), json!(title_sort)); }
     
     if let Some(ref s) = metadata.subtitle { map.insert("subtitle".to_string(), json!(s)); }
     if let Some(ref d) = metadata.description { map.insert("description".to_string(), json!(d)); }
     if let Some(ref p) = metadata.publisher { map.insert("publisher".to_string(), json!(p)); }
     if let Some(ref y) = metadata.year { map.insert("publishedYear".to_string(), json!(y)); }
     if let Some(ref i) = metadata.isbn { map.insert("isbn".to_string(), json!(i)); }
-    if let Some(ref n) = metadata.narrator { map.insert("narrators".to_string(), json!([n])); }
+    if let Some(ref a) = metadata.asin { map.insert("asin".to_string(), json!(a)); }
+    let narrators = metadata.narrator_names();
+    if !narrators.is_empty() { map.insert("narrators".to_string(), json!(narrators)); }
     if !metadata.genres.is_empty() { map.insert("genres".to_string(), json!(metadata.genres)); }
     
     let authors: Vec<Value> = metadata.author.split(&[',', '&'][..])
@@ -722,6 +2301,21 @@ fn build_update_payload(metadata: &scanner::BookMetadata) -> Value {
     json!({"metadata": map})
 }
 
+/// Like `build_update_payload`, but explicitly clears fields that were empty in
+/// the snapshot so an undo also removes values the push added.
+fn build_restore_payload(previous: &scanner::BookMetadata) -> Value {
+    let mut payload = build_update_payload(previous);
+    if let Some(map) = payload["metadata"].as_object_mut() {
+        for key in ["subtitle", "description", "publisher", "publishedYear", "isbn", "asin"] {
+            map.entry(key).or_insert(Value::Null);
+        }
+        for key in ["narrators", "genres", "authors", "series"] {
+            map.entry(key).or_insert(json!([]));
+        }
+    }
+    payload
+}
+
 #[tauri::command]
 async fn login_to_audible(email: String, password: String, country_code: String) -> Result<String, String> {
     audible_auth::login_audible(&email, &password, &country_code).map_err(|e| e.to_string())
@@ -731,12 +2325,61 @@ async fn login_to_audible(email: String, password: String, country_code: String)
 async fn check_audible_installed() -> Result<bool, String> {
     audible_auth::check_audible_status().map_err(|e| e.to_string())
 }
+#[tauri::command]
+async fn decrypt_aax_files(file_paths: Vec<String>) -> Result<audible::DecryptReport, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let bytes = audible::get_activation_bytes(&config.audible_cli_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || audible::decrypt_all(&file_paths, |p| audible::decrypt_aax(p, &bytes)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_audible_chapters(
+    file_paths: Vec<String>,
+    asin: String,
+    out_path: Option<String>,
+) -> Result<Vec<chapters::Chapter>, String> {
+    if file_paths.is_empty() {
+        return Err("No files provided".to_string());
+    }
+    
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let audible_chapters = audible::fetch_chapters(&asin, &config.audible_cli_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    let mut files = Vec::new();
+    for path in &file_paths {
+        let duration = chapters::file_duration_seconds(path).map_err(|e| format!("{}: {}", path, e))?;
+        files.push((path.clone(), duration));
+    }
+    
+    let book_chapters = chapters::build_chapters(&files, &audible_chapters);
+    
+    let out_path = out_path.unwrap_or_else(|| {
+        std::path::Path::new(&file_paths[0])
+            .with_file_name("chapters.json")
+            .to_string_lossy()
+            .to_string()
+    });
+    chapters::export_abs_chapters(&book_chapters, &out_path).map_err(|e| e.to_string())?;
+    println!("✅ Exported {} chapters to {}", book_chapters.len(), out_path);
+    
+    Ok(book_chapters)
+}
+
 #[tauri::command]
 async fn get_scan_progress() -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "current": crate::progress::get_current_progress(),
         "total": crate::progress::get_total_files(),
-        "current_file": crate::progress::get_current_file()
+        "current_file": crate::progress::get_current_file(),
+        "estimated_remaining_seconds": crate::progress::get_estimated_remaining_seconds(),
+        "phase": crate::progress::get_current_phase(),
+        "phase_label": crate::progress::get_current_phase().label()
     }))
 }
 fn main() {
@@ -744,26 +2387,950 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             scan_library,
+            recompute_changes,
             write_tags,
             get_config,
             save_config,
+            validate_config,
+            list_profiles,
+            switch_profile,
+            save_profile,
             test_abs_connection,
+            test_openai_key,
+            estimate_scan,
+            clean_backups,
             clear_cache,
+            migrate_cache,
             restart_abs_docker,
             force_abs_rescan,
             clear_abs_cache,
             clear_all_genres,
             normalize_genres,
+            retry_failed_pushes,
+            set_group_field,
+            find_missing_in_abs,
+            normalize_author_order,
+            canonicalize_series,
             push_abs_updates,
+            preview_abs_matches,
+            undo_last_push,
             login_to_audible,
             check_audible_installed,
+            get_abs_item,
+            pull_from_abs,
+            find_duplicates,
+            set_group_type,
+            fetch_cover_preview,
+            preview_merge_prompt,
+            preview_extract_prompt,
             inspect_file_tags,
+            export_library_metadata,
+            library_stats,
+            collect_local_genres,
+            detect_encoding_issues,
+            fix_encoding,
+            import_library_metadata,
+            write_chapters,
+            rewrite_clean,
+            embed_folder_covers,
+            lock_book,
+            strip_app_formatting,
+            unlock_book,
             preview_rename,
             rename_files,
             get_scan_progress,
             cancel_scan,
+            import_audible_chapters,
+            decrypt_aax_files,
 
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_abs_status_message_auth() {
+        assert_eq!(
+            abs_status_message(401),
+            "Authentication failed — your ABS API token may be expired or invalid; regenerate it in ABS settings."
+        );
+        assert!(abs_status_message(403).starts_with("Permission denied"));
+    }
+    
+    #[test]
+    fn test_abs_status_message_other() {
+        assert!(abs_status_message(404).starts_with("Not found"));
+        assert!(abs_status_message(429).starts_with("Rate limited"));
+        assert!(abs_status_message(502).contains("server error (502)"));
+        assert_eq!(abs_status_message(418), "AudiobookShelf responded with 418");
+    }
+    
+    fn mock_config(server: &mockito::Server) -> config::Config {
+        config::Config {
+            abs_base_url: server.url(),
+            abs_api_token: "test-token".to_string(),
+            abs_library_id: "lib1".to_string(),
+            ..config::Config::default()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_normalize_genres_patches_aliased_genres() {
+        let mut server = mockito::Server::new_async().await;
+        let mut config = mock_config(&server);
+        config.genre_aliases.insert("Sci-Fi".to_string(), "Science Fiction".to_string());
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "media": {"metadata": {"title": "Dune", "genres": ["Sci-Fi"]}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        let patch = server.mock("PATCH", "/api/items/li_1/media")
+            .match_body(mockito::Matcher::Json(json!({"metadata": {"genres": ["Science Fiction"]}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        
+        let summary = normalize_abs_genres(&reqwest::Client::new(), &config, true, false).await.unwrap();
+        
+        patch.assert_async().await;
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.items[0].mappings, vec!["Sci-Fi → Science Fiction"]);
+    }
+    
+    #[tokio::test]
+    async fn test_openai_key_check_sends_configured_account_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let config = config::Config {
+            openai_api_key: "sk-test".to_string(),
+            openai_org: "org-123".to_string(),
+            openai_project: "proj_abc".to_string(),
+            ..config::Config::default()
+        };
+        let accepted = server.mock("GET", "/v1/models")
+            .match_header("authorization", "Bearer sk-test")
+            .match_header("OpenAI-Organization", "org-123")
+            .match_header("OpenAI-Project", "proj_abc")
+            .with_status(200)
+            .with_body(json!({"data": [{"id": llm::OPENAI_MODEL}]}).to_string())
+            .create_async()
+            .await;
+        
+        let result = run_test_openai_key(&server.url(), &config).await;
+        accepted.assert_async().await;
+        assert!(result.success);
+        assert!(result.model_available);
+        
+        // A key from another organization is rejected, and reported as such
+        server.reset();
+        server.mock("GET", "/v1/models")
+            .with_status(401)
+            .create_async()
+            .await;
+        let rejected = run_test_openai_key(&server.url(), &config).await;
+        assert!(!rejected.success);
+        assert_eq!(rejected.failure, Some(llm::KeyTestFailure::Unauthorized));
+    }
+    
+    #[tokio::test]
+    async fn test_clear_genres_dry_run_sends_no_writes() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        
+        server.mock("GET", "/api/libraries/lib1/filterdata")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"genres": ["Science Fiction", "Sci-Fi", "Westerns"]}).to_string())
+            .create_async()
+            .await;
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "media": {"metadata": {"title": "Dune", "genres": ["Science Fiction"]}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        let patch = server.mock("PATCH", mockito::Matcher::Any).expect(0).create_async().await;
+        let delete = server.mock("DELETE", mockito::Matcher::Any).expect(0).create_async().await;
+        
+        let summary = clear_unused_abs_genres(&reqwest::Client::new(), &config, true).await.unwrap();
+        
+        patch.assert_async().await;
+        delete.assert_async().await;
+        assert!(summary.dry_run);
+        assert_eq!(summary.deleted, 2);
+        assert_eq!(summary.genres, vec!["Sci-Fi", "Westerns"]);
+        assert_eq!(summary.message, "Would remove 2 unused genres");
+    }
+    
+    #[tokio::test]
+    async fn test_normalize_author_order_patches_reordered_authors() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        
+        // The listing is minified, as a real ABS server returns it
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "path": "/audiobooks/Foundation", "media": {"metadata": {"title": "Foundation", "authorName": "Asimov, Isaac"}}},
+                {"id": "li_2", "path": "/audiobooks/The Iliad", "media": {"metadata": {"title": "The Iliad", "authorName": "Homer"}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        mock_expanded_item(&mut server, "li_1", json!({"title": "Foundation", "authors": [{"id": "a1", "name": "Asimov, Isaac"}]})).await;
+        mock_expanded_item(&mut server, "li_2", json!({"title": "The Iliad", "authors": [{"id": "a2", "name": "Homer"}]})).await;
+        let patch = server.mock("PATCH", "/api/items/li_1/media")
+            .match_body(mockito::Matcher::Json(json!({"metadata": {"authors": [{"id": "new-1", "name": "Isaac Asimov"}]}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        let untouched = server.mock("PATCH", "/api/items/li_2/media")
+            .expect(0)
+            .create_async()
+            .await;
+        
+        let summary = normalize_abs_author_order(&reqwest::Client::new(), &config, metadata::AuthorOrder::FirstLast, false).await.unwrap();
+        
+        patch.assert_async().await;
+        untouched.assert_async().await;
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.changes.len(), 1);
+        assert_eq!(summary.changes[0].from, "Asimov, Isaac");
+        assert_eq!(summary.changes[0].to, "Isaac Asimov");
+    }
+    
+    #[tokio::test]
+    async fn test_canonicalize_series_renames_three_variant_cluster() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "path": "/audiobooks/Leviathan Wakes", "media": {"metadata": {"title": "Leviathan Wakes", "seriesName": "The Expanse #1"}}},
+                {"id": "li_2", "path": "/audiobooks/Caliban's War", "media": {"metadata": {"title": "Caliban's War", "seriesName": "Expanse #2"}}},
+                {"id": "li_3", "path": "/audiobooks/Abaddon's Gate", "media": {"metadata": {"title": "Abaddon's Gate", "seriesName": "Expanse Series #3"}}},
+                {"id": "li_4", "path": "/audiobooks/Dune", "media": {"metadata": {"title": "Dune", "seriesName": "Dune Chronicles #1"}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        for (id, name, sequence) in [("li_1", "The Expanse", "1"), ("li_2", "Expanse", "2"), ("li_3", "Expanse Series", "3"), ("li_4", "Dune Chronicles", "1")] {
+            mock_expanded_item(&mut server, id, json!({"series": [{"id": format!("s_{}", id), "name": name, "sequence": sequence}]})).await;
+        }
+        let renamed_2 = server.mock("PATCH", "/api/items/li_2/media")
+            .match_body(mockito::Matcher::Json(json!({"metadata": {"series": [{"id": "new-1", "name": "The Expanse", "sequence": "2"}]}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        let rejected_3 = server.mock("PATCH", "/api/items/li_3/media")
+            .match_body(mockito::Matcher::Json(json!({"metadata": {"series": [{"id": "new-1", "name": "The Expanse", "sequence": "3"}]}})))
+            .with_status(500)
+            .create_async()
+            .await;
+        let untouched = server.mock("PATCH", mockito::Matcher::Regex(r"^/api/items/li_[14]/media$".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+        
+        let proposal = canonicalize_abs_series(&reqwest::Client::new(), &config, &HashMap::new(), false).await.unwrap();
+        assert_eq!(proposal.updated, 0);
+        assert_eq!(proposal.clusters.len(), 1);
+        assert_eq!(proposal.clusters[0].canonical, "The Expanse");
+        assert_eq!(proposal.clusters[0].variants.len(), 3);
+        
+        let summary = canonicalize_abs_series(&reqwest::Client::new(), &config, &HashMap::new(), true).await.unwrap();
+        
+        renamed_2.assert_async().await;
+        rejected_3.assert_async().await;
+        untouched.assert_async().await;
+        assert!(summary.confirmed);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].path, "/audiobooks/Abaddon's Gate");
+        assert_eq!(summary.failed[0].status, Some(500));
+    }
+    
+    #[test]
+    fn test_abs_metadata_to_book() {
+        let meta: AbsBookMetadata = serde_json::from_value(json!({
+            "title": "Leviathan Wakes",
+            "authors": [{"id": "a1", "name": "James S. A. Corey"}],
+            "narrators": ["Jefferson Mays"],
+            "series": [{"id": "s1", "name": "The Expanse", "sequence": "1"}],
+            "genres": ["Science Fiction"],
+            "publishedYear": "2011"
+        })).unwrap();
+        
+        let book = abs_metadata_to_book(meta);
+        assert_eq!(book.title, "Leviathan Wakes");
+        assert_eq!(book.author, "James S. A. Corey");
+        assert_eq!(book.narrator.as_deref(), Some("Jefferson Mays"));
+        assert_eq!(book.series.as_deref(), Some("The Expanse"));
+        assert_eq!(book.sequence.as_deref(), Some("1"));
+        assert_eq!(book.year.as_deref(), Some("2011"));
+    }
+    
+    #[tokio::test]
+    async fn test_normalize_genres_dry_run_issues_no_patch() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "media": {"metadata": {"title": "Dune", "genres": ["sci-fi"]}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        let patch = server.mock("PATCH", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        
+        let summary = normalize_abs_genres(&reqwest::Client::new(), &config, true, true).await.unwrap();
+        
+        patch.assert_async().await;
+        assert!(summary.dry_run);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.items[0].id, "li_1");
+    }
+    
+    #[tokio::test]
+    async fn test_normalize_genres_dry_run_reports_per_item_diff() {
+        let mut server = mockito::Server::new_async().await;
+        let mut config = mock_config(&server);
+        config.genre_aliases.insert("Sci-Fi".to_string(), "Science Fiction".to_string());
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "media": {"metadata": {"title": "Dune", "genres": ["Sci-Fi"]}}},
+                {"id": "li_2", "media": {"metadata": {"title": "Emma", "genres": ["Romance"]}}}
+            ]}).to_string())
+            .create_async()
+            .await;
+        
+        let summary = normalize_abs_genres(&reqwest::Client::new(), &config, true, true).await.unwrap();
+        
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.skipped, 1);
+        let report = &summary.items[0];
+        assert_eq!(report.id, "li_1");
+        assert_eq!(report.title.as_deref(), Some("Dune"));
+        assert_eq!(report.old_genres, vec!["Sci-Fi"]);
+        assert_eq!(report.new_genres, vec!["Science Fiction"]);
+    }
+    
+    fn push_item(path: &str, title: &str) -> PushItem {
+        PushItem {
+            path: path.to_string(),
+            metadata: scanner::BookMetadata {
+                title: title.to_string(),
+                subtitle: None,
+                author: "Frank Herbert".to_string(),
+                narrator: None,
+                narrators: vec![],
+                series: None,
+                sequence: None,
+                genres: vec![],
+                publisher: None,
+                year: None,
+                description: None,
+                isbn: None,
+                asin: None,
+            },
+        }
+    }
+    
+    async fn mock_expanded_item(server: &mut mockito::Server, id: &str, metadata: Value) {
+        server.mock("GET", format!("/api/items/{}", id).as_str())
+            .match_query(mockito::Matcher::UrlEncoded("expanded".to_string(), "1".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"media": {"metadata": metadata}}).to_string())
+            .create_async()
+            .await;
+    }
+    
+    async fn mock_library_item(server: &mut mockito::Server) {
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [{"id": "li_1", "path": "/audiobooks/Dune"}]}).to_string())
+            .create_async()
+            .await;
+        server.mock("GET", "/api/items/li_1")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"media": {"metadata": {
+                "title": "Old Title",
+                "authors": [{"id": "a1", "name": "Frank Herbert"}]
+            }}}).to_string())
+            .create_async()
+            .await;
+    }
+    
+    #[tokio::test]
+    async fn test_pull_from_abs_writes_abs_metadata_into_files() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-pull");
+        std::fs::create_dir_all(dir.join("Dune")).unwrap();
+        let file = dir.join("Dune").join("dune.flac");
+        test_fixtures::write_flac(&file, 1);
+        let original: HashMap<String, scanner::FieldChange> = [("title", "Dune (Unabridged)"), ("author", "Frank Herbert")].iter()
+            .map(|(k, v)| (k.to_string(), scanner::FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        tags::write_file_tags(file.to_str().unwrap(), &original, false, false, &[], false, false).await.unwrap();
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [{"id": "li_1", "path": dir.join("Dune").to_str().unwrap()}]}).to_string())
+            .create_async()
+            .await;
+        server.mock("GET", "/api/items/li_1")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"media": {"metadata": {
+                "title": "Dune",
+                "authors": [{"id": "a1", "name": "Frank Herbert"}],
+                "series": [{"id": "s1", "name": "Dune Chronicles", "sequence": "1"}]
+            }}}).to_string())
+            .create_async()
+            .await;
+        
+        let paths = vec![file.to_str().unwrap().to_string(), "/elsewhere/Unknown/book.flac".to_string()];
+        let first = run_pull(&reqwest::Client::new(), &config, &paths, false).await.unwrap();
+        let second = run_pull(&reqwest::Client::new(), &config, &paths[..1], false).await.unwrap();
+        let pulled = scanner::read_raw_file(&file);
+        
+        assert_eq!(first.written, 1);
+        assert_eq!(first.unmatched, vec!["/elsewhere/Unknown/book.flac"]);
+        assert!(first.failed.is_empty());
+        assert!(first.warnings.is_empty());
+        assert_eq!(second.written, 0);
+        assert_eq!(second.unchanged, 1);
+        assert_eq!(pulled.tags.title.as_deref(), Some("Dune"));
+        assert_eq!(pulled.tags.series.as_deref(), Some("Dune Chronicles"));
+        assert_eq!(pulled.tags.sequence.as_deref(), Some("1"));
+    }
+    
+    #[tokio::test]
+    async fn test_find_missing_in_abs_reports_unimported_books() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-missing");
+        for book in ["Dune", "Emma", "Hyperion", "Persuasion"] {
+            std::fs::create_dir_all(dir.join(book)).unwrap();
+            test_fixtures::write_flac(&dir.join(book).join("01.flac"), 1);
+        }
+        let persuasion: HashMap<String, scanner::FieldChange> = [("title", "Persuasion"), ("author", "Jane Austen")].iter()
+            .map(|(k, v)| (k.to_string(), scanner::FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        tags::write_file_tags(dir.join("Persuasion").join("01.flac").to_str().unwrap(), &persuasion, false, false, &[], false, false).await.unwrap();
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": [
+                {"id": "li_1", "path": dir.join("Dune").to_str().unwrap()},
+                {"id": "li_2", "path": dir.join("Hyperion").to_str().unwrap()}
+            ]}).to_string())
+            .create_async()
+            .await;
+        // Imported from another mount point, so only the title search finds it
+        server.mock("GET", "/api/libraries/lib1/search")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), "Persuasion".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"book": [{"libraryItem": {
+                "id": "li_3",
+                "path": "/audiobooks/Jane Austen/Persuasion",
+                "media": {"metadata": {"title": "Persuasion", "authors": [{"id": "a2", "name": "Jane Austen"}]}}
+            }}]}).to_string())
+            .create_async()
+            .await;
+        
+        let missing = run_find_missing(&reqwest::Client::new(), &config, dir.to_str().unwrap()).await.unwrap();
+        
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].name, "Emma");
+        assert!(missing[0].files[0].ends_with("01.flac"));
+    }
+    
+    #[tokio::test]
+    async fn test_undo_last_push_restores_previous_metadata() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-push-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        let push = server.mock("PATCH", "/api/items/li_1/media")
+            .match_body(mockito::Matcher::PartialJson(json!({"metadata": {"title": "New Title"}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        let restore = server.mock("PATCH", "/api/items/li_1/media")
+            .match_body(mockito::Matcher::PartialJson(json!({"metadata": {"title": "Old Title", "subtitle": null}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        
+        let client = reqwest::Client::new();
+        let request = PushRequest {
+            items: vec![push_item("/audiobooks/Dune", "New Title")],
+            ..PushRequest::default()
+        };
+        let result = run_push(&client, &config, &request, &history_path, |_| {}).await.unwrap();
+        assert_eq!(result.updated, 1);
+        
+        let undo = undo_push(&client, &config, &history_path).await.unwrap();
+        let history_left = load_push_history(&history_path);
+        
+        push.assert_async().await;
+        restore.assert_async().await;
+        assert_eq!(undo.reverted, vec!["/audiobooks/Dune"]);
+        assert!(undo.failed.is_empty());
+        assert!(history_left.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_push_verify_flags_dropped_series() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-verify-history");
+        let history_path = dir.join("history.json");
+        
+        // The echoed item keeps title and author but has no series
+        mock_library_item(&mut server).await;
+        server.mock("PATCH", "/api/items/li_1/media")
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        
+        let mut item = push_item("/audiobooks/Dune", "Old Title");
+        item.metadata.series = Some("Dune Chronicles".to_string());
+        item.metadata.sequence = Some("1".to_string());
+        let unverified = PushRequest { items: vec![item.clone()], ..PushRequest::default() };
+        let verified = PushRequest { items: vec![item], verify: true, ..PushRequest::default() };
+        
+        let client = reqwest::Client::new();
+        let trusting = run_push(&client, &config, &unverified, &history_path, |_| {}).await.unwrap();
+        let checked = run_push(&client, &config, &verified, &history_path, |_| {}).await.unwrap();
+        let history = load_push_history(&history_path);
+        
+        assert_eq!(trusting.updated, 1);
+        assert!(trusting.failed.is_empty());
+        
+        assert_eq!(checked.updated, 0);
+        assert_eq!(checked.failed.len(), 1);
+        assert_eq!(checked.failed[0].path, "/audiobooks/Dune");
+        assert!(checked.failed[0].reason.contains("series"), "{}", checked.failed[0].reason);
+        assert!(!checked.failed[0].reason.contains("title"));
+        // The diverged update can still be undone
+        assert_eq!(history.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_push_sends_asin() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-asin-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        let patch = server.mock("PATCH", "/api/items/li_1/media")
+            .match_body(mockito::Matcher::PartialJson(json!({"metadata": {"title": "Dune", "asin": "B002V1OF70"}})))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        
+        let mut item = push_item("/audiobooks/Dune", "Dune");
+        item.metadata.asin = Some("B002V1OF70".to_string());
+        let request = PushRequest { items: vec![item], ..PushRequest::default() };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        patch.assert_async().await;
+        assert_eq!(result.updated, 1);
+    }
+    
+    #[test]
+    fn test_payload_sends_every_narrator() {
+        let mut item = push_item("/audiobooks/Wheel", "The Eye of the World");
+        item.metadata.narrator = Some("Kate Reading".to_string());
+        item.metadata.narrators = vec!["Kate Reading".to_string(), "Michael Kramer".to_string()];
+        let payload = build_update_payload(&item.metadata);
+        assert_eq!(payload["metadata"]["narrators"], json!(["Kate Reading", "Michael Kramer"]));
+        
+        // Older callers that only set `narrator` still send it
+        item.metadata.narrators.clear();
+        let payload = build_update_payload(&item.metadata);
+        assert_eq!(payload["metadata"]["narrators"], json!(["Kate Reading"]));
+    }
+    
+    #[test]
+    fn test_payload_sends_title_sort() {
+        let item = push_item("/audiobooks/Hobbit", "The Hobbit");
+        let payload = build_update_payload(&item.metadata);
+        assert_eq!(payload["metadata"]["titleIgnorePrefix"], json!("Hobbit"));
+        
+        let item = push_item("/audiobooks/Dune", "Dune");
+        assert!(build_update_payload(&item.metadata)["metadata"].get("titleIgnorePrefix").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_retry_failed_pushes_only_repushes_transient_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-retry-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        let patch = server.mock("PATCH", "/api/items/li_1/media")
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        
+        let failures = vec![
+            PushFailure { path: "/audiobooks/Dune".to_string(), reason: "server error".to_string(), status: Some(503), kind: FailureKind::Request },
+            PushFailure { path: "/audiobooks/Gone".to_string(), reason: "Not found".to_string(), status: Some(404), kind: FailureKind::Request },
+            PushFailure {
+                path: "/audiobooks/Locked".to_string(),
+                reason: "Could not save undo snapshot: connection reset".to_string(),
+                status: None,
+                kind: FailureKind::Snapshot,
+            },
+        ];
+        let items = vec![
+            push_item("/audiobooks/Dune", "Dune"),
+            push_item("/audiobooks/Gone", "Gone"),
+            push_item("/audiobooks/Locked", "Locked"),
+            push_item("/audiobooks/Fine", "Already pushed"),
+        ];
+        let result = run_retry(&reqwest::Client::new(), &config, failures, items, false, &history_path, std::time::Duration::from_millis(1)).await.unwrap();
+        
+        patch.assert_async().await;
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.failed.len(), 2);
+        assert_eq!(result.failed[0].path, "/audiobooks/Gone");
+        assert_eq!(result.failed[0].status, Some(404));
+        assert_eq!(result.failed[1].kind, FailureKind::Snapshot);
+        assert!(result.unmatched.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_push_dry_run_issues_no_patch() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-dry-run-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        let patch = server.mock("PATCH", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        
+        let request = PushRequest {
+            items: vec![
+                push_item("/audiobooks/Dune", "New Title"),
+                push_item("/audiobooks/Missing", "Other"),
+            ],
+            dry_run: true,
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        patch.assert_async().await;
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.unmatched, vec!["/audiobooks/Missing"]);
+        assert_eq!(result.planned.len(), 1);
+        assert_eq!(result.planned[0].item_id, "li_1");
+        assert_eq!(result.planned[0].payload["metadata"]["title"], "New Title");
+        assert!(!history_path.exists());
+    }
+    
+    #[tokio::test]
+    async fn test_push_creates_collection_and_adds_item() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-collection-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        server.mock("PATCH", "/api/items/li_1/media")
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/api/collections")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"collections": [{"id": "col_other", "name": "Favorites", "libraryId": "lib1"}]}).to_string())
+            .create_async()
+            .await;
+        let create = server.mock("POST", "/api/collections")
+            .match_body(mockito::Matcher::Json(json!({"libraryId": "lib1", "name": "To Read"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "col_1", "name": "To Read", "libraryId": "lib1"}).to_string())
+            .create_async()
+            .await;
+        let add = server.mock("POST", "/api/collections/col_1/book")
+            .match_body(mockito::Matcher::Json(json!({"id": "li_1"})))
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+        
+        let request = PushRequest {
+            items: vec![push_item("/audiobooks/Dune", "New Title")],
+            collection_name: Some("To Read".to_string()),
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        create.assert_async().await;
+        add.assert_async().await;
+        assert_eq!(result.updated, 1);
+        assert!(result.failed.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_push_collection_failure_is_non_fatal() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-collection-fail-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        server.mock("PATCH", "/api/items/li_1/media")
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/api/collections")
+            .with_status(403)
+            .create_async()
+            .await;
+        
+        let request = PushRequest {
+            items: vec![push_item("/audiobooks/Dune", "New Title")],
+            collection_name: Some("To Read".to_string()),
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        assert_eq!(result.updated, 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].status, Some(403));
+        assert!(result.failed[0].reason.starts_with("Metadata updated"));
+    }
+    
+    #[tokio::test]
+    async fn test_push_parallel_updates_tally_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let mut config = mock_config(&server);
+        config.max_workers = 4;
+        let dir = test_fixtures::temp_dir("main-parallel-history");
+        let history_path = dir.join("history.json");
+        
+        let mut results: Vec<Value> = (0..24)
+            .map(|i| json!({"id": format!("li_ok_{}", i), "path": format!("/audiobooks/Book {}", i)}))
+            .collect();
+        results.push(json!({"id": "li_bad", "path": "/audiobooks/Broken"}));
+        
+        server.mock("GET", "/api/libraries/lib1/items")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"results": results}).to_string())
+            .create_async()
+            .await;
+        server.mock("GET", mockito::Matcher::Regex(r"^/api/items/li_[a-z_0-9]+$".to_string()))
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"media": {"metadata": {"title": "Old Title"}}}).to_string())
+            .create_async()
+            .await;
+        let ok = server.mock("PATCH", mockito::Matcher::Regex(r"^/api/items/li_ok_\d+/media$".to_string()))
+            .with_status(200)
+            .with_body(r#"{"updated":true}"#)
+            .expect(24)
+            .create_async()
+            .await;
+        server.mock("PATCH", "/api/items/li_bad/media")
+            .with_status(500)
+            .create_async()
+            .await;
+        
+        let mut items: Vec<PushItem> = (0..24)
+            .map(|i| push_item(&format!("/audiobooks/Book {}", i), "New Title"))
+            .collect();
+        items.insert(10, push_item("/audiobooks/Broken", "New Title"));
+        let request = PushRequest { items, ..PushRequest::default() };
+        
+        let events = std::sync::Mutex::new(Vec::new());
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |p| {
+            events.lock().unwrap().push(p);
+        }).await.unwrap();
+        let history = load_push_history(&history_path);
+        
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 25);
+        let last = events.last().unwrap();
+        assert_eq!((last.processed, last.total, last.updated, last.failed), (25, 25, 24, 1));
+        
+        ok.assert_async().await;
+        assert_eq!(result.updated, 24);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].path, "/audiobooks/Broken");
+        assert_eq!(result.failed[0].status, Some(500));
+        assert_eq!(history[0].items.len(), 24);
+    }
+    
+    #[tokio::test]
+    async fn test_push_reports_nested_file_match() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-match-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        
+        let request = PushRequest {
+            items: vec![push_item("\\audiobooks\\Dune\\Part 01.mp3", "New Title")],
+            dry_run: true,
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        assert_eq!(result.matched.len(), 1);
+        let info = &result.matched[0];
+        assert_eq!(info.path, "\\audiobooks\\Dune\\Part 01.mp3");
+        assert_eq!(info.normalized_path, "/audiobooks/Dune/Part 01.mp3");
+        assert_eq!(info.item_id, "li_1");
+        assert_eq!(info.abs_path, "/audiobooks/Dune");
+        assert_eq!(info.method, MatchMethod::Path);
+    }
+    
+    async fn mock_title_search(server: &mut mockito::Server, title: &str) {
+        server.mock("GET", "/api/libraries/lib1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"book": [{"libraryItem": {
+                "id": "li_9",
+                "path": "/audiobooks/Frank Herbert/Dune",
+                "media": {"metadata": {"title": title, "authors": [{"id": "a1", "name": "Frank Herbert"}]}}
+            }}]}).to_string())
+            .create_async()
+            .await;
+    }
+    
+    #[tokio::test]
+    async fn test_push_falls_back_to_title_match_for_docker_paths() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-fuzzy-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        mock_title_search(&mut server, "Dune").await;
+        
+        let request = PushRequest {
+            items: vec![push_item("/Volumes/NAS/Books/Herbert, Frank - Dune (1965)", "Dune")],
+            dry_run: true,
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        assert!(result.unmatched.is_empty());
+        assert_eq!(result.matched.len(), 1);
+        assert_eq!(result.matched[0].item_id, "li_9");
+        assert_eq!(result.matched[0].method, MatchMethod::Fuzzy);
+        assert_eq!(result.planned[0].item_id, "li_9");
+    }
+    
+    #[tokio::test]
+    async fn test_push_title_match_rejects_low_confidence() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        let dir = test_fixtures::temp_dir("main-fuzzy-reject-history");
+        let history_path = dir.join("history.json");
+        
+        mock_library_item(&mut server).await;
+        mock_title_search(&mut server, "Children of Dune").await;
+        
+        let request = PushRequest {
+            items: vec![push_item("/Volumes/NAS/Books/Herbert, Frank - Dune (1965)", "Dune")],
+            dry_run: true,
+            ..PushRequest::default()
+        };
+        let result = run_push(&reqwest::Client::new(), &config, &request, &history_path, |_| {}).await.unwrap();
+        
+        assert!(result.matched.is_empty());
+        assert_eq!(result.unmatched, vec!["/Volumes/NAS/Books/Herbert, Frank - Dune (1965)"]);
+    }
+    
+    #[tokio::test]
+    async fn test_match_preview_reports_coverage_without_updates() {
+        let mut server = mockito::Server::new_async().await;
+        let config = mock_config(&server);
+        
+        mock_library_item(&mut server).await;
+        mock_title_search(&mut server, "Children of Dune").await;
+        let patch = server.mock("PATCH", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        
+        let items = vec![
+            push_item("/audiobooks/Dune", "Dune"),
+            push_item("/Volumes/NAS/Books/Herbert, Frank - Dune (1965)", "Dune"),
+        ];
+        let (preview, targets) = match_push_items(&reqwest::Client::new(), &config, &items).await.unwrap();
+        
+        patch.assert_async().await;
+        assert_eq!(preview.matched.len(), 1);
+        assert_eq!(preview.matched[0].item_id, "li_1");
+        assert_eq!(preview.matched[0].method, MatchMethod::Path);
+        assert_eq!(preview.unmatched, vec!["/Volumes/NAS/Books/Herbert, Frank - Dune (1965)"]);
+        assert_eq!(targets.len(), 1);
+    }
+}
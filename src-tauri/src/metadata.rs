@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookMetadata {
@@ -53,9 +54,49 @@ struct IndustryId {
     identifier: String,
 }
 
+const GOOGLE_BOOKS_URL: &str = "https://www.googleapis.com";
+
+/// Google Books lookup, restricted to the configured `metadata_language`
+/// when one is set
 pub async fn fetch_from_google_books(
     title: &str,
     author: &str,
+) -> Result<Option<BookMetadata>> {
+    let language = crate::config::load_config().ok()
+        .and_then(|c| c.metadata_language)
+        .filter(|l| !l.trim().is_empty());
+    fetch_from_google_books_at(GOOGLE_BOOKS_URL, title, author, language.as_deref()).await
+}
+
+async fn query_google_books(
+    client: &reqwest::Client,
+    base_url: &str,
+    query: &str,
+    language: Option<&str>,
+) -> Result<GoogleBooksResponse> {
+    let mut url = format!("{}/books/v1/volumes?q={}", base_url, urlencoding::encode(query));
+    if let Some(lang) = language {
+        url.push_str(&format!("&langRestrict={}", urlencoding::encode(lang.trim())));
+    }
+    
+    let response = client.get(&url).send().await?;
+    
+    // An error status says nothing about whether the book exists
+    if !response.status().is_success() {
+        println!("             ❌ API error: {}", response.status());
+        anyhow::bail!("Google Books returned {}", response.status());
+    }
+    
+    Ok(response.json().await?)
+}
+
+/// With a `language`, editions in that language are preferred. If the
+/// restricted query finds nothing, it's retried without the restriction.
+async fn fetch_from_google_books_at(
+    base_url: &str,
+    title: &str,
+    author: &str,
+    language: Option<&str>,
 ) -> Result<Option<BookMetadata>> {
     let clean_title = clean_for_search(title);
     let clean_author = clean_for_search(author);
@@ -64,25 +105,24 @@ pub async fn fetch_from_google_books(
     println!("             Title: '{}' | Author: '{}'", clean_title, clean_author);
     
     let query = format!("intitle:{} inauthor:{}", clean_title, clean_author);
-    let url = format!(
-        "https://www.googleapis.com/books/v1/volumes?q={}",
-        urlencoding::encode(&query)
-    );
     
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()?;
     
-    let response = client.get(&url).send().await?;
-    
-    if !response.status().is_success() {
-        println!("             ❌ API error: {}", response.status());
-        return Ok(None);
+    let mut books = query_google_books(&client, base_url, &query, language).await?;
+    if books.items.is_empty() && language.is_some() {
+        println!("             ⚠️  Nothing in '{}', retrying in any language", language.unwrap_or_default());
+        books = query_google_books(&client, base_url, &query, None).await?;
     }
     
-    let books: GoogleBooksResponse = response.json().await?;
+    let preferred = language.and_then(|lang| {
+        books.items.iter().find(|item| {
+            item.volume_info.language.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(lang.trim()))
+        })
+    });
     
-    if let Some(book) = books.items.first() {
+    if let Some(book) = preferred.or(books.items.first()) {
         let vi = &book.volume_info;
         
         println!("             ✅ Found:");
@@ -121,6 +161,114 @@ pub async fn fetch_from_google_books(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct OpenLibraryResponse {
+    #[serde(default)]
+    docs: Vec<OpenLibraryDoc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryDoc {
+    title: Option<String>,
+    subtitle: Option<String>,
+    #[serde(default)]
+    author_name: Vec<String>,
+    #[serde(default)]
+    publisher: Vec<String>,
+    first_publish_year: Option<u32>,
+    #[serde(default)]
+    isbn: Vec<String>,
+    #[serde(default)]
+    subject: Vec<String>,
+    #[serde(default)]
+    language: Vec<String>,
+}
+
+/// Open Library search, mapped onto the same shape as Google Books results.
+/// Used as an extra source when the usual ones leave gaps.
+pub async fn fetch_from_open_library(
+    title: &str,
+    author: &str,
+) -> Result<Option<BookMetadata>> {
+    let clean_title = clean_for_search(title);
+    let clean_author = clean_for_search(author);
+    
+    println!("          📖 Open Library Query:");
+    println!("             Title: '{}' | Author: '{}'", clean_title, clean_author);
+    
+    let url = format!(
+        "https://openlibrary.org/search.json?title={}&author={}&limit=1",
+        urlencoding::encode(&clean_title),
+        urlencoding::encode(&clean_author)
+    );
+    
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+    
+    let response = client.get(&url).send().await?;
+    
+    if !response.status().is_success() {
+        println!("             ❌ API error: {}", response.status());
+        return Ok(None);
+    }
+    
+    let results: OpenLibraryResponse = response.json().await?;
+    
+    let Some(doc) = results.docs.into_iter().next() else {
+        println!("             ⚠️  No results");
+        return Ok(None);
+    };
+    
+    println!("             ✅ Found: {:?} by {:?}", doc.title, doc.author_name);
+    
+    let isbn = doc.isbn.iter()
+        .find(|i| i.len() == 13)
+        .or(doc.isbn.first())
+        .cloned();
+    
+    Ok(Some(BookMetadata {
+        title: doc.title,
+        subtitle: doc.subtitle,
+        authors: doc.author_name,
+        narrator: None,
+        series: None,
+        sequence: None,
+        // Subjects are free-form and numerous; the merge prompt picks from them
+        genres: doc.subject.into_iter().take(5).collect(),
+        publisher: doc.publisher.into_iter().next(),
+        publish_date: doc.first_publish_year.map(|y| y.to_string()),
+        description: None,
+        isbn,
+        language: doc.language.into_iter().next(),
+    }))
+}
+
+/// Fill the gaps in `primary` from `extra`, keeping everything `primary` has
+pub fn fill_missing(primary: Option<BookMetadata>, extra: BookMetadata) -> BookMetadata {
+    let Some(mut merged) = primary else {
+        return extra;
+    };
+    
+    merged.title = merged.title.or(extra.title);
+    merged.subtitle = merged.subtitle.or(extra.subtitle);
+    if merged.authors.is_empty() {
+        merged.authors = extra.authors;
+    }
+    merged.narrator = merged.narrator.or(extra.narrator);
+    merged.series = merged.series.or(extra.series);
+    merged.sequence = merged.sequence.or(extra.sequence);
+    if merged.genres.is_empty() {
+        merged.genres = extra.genres;
+    }
+    merged.publisher = merged.publisher.or(extra.publisher);
+    merged.publish_date = merged.publish_date.or(extra.publish_date);
+    merged.description = merged.description.or(extra.description);
+    merged.isbn = merged.isbn.or(extra.isbn);
+    merged.language = merged.language.or(extra.language);
+    merged
+}
+
 fn clean_for_search(input: &str) -> String {
     let mut cleaned = input.to_string();
     
@@ -196,3 +344,340 @@ pub fn extract_narrator_from_comment(comment: &str) -> Option<String> {
     
     None
 }
+
+/// Lowercase and strip punctuation so titles compare on words only.
+pub fn normalize_for_match(input: &str) -> String {
+    input
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalized Levenshtein similarity in the range 0.0..=1.0.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = normalize_for_match(a).chars().collect();
+    let b: Vec<char> = normalize_for_match(b).chars().collect();
+    
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    
+    let distance = prev[b.len()];
+    1.0 - distance as f64 / a.len().max(b.len()) as f64
+}
+
+/// How author names are written: "Isaac Asimov" or "Asimov, Isaac"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorOrder {
+    FirstLast,
+    LastFirst,
+}
+
+impl AuthorOrder {
+    pub fn parse(style: &str) -> Result<Self> {
+        match style.trim().to_lowercase().as_str() {
+            "first_last" | "first last" => Ok(AuthorOrder::FirstLast),
+            "last_first" | "last, first" => Ok(AuthorOrder::LastFirst),
+            other => anyhow::bail!("Unknown author order '{}' (expected first_last or last_first)", other),
+        }
+    }
+}
+
+const NAME_SUFFIXES: &[&str] = &["jr", "jr.", "sr", "sr.", "ii", "iii", "iv", "phd", "ph.d.", "md", "m.d."];
+// Lowercase words that belong to the surname ("da Vinci", "Le Guin")
+const SURNAME_PARTICLES: &[&str] = &["van", "von", "de", "da", "del", "della", "der", "di", "du", "la", "le", "st.", "bin", "ibn"];
+
+fn is_name_suffix(word: &str) -> bool {
+    NAME_SUFFIXES.contains(&word.trim().to_lowercase().as_str())
+}
+
+/// Split "First Last" words into (first, last), pulling surname particles
+/// into the last name. None for mononyms.
+fn split_first_last(words: &[&str]) -> Option<(String, String)> {
+    if words.len() < 2 {
+        return None;
+    }
+    let mut start = words.len() - 1;
+    while start > 1 && SURNAME_PARTICLES.contains(&words[start - 1].to_lowercase().as_str()) {
+        start -= 1;
+    }
+    Some((words[..start].join(" "), words[start..].join(" ")))
+}
+
+/// Rewrite one author name in the given order. Returns None when the name
+/// is already in that order or can't be split safely - mononyms ("Plato"),
+/// groups ("Various Authors & Friends") and anything with digits.
+pub fn reorder_author(name: &str, order: AuthorOrder) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name.contains('&') || name.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    
+    let parts: Vec<&str> = name.split(',').map(str::trim).collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return None;
+    }
+    let (first, last, suffix) = match parts.as_slice() {
+        // "Martin Luther King Jr." / "Martin Luther King, Jr."
+        [whole] | [whole, _] if parts.len() == 1 || is_name_suffix(parts[1]) => {
+            let mut words: Vec<&str> = whole.split_whitespace().collect();
+            let suffix = match parts.get(1) {
+                Some(s) => Some(s.to_string()),
+                None if words.last().is_some_and(|w| is_name_suffix(w)) => words.pop().map(str::to_string),
+                None => None,
+            };
+            let (first, last) = split_first_last(&words)?;
+            (first, last, suffix)
+        }
+        // "Asimov, Isaac"
+        [last, first] => (first.to_string(), last.to_string(), None),
+        // "King, Martin Luther, Jr."
+        [last, first, suffix] if is_name_suffix(suffix) => (first.to_string(), last.to_string(), Some(suffix.to_string())),
+        _ => return None,
+    };
+    
+    let reordered = match (order, suffix) {
+        (AuthorOrder::FirstLast, Some(s)) => format!("{} {} {}", first, last, s),
+        (AuthorOrder::FirstLast, None) => format!("{} {}", first, last),
+        (AuthorOrder::LastFirst, Some(s)) => format!("{}, {}, {}", last, first, s),
+        (AuthorOrder::LastFirst, None) => format!("{}, {}", last, first),
+    };
+    (reordered != name).then_some(reordered)
+}
+
+// Leading articles ABS ignores when alphabetizing titles
+const SORT_ARTICLES: &[&str] = &["the ", "a ", "an "];
+
+/// Title as it should sort: "The Hobbit" → "Hobbit". A title that is only
+/// an article is left as it is.
+pub fn sort_title(title: &str) -> String {
+    let title = title.trim();
+    let lower = title.to_lowercase();
+    SORT_ARTICLES.iter()
+        .find(|article| lower.starts_with(*article))
+        .map(|article| title[article.len()..].trim_start())
+        .filter(|rest| !rest.is_empty())
+        .unwrap_or(title)
+        .to_string()
+}
+
+/// Author as it should sort: "Last, First", or the name unchanged when it
+/// can't be split safely (see `reorder_author`)
+pub fn sort_author(author: &str) -> String {
+    reorder_author(author, AuthorOrder::LastFirst).unwrap_or_else(|| author.trim().to_string())
+}
+
+const SERIES_SUFFIXES: &[&str] = &[" series", " saga", " trilogy"];
+
+/// Series names whose keys are at least this similar are the same series
+pub const SERIES_MATCH_THRESHOLD: f64 = 0.85;
+
+/// What identifies a series regardless of spelling: "The Expanse Series" → "expanse"
+pub fn series_key(name: &str) -> String {
+    let key = normalize_for_match(&sort_title(name));
+    SERIES_SUFFIXES.iter()
+        .find_map(|suffix| key.strip_suffix(suffix).filter(|rest| !rest.is_empty()))
+        .unwrap_or(key.as_str())
+        .to_string()
+}
+
+/// Spellings of one series and the name they should all become
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesCluster {
+    pub canonical: String,
+    /// Each spelling with the number of items using it
+    pub variants: Vec<(String, usize)>,
+}
+
+/// Group series spellings (with item counts) into clusters of the same
+/// series. A `mapping` entry (spelling → canonical, case-insensitive) puts
+/// that spelling under the given name instead of clustering it. Only
+/// clusters with something to rename are returned.
+pub fn cluster_series(counts: &[(String, usize)], mapping: &HashMap<String, String>) -> Vec<SeriesCluster> {
+    let lookup: HashMap<String, &String> = mapping.iter()
+        .map(|(variant, canonical)| (variant.trim().to_lowercase(), canonical))
+        .collect();
+    
+    let mut sorted = counts.to_vec();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    
+    // (key, canonical from the mapping, variants)
+    let mut clusters: Vec<(String, Option<String>, Vec<(String, usize)>)> = Vec::new();
+    for (name, count) in sorted {
+        let position = match lookup.get(&name.trim().to_lowercase()) {
+            Some(canonical) => clusters.iter()
+                .position(|(_, mapped, _)| mapped.as_deref().is_some_and(|m| m.eq_ignore_ascii_case(canonical.trim())))
+                .unwrap_or_else(|| {
+                    clusters.push((series_key(canonical), Some(canonical.trim().to_string()), Vec::new()));
+                    clusters.len() - 1
+                }),
+            None => {
+                let key = series_key(&name);
+                clusters.iter()
+                    .position(|(cluster_key, _, _)| similarity(cluster_key, &key) >= SERIES_MATCH_THRESHOLD)
+                    .unwrap_or_else(|| {
+                        clusters.push((key, None, Vec::new()));
+                        clusters.len() - 1
+                    })
+            }
+        };
+        clusters[position].2.push((name, count));
+    }
+    
+    clusters.into_iter()
+        .map(|(_, mapped, variants)| SeriesCluster {
+            canonical: mapped.unwrap_or_else(|| canonical_series_name(&variants)),
+            variants,
+        })
+        .filter(|cluster| cluster.variants.iter().any(|(name, _)| *name != cluster.canonical))
+        .collect()
+}
+
+/// The most used spelling; on a tie, one without a "Series" suffix, then the
+/// longest (so "The Expanse" beats "Expanse")
+fn canonical_series_name(variants: &[(String, usize)]) -> String {
+    variants.iter()
+        .max_by(|a, b| {
+            let plain = |name: &str| !SERIES_SUFFIXES.iter().any(|s| name.to_lowercase().ends_with(s));
+            a.1.cmp(&b.1)
+                .then_with(|| plain(&a.0).cmp(&plain(&b.0)))
+                .then_with(|| a.0.len().cmp(&b.0.len()))
+                .then_with(|| b.0.cmp(&a.0))
+        })
+        .map(|(name, _)| name.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_sort_names_strip_articles_and_invert_authors() {
+        assert_eq!(sort_title("The Hobbit"), "Hobbit");
+        assert_eq!(sort_title("A Game of Thrones"), "Game of Thrones");
+        assert_eq!(sort_title("An Echo of Things to Come"), "Echo of Things to Come");
+        assert_eq!(sort_title("Theory of Everything"), "Theory of Everything");
+        assert_eq!(sort_title("The"), "The");
+        assert_eq!(sort_author("J.R.R. Tolkien"), "Tolkien, J.R.R.");
+        assert_eq!(sort_author("Tolkien, J.R.R."), "Tolkien, J.R.R.");
+        assert_eq!(sort_author("Plato"), "Plato");
+    }
+    
+    #[test]
+    fn test_cluster_series_mapping_overrides_clustering() {
+        let counts = vec![
+            ("The Expanse".to_string(), 2),
+            ("Expanse".to_string(), 1),
+            ("Dune Chronicles".to_string(), 3),
+            ("Dune".to_string(), 1),
+            ("Discworld".to_string(), 5),
+        ];
+        let mapping = HashMap::from([("dune chronicles".to_string(), "Dune".to_string())]);
+        
+        let clusters = cluster_series(&counts, &mapping);
+        
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].canonical, "Dune");
+        assert_eq!(clusters[0].variants, vec![("Dune Chronicles".to_string(), 3), ("Dune".to_string(), 1)]);
+        assert_eq!(clusters[1].canonical, "The Expanse");
+        assert_eq!(clusters[1].variants.len(), 2);
+    }
+    
+    #[test]
+    fn test_reorder_author_both_ways() {
+        assert_eq!(reorder_author("Asimov, Isaac", AuthorOrder::FirstLast).as_deref(), Some("Isaac Asimov"));
+        assert_eq!(reorder_author("Isaac Asimov", AuthorOrder::LastFirst).as_deref(), Some("Asimov, Isaac"));
+        assert_eq!(reorder_author("Ursula K. Le Guin", AuthorOrder::LastFirst).as_deref(), Some("Le Guin, Ursula K."));
+        assert_eq!(reorder_author("Martin Luther King Jr.", AuthorOrder::LastFirst).as_deref(), Some("King, Martin Luther, Jr."));
+        assert_eq!(reorder_author("King, Martin Luther, Jr.", AuthorOrder::FirstLast).as_deref(), Some("Martin Luther King Jr."));
+        assert_eq!(reorder_author("Frank Herbert, III", AuthorOrder::LastFirst).as_deref(), Some("Herbert, Frank, III"));
+    }
+    
+    #[test]
+    fn test_reorder_author_leaves_unsafe_names_alone() {
+        assert_eq!(reorder_author("Isaac Asimov", AuthorOrder::FirstLast), None);
+        assert_eq!(reorder_author("Asimov, Isaac", AuthorOrder::LastFirst), None);
+        assert_eq!(reorder_author("Plato", AuthorOrder::LastFirst), None);
+        assert_eq!(reorder_author("Homer, Jr.", AuthorOrder::LastFirst), None);
+        assert_eq!(reorder_author("Homer Jr.", AuthorOrder::LastFirst), None);
+        assert_eq!(reorder_author("Douglas Preston & Lincoln Child", AuthorOrder::LastFirst), None);
+        assert_eq!(reorder_author("Studio 42", AuthorOrder::LastFirst), None);
+    }
+    
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("The Hobbit", "the hobbit!"), 1.0);
+        assert_eq!(similarity("", "Dune"), 0.0);
+        assert!(similarity("The Way of Kings", "The Way of Shadows") < 0.8);
+    }
+    
+    fn volume(title: &str, language: &str) -> serde_json::Value {
+        serde_json::json!({"volumeInfo": {"title": title, "authors": ["Cornelia Funke"], "language": language}})
+    }
+    
+    #[tokio::test]
+    async fn test_language_restricted_and_preferred() {
+        let mut server = mockito::Server::new_async().await;
+        let restricted = server.mock("GET", "/books/v1/volumes")
+            .match_query(mockito::Matcher::UrlEncoded("langRestrict".to_string(), "de".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"totalItems": 2, "items": [
+                volume("Inkheart", "en"),
+                volume("Tintenherz", "de"),
+            ]}).to_string())
+            .create_async()
+            .await;
+        
+        let found = fetch_from_google_books_at(&server.url(), "Tintenherz", "Cornelia Funke", Some("de")).await.unwrap().unwrap();
+        
+        restricted.assert_async().await;
+        assert_eq!(found.title.as_deref(), Some("Tintenherz"));
+        assert_eq!(found.language.as_deref(), Some("de"));
+    }
+    
+    #[tokio::test]
+    async fn test_language_falls_back_to_unrestricted() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/books/v1/volumes")
+            .match_query(mockito::Matcher::UrlEncoded("langRestrict".to_string(), "fr".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"totalItems": 0}"#)
+            .create_async()
+            .await;
+        let unrestricted = server.mock("GET", "/books/v1/volumes")
+            .match_query(mockito::Matcher::Regex("^q=[^&]*$".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"totalItems": 1, "items": [volume("Inkheart", "en")]}).to_string())
+            .create_async()
+            .await;
+        
+        let found = fetch_from_google_books_at(&server.url(), "Inkheart", "Cornelia Funke", Some("fr")).await.unwrap().unwrap();
+        
+        unrestricted.assert_async().await;
+        assert_eq!(found.title.as_deref(), Some("Inkheart"));
+    }
+}
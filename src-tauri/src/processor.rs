@@ -25,6 +25,7 @@ pub async fn process_metadata(
     raw_comment: Option<&str>,
     use_google_books: bool,
     api_key: Option<&str>,
+    config: Option<&crate::config::Config>,
 ) -> Result<ProcessedMetadata> {
     println!("          🔄 Processing metadata...");
     
@@ -82,7 +83,8 @@ pub async fn process_metadata(
             series.as_deref(),
             sequence.as_deref(),
             google_data.as_ref(),
-            key
+            key,
+            config
         ).await?
     } else {
         ProcessedMetadata {
@@ -193,6 +195,7 @@ async fn enhance_with_ai(
     sequence: Option<&str>,
     google_data: Option<&BookMetadata>,
     api_key: &str,
+    config: Option<&crate::config::Config>,
 ) -> Result<ProcessedMetadata> {
     // Build context for AI
     let mut context = format!("Book Title: {}\nAuthor: {}", title, author);
@@ -212,7 +215,7 @@ async fn enhance_with_ai(
     if let Some(d) = description {
         // Limit description length in prompt
         let desc_preview = if d.len() > 500 {
-            format!("{}...", &d[..500])
+            format!("{}...", crate::util::truncate_utf8(d, 500))
         } else {
             d.to_string()
         };
@@ -253,13 +256,13 @@ JSON FORMAT:
     
     println!("          🤖 Calling GPT-5-nano for metadata enhancement...");
     
+    crate::llm::throttle_openai(config).await?;
     let client = reqwest::Client::new();
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
+    let response = crate::llm::openai_account_headers(client.post("https://api.openai.com/v1/chat/completions"), config)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&serde_json::json!({
-            "model": "gpt-5-nano",
+            "model": crate::llm::OPENAI_MODEL,
             "messages": [
                 {
                     "role": "system",
@@ -1,12 +1,67 @@
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+// Weight given to the newest per-group duration in the ETA moving average
+const ETA_SMOOTHING: f64 = 0.2;
+
+/// Which step of the pipeline the current book is in.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanPhase {
+    #[default]
+    Idle,
+    CollectingFiles,
+    ExtractingInfo,
+    QueryingAudible,
+    QueryingGoogle,
+    Merging,
+    Writing,
+    Complete,
+}
+
+impl ScanPhase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanPhase::Idle => "Idle",
+            ScanPhase::CollectingFiles => "Collecting files…",
+            ScanPhase::ExtractingInfo => "Extracting book info with GPT…",
+            ScanPhase::QueryingAudible => "Querying Audible…",
+            ScanPhase::QueryingGoogle => "Querying Google Books…",
+            ScanPhase::Merging => "Merging with GPT…",
+            ScanPhase::Writing => "Writing tags…",
+            ScanPhase::Complete => "Complete",
+        }
+    }
+}
+
+/// Payload of the `push-progress` event, emitted once per ABS item processed
+/// by push_abs_updates.
+#[derive(Debug, Clone, Serialize)]
+pub struct PushProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub current_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ScanProgress {
     pub current: usize,
     pub total: usize,
     pub current_file: String,
+    pub phase: ScanPhase,
+    pub cached_hits: usize,
+    pub files_per_second: f64,
+    pub estimated_remaining_seconds: u64,
+    #[serde(skip)]
+    smoothed_seconds_per_group: Option<f64>,
+    #[serde(skip)]
+    last_update: Option<Instant>,
+    #[serde(skip)]
+    started_at: Option<Instant>,
 }
 
 impl ScanProgress {
@@ -15,39 +70,99 @@ impl ScanProgress {
             current: 0,
             total,
             current_file: String::new(),
+            phase: ScanPhase::Idle,
+            cached_hits: 0,
+            files_per_second: 0.0,
+            estimated_remaining_seconds: 0,
+            smoothed_seconds_per_group: None,
+            last_update: None,
+            started_at: None,
         }
     }
     
-    pub fn update(&mut self, processed: usize, current_file: &str, _start_time: Instant, _completed: bool) {
-        self.current = processed;
+    /// Reset the counters and start the clock for a scan of `total`
+    fn start(&mut self, total: usize, now: Instant) {
+        self.total = total;
+        self.current = 0;
+        self.cached_hits = 0;
+        self.files_per_second = 0.0;
+        self.estimated_remaining_seconds = 0;
+        self.smoothed_seconds_per_group = None;
+        self.last_update = Some(now);
+        self.started_at = Some(now);
+    }
+    
+    /// Count the next group, folding the time since the previous one into the ETA
+    fn advance(&mut self, current_file: &str, now: Instant) {
+        self.current += 1;
         self.current_file = current_file.to_string();
         
-        // Update the global progress state too
-        if let Ok(mut progress) = PROGRESS.lock() {
-            progress.current = processed;
-            progress.current_file = current_file.to_string();
+        let since_last = now.duration_since(self.last_update.unwrap_or(now)).as_secs_f64();
+        self.last_update = Some(now);
+        self.refresh_rate(now);
+        self.record_group_duration(since_last);
+    }
+    
+    /// The current group was served from the cache; it's already counted
+    fn cache_hit(&mut self, current_file: &str, now: Instant) {
+        self.cached_hits += 1;
+        self.current_file = current_file.to_string();
+        self.refresh_rate(now);
+        self.refresh_eta();
+    }
+    
+    // Raw overall rate stays as-is; the ETA uses the smoothed per-group time
+    fn refresh_rate(&mut self, now: Instant) {
+        let elapsed = self.started_at.map(|s| now.duration_since(s).as_secs_f64()).unwrap_or(0.0);
+        if elapsed > 0.0 {
+            self.files_per_second = self.current as f64 / elapsed;
+        }
+    }
+    
+    /// Fold one group's duration into the moving average and refresh the ETA.
+    fn record_group_duration(&mut self, seconds: f64) {
+        let smoothed = match self.smoothed_seconds_per_group {
+            Some(prev) => ETA_SMOOTHING * seconds + (1.0 - ETA_SMOOTHING) * prev,
+            None => seconds,
+        };
+        self.smoothed_seconds_per_group = Some(smoothed);
+        self.refresh_eta();
+    }
+    
+    fn refresh_eta(&mut self) {
+        if let Some(smoothed) = self.smoothed_seconds_per_group {
+            let remaining = self.total.saturating_sub(self.current) as f64;
+            self.estimated_remaining_seconds = (remaining * smoothed).round() as u64;
         }
     }
 }
 
 lazy_static! {
-    static ref PROGRESS: Arc<Mutex<ScanProgress>> = Arc::new(Mutex::new(ScanProgress {
-        current: 0,
-        total: 0,
-        current_file: String::new(),
-    }));
+    static ref PROGRESS: Arc<Mutex<ScanProgress>> = Arc::new(Mutex::new(ScanProgress::new(0)));
 }
 
 pub fn set_total_files(total: usize) {
     if let Ok(mut progress) = PROGRESS.lock() {
-        progress.total = total;
-        progress.current = 0;
+        progress.start(total, Instant::now());
     }
 }
 
 pub fn increment_progress(current_file: &str) {
     if let Ok(mut progress) = PROGRESS.lock() {
-        progress.current += 1;
+        progress.advance(current_file, Instant::now());
+    }
+}
+
+/// Count a group served from MetadataCache or the already-processed path.
+pub fn record_cache_hit(current_file: &str) {
+    if let Ok(mut progress) = PROGRESS.lock() {
+        progress.cache_hit(current_file, Instant::now());
+    }
+}
+
+pub fn set_phase(phase: ScanPhase, current_file: &str) {
+    if let Ok(mut progress) = PROGRESS.lock() {
+        progress.phase = phase;
         progress.current_file = current_file.to_string();
     }
 }
@@ -64,10 +179,77 @@ pub fn get_current_file() -> String {
     PROGRESS.lock().map(|p| p.current_file.clone()).unwrap_or_default()
 }
 
+pub fn get_cached_hits() -> usize {
+    PROGRESS.lock().map(|p| p.cached_hits).unwrap_or(0)
+}
+
+pub fn get_estimated_remaining_seconds() -> u64 {
+    PROGRESS.lock().map(|p| p.estimated_remaining_seconds).unwrap_or(0)
+}
+
+pub fn get_current_phase() -> ScanPhase {
+    PROGRESS.lock().map(|p| p.phase).unwrap_or_default()
+}
+
 pub fn reset_progress() {
     if let Ok(mut progress) = PROGRESS.lock() {
         progress.current = 0;
         progress.total = 0;
         progress.current_file.clear();
+        progress.phase = ScanPhase::Idle;
+        progress.cached_hits = 0;
+        progress.files_per_second = 0.0;
+        progress.estimated_remaining_seconds = 0;
+        progress.smoothed_seconds_per_group = None;
+        progress.last_update = None;
+        progress.started_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_progress_steps_tally_cache_hits_and_estimate() {
+        use std::time::Duration;
+        
+        // The same steps increment_progress and record_cache_hit take, on a
+        // private value so parallel scans in other tests can't interfere
+        let mut progress = ScanProgress::new(0);
+        let start = Instant::now();
+        progress.start(4, start);
+        
+        progress.advance("Book A", start + Duration::from_secs(2));
+        progress.cache_hit("Book A", start + Duration::from_secs(2));
+        progress.advance("Book B", start + Duration::from_secs(4));
+        progress.advance("Book C", start + Duration::from_secs(6));
+        progress.cache_hit("Book C", start + Duration::from_secs(6));
+        
+        assert_eq!(progress.current, 3);
+        assert_eq!(progress.cached_hits, 2);
+        assert_eq!(progress.current_file, "Book C");
+        assert_eq!(progress.files_per_second, 0.5);
+        // Two seconds per group, one group left
+        assert_eq!(progress.estimated_remaining_seconds, 2);
+    }
+    
+    #[test]
+    fn test_eta_smoothing_limits_swings() {
+        let mut progress = ScanProgress::new(20);
+        let mut etas = Vec::new();
+        
+        // A burst of fast cache hits followed by slow GPT merges
+        for i in 0..20 {
+            progress.current = i + 1;
+            progress.record_group_duration(if i < 10 { 1.0 } else { 5.0 });
+            etas.push(progress.estimated_remaining_seconds as f64);
+        }
+        
+        for pair in etas[..19].windows(2) {
+            if pair[0] > 0.0 {
+                assert!(pair[1] / pair[0] < 2.0, "ETA jumped from {} to {}", pair[0], pair[1]);
+            }
+        }
     }
 }
\ No newline at end of file
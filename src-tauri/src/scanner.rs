@@ -6,7 +6,9 @@ use anyhow::Result;
 use std::time::Instant;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Semaphore;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use crate::llm::GptError;
 
 static CANCELLATION_FLAG: AtomicBool = AtomicBool::new(false);
 
@@ -17,25 +19,122 @@ pub fn set_cancellation_flag(cancelled: bool) {
 pub fn is_cancelled() -> bool {
     CANCELLATION_FLAG.load(Ordering::Relaxed)
 }
+
+pub const DRM_STATUS: &str = "DRM-protected, requires activation bytes";
+pub const LOCKED_STATUS: &str = "locked";
+pub const READ_ONLY_STATUS: &str = "read-only, format can't be written";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawFileData {
     pub id: String,
     pub path: String,
     pub filename: String,
     pub tags: FileTags,
+    /// Audible .aax/.aaxc files lofty can't read until decrypted
+    #[serde(default)]
+    pub drm_protected: bool,
+    #[serde(default)]
+    pub duration_secs: Option<f64>,
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// Set when the file looks truncated; the file is still scanned
+    #[serde(default)]
+    pub warning: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A file flagged during collection, e.g. a possibly truncated download
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanWarning {
+    pub path: String,
+    pub filename: String,
+    pub message: String,
+}
+
+/// A path the walk couldn't read; the rest of the scan carries on without it
+#[derive(Debug, Clone, Serialize)]
+pub struct PathError {
+    pub path: String,
+    pub error: String,
+}
+
+// Audiobooks are typically 32-320 kbps; far outside that suggests a bad file
+const MIN_PLAUSIBLE_BYTES_PER_SEC: f64 = 1_000.0;
+const MAX_PLAUSIBLE_BYTES_PER_SEC: f64 = 100_000.0;
+// Lossless runs much higher: CD audio is ~176 KB/s, 24-bit/192 kHz ~1.15 MB/s
+const MAX_PLAUSIBLE_LOSSLESS_BYTES_PER_SEC: f64 = 1_600_000.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileTags {
     pub title: Option<String>,
     pub artist: Option<String>,
     pub album: Option<String>,
     pub album_artist: Option<String>,
     pub composer: Option<String>,
+    /// The narrator custom field, or the composer where that's missing.
+    /// Co-narrators are joined with `NARRATOR_SEPARATOR`.
+    #[serde(default)]
+    pub narrator: Option<String>,
     pub genre: Option<String>,
     pub year: Option<String>,
     pub track: Option<String>,
     pub comment: Option<String>,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub series: Option<String>,
+    #[serde(default)]
+    pub sequence: Option<String>,
+    #[serde(default)]
+    pub isbn: Option<String>,
+    #[serde(default)]
+    pub asin: Option<String>,
+    /// Series and number from the movement frames (ID3 MVNM/MVIN, MP4
+    /// ©mvn/©mvi), only used in place of SERIES when movement frames are on
+    #[serde(default)]
+    pub movement: Option<String>,
+    #[serde(default)]
+    pub movement_number: Option<String>,
+    #[serde(default)]
+    pub title_sort: Option<String>,
+    #[serde(default)]
+    pub author_sort: Option<String>,
+    #[serde(default)]
+    pub locked: bool,
+    /// Read by the ffprobe fallback; nothing can be written back
+    #[serde(default)]
+    pub read_only: bool,
+    /// The file carries embedded cover art
+    #[serde(default)]
+    pub has_cover: bool,
+}
+
+impl FileTags {
+    /// Movement frames are set deliberately by tagging tools, so with
+    /// movement frames on they win over SERIES/SERIES-PART
+    pub fn prefer_movement_series(&mut self) {
+        if let Some(movement) = &self.movement {
+            self.series = Some(movement.clone());
+        }
+        if let Some(number) = &self.movement_number {
+            self.sequence = Some(number.clone());
+        }
+    }
+    
+    /// Series taken from the movement frames by `prefer_movement_series`
+    pub fn series_from_movement(&self) -> bool {
+        self.movement.is_some() && self.series == self.movement
+    }
+}
+
+/// User-confirmed title/author for a folder, trusted over GPT extraction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookOverride {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +145,262 @@ pub struct BookGroup {
     pub files: Vec<AudioFile>,
     pub metadata: BookMetadata,
     pub total_changes: usize,
+    /// 0-100 score from validate_metadata_quality
+    #[serde(default)]
+    pub quality_score: u32,
+    /// Score fell below the configured review threshold
+    #[serde(default)]
+    pub needs_review: bool,
+    /// Files that look like they belong to a different book
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Cover image next to the audio, for groups with no embedded art
+    #[serde(default)]
+    pub cover_path: Option<String>,
+}
+
+/// Counts-only view of a scan, for libraries too large to ship every diff
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanSummary {
+    pub total_groups: usize,
+    pub changed_groups: usize,
+    pub total_files: usize,
+    pub total_changes: usize,
+    pub needs_review: usize,
+}
+
+pub fn summarize_groups(groups: &[BookGroup]) -> ScanSummary {
+    groups.iter().fold(ScanSummary::default(), |mut summary, group| {
+        summary.total_groups += 1;
+        summary.total_files += group.files.len();
+        summary.total_changes += group.total_changes;
+        if group.total_changes > 0 {
+            summary.changed_groups += 1;
+        }
+        if group.needs_review {
+            summary.needs_review += 1;
+        }
+        summary
+    })
+}
+
+/// Drop groups with nothing to write, returning the rest and how many were
+/// dropped. Groups with a DRM, locked or read-only file stay so they're still reported.
+pub fn changed_groups_only(groups: Vec<BookGroup>) -> (Vec<BookGroup>, usize) {
+    let total = groups.len();
+    let changed: Vec<BookGroup> = groups.into_iter()
+        .filter(|g| g.total_changes > 0 || g.files.iter().any(has_special_status))
+        .collect();
+    let omitted = total - changed.len();
+    (changed, omitted)
+}
+
+/// Most recent scan, kept so groups can be re-split without rescanning
+static LAST_SCAN: Lazy<Mutex<Vec<BookGroup>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn remember_scan(groups: &[BookGroup]) {
+    *LAST_SCAN.lock().unwrap() = groups.to_vec();
+}
+
+/// Force a group type on a group of the held scan and return the replacement group(s)
+pub fn set_group_type(group_id: &str, group_type: GroupType, templates: &CommentTemplates) -> Result<Vec<BookGroup>> {
+    let mut groups = LAST_SCAN.lock().unwrap();
+    if groups.is_empty() {
+        anyhow::bail!("No scan result to regroup - scan a library first");
+    }
+    regroup(&mut groups, group_id, group_type, templates)
+}
+
+/// Series splits a multi-file group into one book per file; Chapters merges
+/// the books of an earlier split back into one. File changes are re-diffed
+/// against the new metadata.
+pub fn regroup(
+    groups: &mut Vec<BookGroup>,
+    group_id: &str,
+    group_type: GroupType,
+    templates: &CommentTemplates,
+) -> Result<Vec<BookGroup>> {
+    let index = groups.iter()
+        .position(|g| g.id == group_id)
+        .ok_or_else(|| anyhow::anyhow!("Group not found: {}", group_id))?;
+    
+    match group_type {
+        GroupType::Single => {
+            if groups[index].files.len() != 1 {
+                anyhow::bail!("Only one-file groups can be marked Single");
+            }
+            groups[index].group_type = GroupType::Single;
+            Ok(vec![groups[index].clone()])
+        }
+        GroupType::Series => {
+            let group = groups.remove(index);
+            if group.files.len() < 2 {
+                groups.insert(index, group);
+                anyhow::bail!("A group needs more than one file to split into a series");
+            }
+            
+            let books: Vec<BookGroup> = group.files.iter().enumerate()
+                .map(|(i, file)| split_book(&group, file, i, templates))
+                .collect();
+            for (offset, book) in books.iter().enumerate() {
+                groups.insert(index + offset, book.clone());
+            }
+            Ok(books)
+        }
+        GroupType::Chapters => {
+            // Split siblings "{id}-1", "{id}-2" merge back under their original id;
+            // any other group is only retyped
+            let mut merged = groups[index].clone();
+            let base = split_base(&merged).map(str::to_string);
+            let members: Vec<usize> = match &base {
+                Some(base) => groups.iter().enumerate()
+                    .filter(|(_, g)| split_base(g) == Some(base.as_str()))
+                    .map(|(i, _)| i)
+                    .collect(),
+                None => vec![index],
+            };
+            
+            merged.group_type = GroupType::Chapters;
+            merged.files = members.iter().flat_map(|&i| groups[i].files.clone()).collect();
+            merged.files.sort_by(|a, b| a.path.cmp(&b.path));
+            if members.len() > 1 {
+                if let Some(base) = base {
+                    merged.id = base;
+                }
+                let folder = group_folder(&merged);
+                if let Some(name) = folder.as_ref().and_then(|f| f.file_name()).and_then(|n| n.to_str()) {
+                    merged.group_name = name.to_string();
+                }
+                merged.metadata.sequence = None;
+                if let Some(series) = merged.metadata.series.clone() {
+                    merged.metadata.title = series;
+                }
+            }
+            merged.files = rediff_files(&merged.files, &merged.metadata, templates);
+            merged.total_changes = count_changed_files(&merged.files);
+            
+            let first = members[0];
+            for &i in members.iter().rev() {
+                groups.remove(i);
+            }
+            groups.insert(first, merged.clone());
+            Ok(vec![merged])
+        }
+    }
+}
+
+/// Cover image names looked for next to the audio, most preferred first
+pub const FOLDER_COVER_NAMES: &[&str] = &["cover.jpg", "cover.jpeg", "cover.png", "folder.jpg", "folder.jpeg", "folder.png"];
+
+/// The cover image in `dir`, with names matched case-insensitively
+pub fn find_folder_cover(dir: &Path) -> Option<PathBuf> {
+    let entries: Vec<PathBuf> = std::fs::read_dir(dir).ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    FOLDER_COVER_NAMES.iter().find_map(|name| {
+        entries.iter()
+            .find(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.eq_ignore_ascii_case(name)))
+            .cloned()
+    })
+}
+
+/// Folder cover for a book, unless one of its files already has embedded art
+pub fn folder_cover_for(files: &[RawFileData]) -> Option<String> {
+    if files.iter().any(|f| f.tags.has_cover) {
+        return None;
+    }
+    sibling_cover(files)
+}
+
+/// Cover image next to a book's files. Disc folders merged into one book
+/// fall back to their parent folder.
+pub fn sibling_cover(files: &[RawFileData]) -> Option<String> {
+    let mut dirs: Vec<&Path> = Vec::new();
+    for dir in files.iter().filter_map(|f| Path::new(&f.path).parent()) {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    let parent = (dirs.len() > 1).then(|| dirs[0].parent()).flatten();
+    dirs.into_iter()
+        .chain(parent)
+        .find_map(find_folder_cover)
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+fn group_folder(group: &BookGroup) -> Option<PathBuf> {
+    group.files.first()
+        .and_then(|f| Path::new(&f.path).parent())
+        .map(Path::to_path_buf)
+}
+
+/// The original id of a book produced by a Series split ("{id}-N")
+fn split_base(group: &BookGroup) -> Option<&str> {
+    if group.group_type != GroupType::Series {
+        return None;
+    }
+    let (base, number) = group.id.rsplit_once('-')?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(base)
+}
+
+/// DRM, locked and read-only files: anything but a plain changed/unchanged diff
+fn has_special_status(file: &AudioFile) -> bool {
+    file.status != "changed" && file.status != "unchanged"
+}
+
+/// Re-read each writable file's tags and diff them against `metadata`.
+/// DRM, locked and read-only files keep their status.
+fn rediff_files(files: &[AudioFile], metadata: &BookMetadata, templates: &CommentTemplates) -> Vec<AudioFile> {
+    files.iter().map(|file| {
+        if has_special_status(file) {
+            return file.clone();
+        }
+        let raw = RawFileData {
+            id: file.id.clone(),
+            ..read_raw_file(Path::new(&file.path))
+        };
+        build_audio_files(std::slice::from_ref(&raw), metadata, templates).remove(0)
+    }).collect()
+}
+
+fn split_book(group: &BookGroup, file: &AudioFile, index: usize, templates: &CommentTemplates) -> BookGroup {
+    let mut metadata = group.metadata.clone();
+    let stem = Path::new(&file.filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file.filename)
+        .to_string();
+    
+    match series_from_filename(&file.filename) {
+        Some(parsed) => {
+            metadata.series = Some(parsed.series);
+            metadata.sequence = Some(parsed.sequence);
+            metadata.title = parsed.title;
+        }
+        None => {
+            if metadata.series.is_none() {
+                metadata.series = Some(group.metadata.title.clone());
+            }
+            metadata.sequence = Some((index + 1).to_string());
+            metadata.title = stem.clone();
+        }
+    }
+    
+    let files = rediff_files(std::slice::from_ref(file), &metadata, templates);
+    BookGroup {
+        id: format!("{}-{}", group.id, index + 1),
+        group_name: stem,
+        group_type: GroupType::Series,
+        total_changes: count_changed_files(&files),
+        files,
+        metadata,
+        quality_score: group.quality_score,
+        needs_review: group.needs_review,
+        warnings: Vec::new(),
+        cover_path: group.cover_path.clone(),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,7 +412,7 @@ pub struct AudioFile {
     pub changes: HashMap<String, FieldChange>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FieldChange {
     pub old: String,
     pub new: String,
@@ -76,6 +431,9 @@ pub struct BookMetadata {
     pub subtitle: Option<String>,
     pub author: String,
     pub narrator: Option<String>,
+    /// Every narrator, for co-narrated books; `narrator` stays the first one
+    #[serde(default)]
+    pub narrators: Vec<String>,
     pub series: Option<String>,
     pub sequence: Option<String>,
     pub genres: Vec<String>,
@@ -83,21 +441,75 @@ pub struct BookMetadata {
     pub year: Option<String>,
     pub description: Option<String>,
     pub isbn: Option<String>,
+    /// Audible product id; written to files and ABS so the match isn't redone
+    #[serde(default)]
+    pub asin: Option<String>,
+}
+
+impl BookMetadata {
+    /// All narrators, falling back to the single `narrator` field
+    pub fn narrator_names(&self) -> Vec<String> {
+        if self.narrators.is_empty() {
+            self.narrator.iter().cloned().collect()
+        } else {
+            self.narrators.clone()
+        }
+    }
+}
+
+/// What a book's tags must look like to be skipped as already processed
+#[derive(Debug, Clone)]
+pub struct ProcessedRules {
+    pub narrator_prefixes: Vec<String>,
+    pub min_genres: usize,
+    pub max_genres: usize,
+    pub force_reprocess: bool,
+}
+
+impl Default for ProcessedRules {
+    fn default() -> Self {
+        Self::from_config(Some(&crate::config::Config::default()))
+    }
+}
+
+impl ProcessedRules {
+    pub fn from_config(config: Option<&crate::config::Config>) -> Self {
+        match config {
+            Some(c) => Self {
+                narrator_prefixes: c.processed_narrator_prefixes.clone(),
+                min_genres: c.processed_min_genres,
+                max_genres: c.processed_max_genres,
+                force_reprocess: c.force_reprocess,
+            },
+            None => Self::default(),
+        }
+    }
 }
 
-fn is_already_processed(tags: &FileTags) -> bool {
+/// A comment written with the app's narrator convention ("Narrated by ...")
+pub fn has_app_narrator_comment(comment: &str, rules: &ProcessedRules) -> bool {
+    rules.narrator_prefixes.iter().any(|p| !p.is_empty() && comment.contains(p.as_str()))
+}
+
+/// The app's comma-separated genre field of approved genres
+pub fn is_app_genre_list(genre: &str, rules: &ProcessedRules) -> bool {
+    let genre_parts: Vec<&str> = genre.split(',').map(|s| s.trim()).collect();
+    genre_parts.len() >= rules.min_genres && genre_parts.len() <= rules.max_genres &&
+    genre_parts.iter().any(|&genre| crate::genres::APPROVED_GENRES.contains(&genre))
+}
+
+fn is_already_processed(tags: &FileTags, rules: &ProcessedRules) -> bool {
+    if rules.force_reprocess {
+        return false;
+    }
+    
     // Check if tags match our app's output format
     let has_narrator_format = tags.comment.as_ref()
-        .map(|c| c.contains("Narrated by ") || c.contains("Read by "))
+        .map(|c| has_app_narrator_comment(c, rules))
         .unwrap_or(false);
     
     let has_clean_genres = tags.genre.as_ref()
-        .map(|g| {
-            // Check if it's our comma-separated format with approved genres
-            let genre_parts: Vec<&str> = g.split(',').map(|s| s.trim()).collect();
-            genre_parts.len() >= 1 && genre_parts.len() <= 3 && 
-            genre_parts.iter().any(|&genre| crate::genres::APPROVED_GENRES.contains(&genre))
-        })
+        .map(|g| is_app_genre_list(g, rules))
         .unwrap_or(false);
     
     let has_clean_title = tags.title.as_ref()
@@ -116,25 +528,77 @@ fn is_already_processed(tags: &FileTags) -> bool {
     is_processed
 }
 pub async fn scan_directory(
-    dir_path: &str, 
-    api_key: Option<String>,
-    _skip_unchanged: bool,
+    dir_paths: &[String], 
+    llm: Option<crate::llm::LlmAccess>,
+    overrides: HashMap<String, BookOverride>,
+    skip_unchanged: bool,
+    summary_only: bool,
     progress_callback: Option<Box<dyn Fn(crate::progress::ScanProgress) + Send + Sync>>
-) -> Result<Vec<BookGroup>> {
+) -> Result<(Vec<BookGroup>, Vec<ScanWarning>, Vec<PathError>)> {
     // CRITICAL: Reset cancellation flag at start
     set_cancellation_flag(false);
     
     println!("🔍 SCAN STARTED");
     println!("📂 Collecting files...");
+    crate::progress::set_phase(crate::progress::ScanPhase::CollectingFiles, &dir_paths.join(", "));
+    
+    let config = crate::config::load_config().ok();
+    let skip_folders = config.as_ref().map(|c| c.skip_folders.clone()).unwrap_or_default();
     
-    let files = collect_audio_files(dir_path)?;
+    // Unchanged files reuse the tags read last time instead of hitting the disk
+    let index = if skip_unchanged { crate::index::FileIndex::new().ok() } else { None };
+    let mut files = Vec::new();
+    let mut path_errors = Vec::new();
+    for dir_path in dir_paths {
+        let (collected, errors) = collect_audio_files_reporting(dir_path, &skip_folders, index.as_ref());
+        files.extend(collected);
+        path_errors.extend(errors);
+    }
     println!("📊 Found {} files\n", files.len());
+    if !path_errors.is_empty() {
+        println!("   ⚠️  {} path(s) could not be read", path_errors.len());
+    }
+    
+    if config.as_ref().is_some_and(|c| c.write_movement_frames) {
+        for file in files.iter_mut() {
+            file.tags.prefer_movement_series();
+        }
+    }
+    
+    let min_duration_secs = config.as_ref()
+        .map(|c| c.min_duration_secs)
+        .unwrap_or(crate::config::DEFAULT_MIN_DURATION_SECS);
+    let warnings = flag_suspect_files(&mut files, min_duration_secs as f64);
+    
+    // DRM files can't be tagged, so report them without sending them through GPT
+    let (mut drm_files, mut files): (Vec<_>, Vec<_>) = files.into_iter().partition(|f| f.drm_protected);
+    
+    if let Some(cfg) = config.as_ref().filter(|c| c.decrypt_aax && !drm_files.is_empty()) {
+        let drm_paths: Vec<String> = drm_files.iter().map(|f| f.path.clone()).collect();
+        let report = match crate::audible::get_activation_bytes(&cfg.audible_cli_path).await {
+            Ok(bytes) => tokio::task::spawn_blocking(move || {
+                crate::audible::decrypt_all(&drm_paths, |p| crate::audible::decrypt_aax(p, &bytes))
+            }).await?,
+            Err(e) => {
+                println!("   ⚠️  Skipping .aax decryption: {}", e);
+                crate::audible::DecryptReport::default()
+            }
+        };
+        drm_files = add_decrypted_files(drm_files, &mut files, &report);
+    }
+    let drm_groups: Vec<BookGroup> = drm_files.iter()
+        .enumerate()
+        .map(|(idx, f)| drm_protected_group(f, idx))
+        .collect();
     
     if files.is_empty() {
-        return Ok(vec![]);
+        crate::progress::set_phase(crate::progress::ScanPhase::Complete, "");
+        return Ok((drm_groups, warnings, path_errors));
     }
     
-    let groups = process_groups_with_gpt(files, api_key, _skip_unchanged, progress_callback).await;
+    let mut groups = process_groups_with_gpt(files, llm, overrides, skip_unchanged, summary_only, progress_callback).await;
+    groups.extend(drm_groups);
+    crate::progress::set_phase(crate::progress::ScanPhase::Complete, "");
     
     let total_changes: usize = groups.iter().map(|g| g.total_changes).sum();
     println!("✅ Complete: {} files in {} groups, {} changes", 
@@ -143,7 +607,200 @@ pub async fn scan_directory(
         total_changes
     );
     
-    Ok(groups)
+    Ok((groups, warnings, path_errors))
+}
+
+// Rough per-book figures for estimate_scan: an extraction and a merge call,
+// each taking a few seconds at gpt-5-nano prices
+const ESTIMATED_GPT_CALLS_PER_LOOKUP: usize = 2;
+const ESTIMATED_SECONDS_PER_LOOKUP: f64 = 12.0;
+const ESTIMATED_COST_PER_GPT_CALL_USD: f64 = 0.0005;
+
+/// How big a scan would be, worked out from the files and the metadata cache
+/// alone
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanEstimate {
+    pub file_count: usize,
+    /// DRM files are reported, never looked up
+    pub drm_files: usize,
+    pub book_count: usize,
+    /// Locked or already-processed books that skip lookup entirely
+    pub skipped_books: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub estimated_gpt_calls: usize,
+    pub estimated_seconds: u64,
+    pub estimated_cost_usd: f64,
+}
+
+/// Count and group the files under `paths` the way a scan would, and check
+/// each book against the cache. No network or GPT calls are made.
+pub fn estimate_scan(
+    paths: &[String],
+    cache: Option<&crate::cache::MetadataCache>,
+    config: Option<&crate::config::Config>,
+) -> Result<ScanEstimate> {
+    let skip_folders = config.map(|c| c.skip_folders.clone()).unwrap_or_default();
+    let index = if config.map(|c| c.skip_unchanged).unwrap_or(false) {
+        crate::index::FileIndex::new().ok()
+    } else {
+        None
+    };
+    
+    let mut estimate = ScanEstimate::default();
+    let mut files = Vec::new();
+    for path in paths {
+        let collected = collect_audio_files(path, &skip_folders, index.as_ref())?;
+        estimate.file_count += collected.len();
+        files.extend(collected.into_iter().filter(|f| !f.drm_protected));
+    }
+    estimate.drm_files = estimate.file_count - files.len();
+    
+    let grouping = config.map(|c| c.grouping_strategy).unwrap_or_default();
+    let merge_discs = config.map(|c| c.merge_disc_folders).unwrap_or(true);
+    let series_patterns = SeriesPatterns::from_config(config);
+    let rules = ProcessedRules::from_config(config);
+    let groups = bucket_files(files, grouping, merge_discs, &series_patterns, || false);
+    estimate.book_count = groups.len();
+    
+    for (folder_name, folder_files) in &groups {
+        let sample = find_best_sample_file(folder_files);
+        if sample.tags.locked || is_already_processed(&sample.tags, &rules) {
+            estimate.skipped_books += 1;
+            continue;
+        }
+        
+        // Same quick key the scan checks before any lookup
+        let quick_title = sample.tags.title.as_deref().unwrap_or(folder_name);
+        let quick_author = sample.tags.artist.as_deref().unwrap_or("Unknown");
+        if cache.and_then(|c| c.get(quick_title, quick_author)).is_some() {
+            estimate.cache_hits += 1;
+        } else {
+            estimate.cache_misses += 1;
+        }
+    }
+    
+    let workers = config.map(|c| c.max_workers).unwrap_or(10).max(1);
+    estimate.estimated_gpt_calls = estimate.cache_misses * ESTIMATED_GPT_CALLS_PER_LOOKUP;
+    estimate.estimated_seconds = (estimate.cache_misses as f64 * ESTIMATED_SECONDS_PER_LOOKUP / workers as f64).ceil() as u64;
+    estimate.estimated_cost_usd = estimate.estimated_gpt_calls as f64 * ESTIMATED_COST_PER_GPT_CALL_USD;
+    
+    println!("📐 Estimate: {} books ({} cached, {} to look up, {} skipped), ~{}s",
+        estimate.book_count, estimate.cache_hits, estimate.cache_misses, estimate.skipped_books, estimate.estimated_seconds);
+    Ok(estimate)
+}
+
+/// A book as local grouping sees it, before any lookup
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalBook {
+    pub name: String,
+    pub files: Vec<String>,
+}
+
+/// Group the files under `dir_path` the way a scan would, from their tags
+/// alone, sorted by book name then path. DRM files are left out, as ABS
+/// can't import them either.
+pub fn local_book_files(dir_path: &str, config: Option<&crate::config::Config>) -> Result<Vec<(String, Vec<RawFileData>)>> {
+    let skip_folders = config.map(|c| c.skip_folders.clone()).unwrap_or_default();
+    let files: Vec<RawFileData> = collect_audio_files(dir_path, &skip_folders, None)?
+        .into_iter()
+        .filter(|f| !f.drm_protected)
+        .collect();
+    
+    let grouping = config.map(|c| c.grouping_strategy).unwrap_or_default();
+    let merge_discs = config.map(|c| c.merge_disc_folders).unwrap_or(true);
+    let series_patterns = SeriesPatterns::from_config(config);
+    let mut books: Vec<(String, Vec<RawFileData>)> = bucket_files(files, grouping, merge_discs, &series_patterns, || false)
+        .into_iter()
+        .map(|(name, mut files)| {
+            files.sort_by(|a, b| a.path.cmp(&b.path));
+            (name, files)
+        })
+        .collect();
+    books.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(books)
+}
+
+/// Swap each successfully decrypted .aax for its new .m4b, reading the m4b's
+/// tags unless collection already picked it up. Returns the still-locked files.
+fn add_decrypted_files(
+    drm_files: Vec<RawFileData>,
+    files: &mut Vec<RawFileData>,
+    report: &crate::audible::DecryptReport,
+) -> Vec<RawFileData> {
+    for decrypted in &report.decrypted {
+        if files.iter().any(|f| f.path == decrypted.output) {
+            continue;
+        }
+        files.push(read_raw_file(Path::new(&decrypted.output)));
+    }
+    
+    drm_files.into_iter()
+        .filter(|f| !report.decrypted.iter().any(|d| d.source == f.path))
+        .collect()
+}
+
+/// Read one file's current tags outside of a scan
+pub fn read_raw_file(path: &Path) -> RawFileData {
+    let (tags, duration_secs) = extract_tags(path);
+    RawFileData {
+        id: format!("{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()),
+        path: path.to_string_lossy().to_string(),
+        filename: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        tags,
+        drm_protected: false,
+        duration_secs,
+        size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        warning: None,
+    }
+}
+
+/// Lossless formats get a ceiling of their own; ALAC usually comes as .m4a
+fn max_plausible_bytes_per_sec(filename: &str) -> f64 {
+    let ext = Path::new(filename).extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "flac" | "wav" | "aif" | "aiff" | "m4a" => MAX_PLAUSIBLE_LOSSLESS_BYTES_PER_SEC,
+        _ => MAX_PLAUSIBLE_BYTES_PER_SEC,
+    }
+}
+
+/// Annotate files that are implausibly short or whose size doesn't fit their
+/// duration, returning them as warnings. Nothing is skipped.
+fn flag_suspect_files(files: &mut [RawFileData], min_duration_secs: f64) -> Vec<ScanWarning> {
+    let mut warnings = Vec::new();
+    
+    for file in files.iter_mut() {
+        let Some(duration) = file.duration_secs else { continue };
+        
+        let message = if duration < min_duration_secs {
+            Some(format!("Only {:.1}s long — the file may be truncated", duration))
+        } else {
+            let bytes_per_sec = file.size_bytes as f64 / duration;
+            if !(MIN_PLAUSIBLE_BYTES_PER_SEC..=max_plausible_bytes_per_sec(&file.filename)).contains(&bytes_per_sec) {
+                Some(format!(
+                    "Size doesn't match duration ({:.0} kbps) — the file may be truncated or corrupt",
+                    bytes_per_sec * 8.0 / 1000.0
+                ))
+            } else {
+                None
+            }
+        };
+        
+        if let Some(message) = message {
+            println!("   ⚠️  {}: {}", file.filename, message);
+            warnings.push(ScanWarning {
+                path: file.path.clone(),
+                filename: file.filename.clone(),
+                message: message.clone(),
+            });
+            file.warning = Some(message);
+        }
+    }
+    
+    warnings
 }
 // pub async fn scan_directory_streaming<F>(
 //     dir_path: &str,
@@ -179,28 +836,72 @@ pub async fn scan_directory(
 //     Ok(all_groups)
 // }
 
-fn collect_audio_files(dir_path: &str) -> Result<Vec<RawFileData>> {
+/// Directories no one wants tagged - archive and NAS metadata
+const JUNK_DIRS: &[&str] = &["__MACOSX", "@eaDir", ".Trashes", "$RECYCLE.BIN"];
+
+fn collect_audio_files(
+    dir_path: &str,
+    skip_folders: &[String],
+    index: Option<&crate::index::FileIndex>,
+) -> Result<Vec<RawFileData>> {
+    let (files, errors) = collect_audio_files_reporting(dir_path, skip_folders, index);
+    for error in &errors {
+        println!("   ⚠️  Could not read {}: {}", error.path, error.error);
+    }
+    Ok(files)
+}
+
+/// Walk `dir_path` for audio files, recording every entry the walk couldn't
+/// read (permissions, broken links, a missing root) instead of dropping it
+fn collect_audio_files_reporting(
+    dir_path: &str,
+    skip_folders: &[String],
+    index: Option<&crate::index::FileIndex>,
+) -> (Vec<RawFileData>, Vec<PathError>) {
     use walkdir::WalkDir;
     
     let mut files = Vec::new();
+    let mut path_errors = Vec::new();
+    let mut reused = 0;
+    let root = Path::new(dir_path);
+    let skip_patterns: Vec<SkipPattern> = skip_folders.iter()
+        .filter_map(|p| SkipPattern::parse(p))
+        .collect();
+    let ffprobe_path = ffprobe_fallback_path();
     
     for entry in WalkDir::new(dir_path)
         .follow_links(true)
         .into_iter()
-        .filter_map(|e| e.ok())
+        .filter_entry(|e| !is_junk_dir(e.path()))
     {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                path_errors.push(PathError {
+                    path: e.path().unwrap_or(root).to_string_lossy().to_string(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
         let path = entry.path();
         
         if !path.is_file() {
             continue;
         }
         
+        if let Some(pattern) = skip_patterns.iter().find(|p| p.matches(root, path)) {
+            println!("   ⏭️  Skipping {} (matches \"{}\")", path.display(), pattern.raw);
+            continue;
+        }
+        
         let ext = path.extension()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_lowercase();
         
-        if !matches!(ext.as_str(), "m4b" | "m4a" | "mp3" | "flac" | "ogg") {
+        let ffprobe_only = FFPROBE_ONLY_EXTENSIONS.contains(&ext.as_str());
+        if !is_audio_extension(&ext) && !(ffprobe_only && ffprobe_path.is_some()) {
             continue;
         }
         
@@ -213,120 +914,631 @@ fn collect_audio_files(dir_path: &str) -> Result<Vec<RawFileData>> {
             continue;
         }
         
-        let tags = extract_tags(path);
+        // lofty can't parse encrypted Audible files, so don't even try
+        let drm_protected = is_drm_extension(&ext);
+        let (tags, duration_secs) = if drm_protected {
+            println!("   🔒 {}: {}", filename, DRM_STATUS);
+            (FileTags::default(), None)
+        } else if let Some(entry) = index.and_then(|i| i.lookup(path)) {
+            reused += 1;
+            (entry.tags, entry.duration_secs)
+        } else {
+            let (tags, duration_secs) = extract_tags_with(path, || ffprobe_path.clone());
+            if let Some(index) = index {
+                if let Err(e) = index.update(path, &tags, duration_secs) {
+                    println!("   ⚠️  Could not index {}: {}", filename, e);
+                }
+            }
+            (tags, duration_secs)
+        };
         
         files.push(RawFileData {
             id: format!("{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()),
             path: path.to_string_lossy().to_string(),
             filename,
             tags,
+            drm_protected,
+            duration_secs,
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            warning: None,
         });
     }
     
-    Ok(files)
+    if let Some(index) = index {
+        println!("   ♻️  {} of {} files unchanged since the last scan", reused, files.len());
+        if let Err(e) = index.flush() {
+            println!("   ⚠️  Could not save file index: {}", e);
+        }
+    }
+    
+    (files, path_errors)
 }
 
-fn extract_tags(path: &Path) -> FileTags {
-    use lofty::probe::Probe;
-    use lofty::prelude::*;
-    
-    let tag = Probe::open(path)
-        .ok()
-        .and_then(|p| p.read().ok())
-        .and_then(|t| t.primary_tag().cloned());
+pub(crate) fn is_audio_extension(ext: &str) -> bool {
+    matches!(ext, "m4b" | "m4a" | "m4v" | "mp3" | "flac" | "ogg" | "opus" | "aax" | "aaxc")
+}
+
+/// Formats lofty can't parse, collected only when the ffprobe fallback is on
+const FFPROBE_ONLY_EXTENSIONS: &[&str] = &["wma"];
+
+pub(crate) fn is_drm_extension(ext: &str) -> bool {
+    matches!(ext, "aax" | "aaxc")
+}
+
+fn drm_protected_group(file: &RawFileData, index: usize) -> BookGroup {
+    let title = Path::new(&file.filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&file.filename)
+        .to_string();
     
-    FileTags {
-        title: tag.as_ref().and_then(|t| t.title().map(|s| s.to_string())),
-        artist: tag.as_ref().and_then(|t| t.artist().map(|s| s.to_string())),
-        album: tag.as_ref().and_then(|t| t.album().map(|s| s.to_string())),
-        album_artist: None,
-        composer: None,
-        genre: tag.as_ref().and_then(|t| t.genre().map(|s| s.to_string())),
-        year: tag.as_ref().and_then(|t| t.year().map(|y| y.to_string())),
-        track: None,
-        comment: tag.as_ref().and_then(|t| t.comment().map(|s| s.to_string())),
+    BookGroup {
+        id: format!("drm-{}", index),
+        group_name: title.clone(),
+        group_type: GroupType::Single,
+        files: vec![AudioFile {
+            id: file.id.clone(),
+            path: file.path.clone(),
+            filename: file.filename.clone(),
+            status: DRM_STATUS.to_string(),
+            changes: HashMap::new(),
+        }],
+        metadata: BookMetadata {
+            title,
+            subtitle: None,
+            author: "Unknown".to_string(),
+            narrator: None,
+            narrators: vec![],
+            series: None,
+            sequence: None,
+            genres: vec![],
+            publisher: None,
+            year: None,
+            description: None,
+            isbn: None,
+            asin: None,
+        },
+        total_changes: 0,
+        quality_score: 0,
+        needs_review: false,
+        warnings: Vec::new(),
+        cover_path: None,
     }
 }
-async fn process_groups_with_gpt(
-    files: Vec<RawFileData>, 
-    api_key: Option<String>,
-    _skip_unchanged: bool,
-    progress_callback: Option<Box<dyn Fn(crate::progress::ScanProgress) + Send + Sync>>
-) -> Vec<BookGroup> {
-    set_cancellation_flag(false);
-    
-    let total_files = files.len();
-    let start_time = Instant::now();
-   
-    // ADD THIS LINE:
-    crate::progress::set_total_files(total_files);
-    
-    let config = crate::config::load_config().ok();
-    let max_workers = config.as_ref().map(|c| c.max_workers).unwrap_or(10);
-    
-    println!("🚀 Processing {} files with {} parallel workers...", total_files, max_workers);
+
+/// Bucket files into books according to the configured grouping strategy
+fn group_files(
+    files: Vec<RawFileData>,
+    strategy: crate::config::GroupingStrategy,
+    merge_discs: bool,
+    series_patterns: &SeriesPatterns,
+) -> HashMap<String, Vec<RawFileData>> {
+    bucket_files(files, strategy, merge_discs, series_patterns, is_cancelled)
+}
+
+/// `group_files` with the stop check supplied, so estimates can group
+/// without reacting to a scan's cancel button
+fn bucket_files(
+    files: Vec<RawFileData>,
+    strategy: crate::config::GroupingStrategy,
+    merge_discs: bool,
+    series_patterns: &SeriesPatterns,
+    stop: impl Fn() -> bool,
+) -> HashMap<String, Vec<RawFileData>> {
+    use crate::config::GroupingStrategy;
     
-    let mut folder_map: HashMap<String, Vec<RawFileData>> = HashMap::new();
+    let mut groups: HashMap<String, Vec<RawFileData>> = HashMap::new();
+    let mut fuzzy_clusters: Vec<(String, String)> = Vec::new();
     
     for file in files {
-        let path = PathBuf::from(&file.path);
-        let mut parent = path.parent()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
-        
-        parent = parent.replace("(book #", "(Book #").replace("(Book#", "(Book #");
-        if !parent.ends_with(')') && parent.contains("Book #") {
-            if let Some(pos) = parent.rfind(" - ") {
-                parent = format!("{})", &parent[..pos]);
-            }
-        }
-        if is_cancelled() {
+        if stop() {
             println!("🛑 Scan cancelled by user");
             break;
         }
-        let _filename_lower = file.filename.to_lowercase();
-        let parent_lower = parent.to_lowercase();
-        
-        let group_key = if parent_lower.contains("book #") || parent_lower.contains("book#") {
-            if let Some(book_match) = parent_lower.split("book #").nth(1)
-                .or_else(|| parent_lower.split("book#").nth(1)) {
-                if let Some(book_num_end) = book_match.find(|c: char| !c.is_numeric() && c != ')') {
-                    let book_id = &book_match[..book_num_end];
-                    let base_parent = if let Some(pos) = parent.find("(Book #") {
-                        parent[..pos].trim().to_string()
-                    } else if let Some(pos) = parent.find("(book #") {
-                        parent[..pos].trim().to_string()
-                    } else {
-                        parent.clone()
-                    };
-                    format!("{} (Book #{})", base_parent, book_id)
-                } else {
-                    parent.clone()
+        
+        let group_key = match strategy {
+            GroupingStrategy::Folder => folder_group_key(&file, merge_discs, series_patterns),
+            GroupingStrategy::AlbumTag => file.tags.album.as_deref()
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(String::from)
+                .unwrap_or_else(|| folder_group_key(&file, merge_discs, series_patterns)),
+            GroupingStrategy::FuzzyTitle => fuzzy_group_key(&file, &mut fuzzy_clusters, merge_discs, series_patterns),
+        };
+        
+        groups.entry(group_key).or_insert_with(Vec::new).push(file);
+    }
+    
+    // Merged discs play in disc order, then track order within each disc
+    if merge_discs {
+        for files in groups.values_mut() {
+            if files.iter().any(|f| file_disc_number(f).is_some()) {
+                files.sort_by(|a, b| {
+                    (file_disc_number(a).unwrap_or(0), file_track_number(a).unwrap_or(u32::MAX), &a.filename)
+                        .cmp(&(file_disc_number(b).unwrap_or(0), file_track_number(b).unwrap_or(u32::MAX), &b.filename))
+                });
+            }
+        }
+    }
+    
+    groups
+}
+
+/// Disc number from a folder named like "Disc 1", "CD2" or "Disk 03"
+fn disc_number(folder_name: &str) -> Option<u32> {
+    static DISC_FOLDER: Lazy<regex::Regex> = Lazy::new(|| {
+        regex::Regex::new(r"(?i)^(?:disc|disk|cd)\s*[-_#.]?\s*(\d{1,3})$").unwrap()
+    });
+    DISC_FOLDER.captures(folder_name.trim())
+        .and_then(|caps| caps[1].parse().ok())
+}
+
+fn file_disc_number(file: &RawFileData) -> Option<u32> {
+    Path::new(&file.path)
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .and_then(disc_number)
+}
+
+/// Track number from the track tag ("3" or "3/12"), else the filename's
+/// leading digits, so "2.mp3" sorts before "10.mp3"
+fn file_track_number(file: &RawFileData) -> Option<u32> {
+    let from_tag = file.tags.track.as_deref()
+        .and_then(|t| t.split('/').next())
+        .and_then(|t| t.trim().parse().ok());
+    from_tag.or_else(|| {
+        let digits: String = file.filename.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse().ok()
+    })
+}
+
+/// Parent folder name with any part marker after its series marker dropped,
+/// so split parts of one book land together but the title is kept.
+fn folder_group_key(file: &RawFileData, merge_discs: bool, series_patterns: &SeriesPatterns) -> String {
+    let path = PathBuf::from(&file.path);
+    let mut folder = path.parent();
+    // "Book/Disc 1/01.mp3" belongs to "Book"
+    if merge_discs && file_disc_number(file).is_some() {
+        folder = folder.and_then(|p| p.parent());
+    }
+    let parent = folder
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown")
+        .to_string();
+    
+    // "Series (Book #3) - Part 1" and "Series (Book #3) - Part 2" are one book,
+    // "Series Book 3 - Title" and "Series Book 3 - Other Title" are not
+    static PART_MARKER: Lazy<regex::Regex> = Lazy::new(|| {
+        regex::Regex::new(r"(?i)[\s\-_,:.(\[]*\b(?:part|pt|partie|parte|teil|disc|disk|cd)\b\.?\s*\d+.*$").unwrap()
+    });
+    match series_patterns.parse(&parent) {
+        Some(found) if found.series.is_some() && found.sequence.is_some() => {
+            let rest = parent.trim_start().strip_prefix(found.prefix.as_str()).unwrap_or("");
+            let title = PART_MARKER.replace(rest, "");
+            let title = title.trim_end_matches(|c: char| c.is_whitespace() || "-–—:,_".contains(c));
+            format!("{}{}", found.prefix, title)
+        }
+        _ => parent,
+    }
+}
+
+const FUZZY_GROUP_SIMILARITY: f64 = 0.85;
+
+/// Group by album (or title) similarity, ignoring folders entirely.
+/// `clusters` holds (normalized, display) keys seen so far.
+fn fuzzy_group_key(
+    file: &RawFileData,
+    clusters: &mut Vec<(String, String)>,
+    merge_discs: bool,
+    series_patterns: &SeriesPatterns,
+) -> String {
+    let raw = file.tags.album.clone()
+        .filter(|a| !a.trim().is_empty())
+        .or_else(|| file.tags.title.clone())
+        .unwrap_or_else(|| {
+            Path::new(&file.filename)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&file.filename)
+                .to_string()
+        });
+    
+    let display = strip_part_markers(&raw);
+    let normalized = crate::metadata::normalize_for_match(&display);
+    if normalized.is_empty() {
+        return folder_group_key(file, merge_discs, series_patterns);
+    }
+    
+    if let Some((_, existing)) = clusters.iter()
+        .find(|(key, _)| crate::metadata::similarity(key, &normalized) >= FUZZY_GROUP_SIMILARITY)
+    {
+        return existing.clone();
+    }
+    
+    clusters.push((normalized, display.clone()));
+    display
+}
+
+/// Drop chapter/part/disc/track numbering so sibling files share one title
+fn strip_part_markers(title: &str) -> String {
+    use regex::Regex;
+    
+    let patterns = [
+        r"(?i)[\s\-_,:.(\[]*\b(?:chapter|ch|part|pt|disc|disk|cd|track)\b\.?\s*\d+.*$",
+        r"^\d+\s*[-_.]\s*",
+        r"\s+[-_]?\s*\d+\s*$",
+    ];
+    
+    let mut cleaned = title.trim().to_string();
+    for pattern in patterns {
+        if let Ok(re) = Regex::new(pattern) {
+            cleaned = re.replace(&cleaned, "").trim().to_string();
+        }
+    }
+    cleaned
+}
+
+/// Series marker found in a folder name by one of the `series_patterns`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FolderSeries {
+    pub series: Option<String>,
+    pub sequence: Option<String>,
+    /// The folder name up to the end of the marker, e.g. "Dune (Book #1)"
+    pub prefix: String,
+}
+
+/// The configured `series_patterns`, compiled and tried in order
+#[derive(Debug, Clone)]
+pub struct SeriesPatterns {
+    patterns: Vec<regex::Regex>,
+}
+
+impl Default for SeriesPatterns {
+    fn default() -> Self {
+        Self::from_config(Some(&crate::config::Config::default()))
+    }
+}
+
+impl SeriesPatterns {
+    pub fn new(patterns: &[String]) -> Self {
+        let patterns = patterns.iter()
+            .filter_map(|p| match regex::Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    println!("   ⚠️  Ignoring series pattern {}: {}", p, e);
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+    
+    pub fn from_config(config: Option<&crate::config::Config>) -> Self {
+        match config {
+            Some(c) => Self::new(&c.series_patterns),
+            None => Self::default(),
+        }
+    }
+    
+    /// First pattern that captures a series or sequence wins
+    pub fn parse(&self, folder_name: &str) -> Option<FolderSeries> {
+        for re in &self.patterns {
+            let Some(caps) = re.captures(folder_name) else { continue };
+            
+            let series = caps.name("series")
+                .map(|m| m.as_str().trim().trim_end_matches([',', ':', '-', '(', '[']).trim().to_string())
+                .filter(|s| !s.is_empty());
+            let sequence = caps.name("sequence")
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|s| !s.is_empty());
+            if series.is_none() && sequence.is_none() {
+                continue;
+            }
+            
+            let end = caps.get(0).map(|m| m.end()).unwrap_or(folder_name.len());
+            return Some(FolderSeries {
+                series,
+                sequence,
+                prefix: folder_name[..end].trim().to_string(),
+            });
+        }
+        None
+    }
+}
+
+/// Series info parsed from a box-set style filename such as
+/// "The Expanse 03 - Abaddon's Gate.m4b"
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameSeries {
+    pub series: String,
+    pub sequence: String,
+    pub title: String,
+}
+
+pub fn series_from_filename(filename: &str) -> Option<FilenameSeries> {
+    use regex::Regex;
+    
+    let stem = Path::new(filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(filename);
+    
+    let re = Regex::new(
+        r"(?i)^(?P<series>.+?)[\s,]+(?:book\s*|vol\.?\s*|volume\s*|#)?(?P<seq>\d{1,3}(?:\.\d+)?)\s*[-–:]\s*(?P<title>.+)$"
+    ).ok()?;
+    let caps = re.captures(stem)?;
+    
+    let series = caps["series"].trim().trim_end_matches(&['-', ',', '('][..]).trim().to_string();
+    let title = caps["title"].trim().to_string();
+    if series.is_empty() || title.is_empty() {
+        return None;
+    }
+    
+    // "03" -> "3", "1.5" stays as-is
+    let sequence = caps["seq"].trim_start_matches('0');
+    let sequence = if sequence.is_empty() || sequence.starts_with('.') {
+        format!("0{}", sequence)
+    } else {
+        sequence.to_string()
+    };
+    
+    Some(FilenameSeries { series, sequence, title })
+}
+
+fn is_junk_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| JUNK_DIRS.iter().any(|junk| n.eq_ignore_ascii_case(junk)))
+        .unwrap_or(false)
+}
+
+/// A skip_folders entry: globs compile to a regex, anything else is a
+/// case-insensitive substring
+struct SkipPattern {
+    raw: String,
+    absolute: bool,
+    glob: Option<regex::Regex>,
+}
+
+impl SkipPattern {
+    fn parse(raw: &str) -> Option<Self> {
+        let pattern = raw.trim().replace('\\', "/");
+        if pattern.is_empty() {
+            return None;
+        }
+        let absolute = Path::new(raw.trim()).is_absolute() || pattern.starts_with('/');
+        
+        let glob = if pattern.contains(['*', '?', '[']) {
+            let mut re = String::from("(?i)^");
+            let mut chars = pattern.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '*' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        re.push_str(".*");
+                    }
+                    '*' => re.push_str("[^/]*"),
+                    '?' => re.push_str("[^/]"),
+                    '[' | ']' => re.push(c),
+                    _ => re.push_str(&regex::escape(&c.to_string())),
                 }
-            } else {
-                parent.clone()
             }
+            // A folder pattern covers everything beneath it
+            re.push_str("(/.*)?$");
+            Some(regex::Regex::new(&re).ok()?)
         } else {
-            parent.clone()
+            None
         };
         
-        folder_map.entry(group_key).or_insert_with(Vec::new).push(file);
+        Some(SkipPattern { raw: raw.to_string(), absolute, glob })
+    }
+    
+    fn matches(&self, root: &Path, file: &Path) -> bool {
+        let full = file.to_string_lossy().replace('\\', "/");
+        let target = if self.absolute {
+            full
+        } else {
+            file.strip_prefix(root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or(full)
+        };
+        
+        match &self.glob {
+            Some(re) => re.is_match(&target),
+            None => {
+                let needle = self.raw.trim().replace('\\', "/").to_lowercase();
+                if self.absolute {
+                    target.to_lowercase().starts_with(&needle)
+                } else {
+                    target.to_lowercase().contains(&needle)
+                }
+            }
+        }
+    }
+}
+
+/// Single-file books in a box set carry their series position in the
+/// filename; use it when the lookups didn't supply one.
+fn fill_series_from_filename(mut metadata: BookMetadata, files: &[RawFileData]) -> BookMetadata {
+    if files.len() != 1 || (metadata.series.is_some() && metadata.sequence.is_some()) {
+        return metadata;
+    }
+    
+    if let Some(parsed) = series_from_filename(&files[0].filename) {
+        println!("   📚 Series from filename: {} #{}", parsed.series, parsed.sequence);
+        if metadata.series.is_none() {
+            metadata.series = Some(parsed.series);
+        }
+        if metadata.sequence.is_none() {
+            metadata.sequence = Some(parsed.sequence);
+        }
+    }
+    
+    metadata
+}
+
+/// Report a hand-locked book as-is, with no proposed changes.
+fn locked_group(folder_name: &str, files: &[RawFileData], index: usize, templates: &CommentTemplates) -> BookGroup {
+    let sample_file = find_best_sample_file(files);
+    
+    BookGroup {
+        id: format!("locked-{}", index),
+        group_name: folder_name.to_string(),
+        group_type: if files.len() > 1 { GroupType::Chapters } else { GroupType::Single },
+        files: files.iter().map(|f| AudioFile {
+            id: f.id.clone(),
+            path: f.path.clone(),
+            filename: f.filename.clone(),
+            status: LOCKED_STATUS.to_string(),
+            changes: HashMap::new(),
+        }).collect(),
+        metadata: metadata_from_processed_tags(&sample_file.tags, folder_name, templates),
+        total_changes: 0,
+        quality_score: 100,
+        needs_review: false,
+        warnings: Vec::new(),
+        cover_path: folder_cover_for(files),
+    }
+}
+
+/// Read the primary tag and the audio duration in one probe
+fn extract_tags(path: &Path) -> (FileTags, Option<f64>) {
+    extract_tags_with(path, ffprobe_fallback_path)
+}
+
+fn ffprobe_fallback_path() -> Option<String> {
+    crate::config::load_config().ok()
+        .filter(|c| c.ffprobe_fallback)
+        .map(|c| c.ffprobe_path)
+}
+
+/// `ffprobe` is only asked for when lofty can't parse the file at all; a
+/// parsed file with no tag yet is simply untagged
+fn extract_tags_with(path: &Path, ffprobe: impl FnOnce() -> Option<String>) -> (FileTags, Option<f64>) {
+    use lofty::probe::Probe;
+    use lofty::prelude::*;
+    use lofty::tag::ItemKey;
+    
+    let tagged = Probe::open(path)
+        .ok()
+        .and_then(|p| p.read().ok());
+    let duration_secs = tagged.as_ref()
+        .map(|t| t.properties().duration().as_secs_f64())
+        .filter(|d| *d > 0.0);
+    let tag = tagged.as_ref().and_then(|t| t.primary_tag().cloned());
+    
+    if tagged.is_none() {
+        if let Some(ffprobe_path) = ffprobe() {
+            match crate::ffprobe::read_tags(&ffprobe_path, path) {
+                Ok((tags, probed_secs)) => {
+                    println!("   🔎 Read {} with ffprobe ({})", path.display(), READ_ONLY_STATUS);
+                    return (tags, duration_secs.or(probed_secs));
+                }
+                Err(e) => println!("   ⚠️  ffprobe fallback failed for {}: {}", path.display(), e),
+            }
+        }
+    }
+    
+    let tags = FileTags {
+        title: tag.as_ref().and_then(|t| t.title().map(|s| s.to_string())),
+        artist: tag.as_ref().and_then(|t| t.artist().map(|s| s.to_string())),
+        album: tag.as_ref().and_then(|t| t.album().map(|s| s.to_string())),
+        album_artist: None,
+        composer: tag.as_ref()
+            .map(|t| t.get_strings(&ItemKey::Composer).collect::<Vec<_>>().join(crate::tags::NARRATOR_SEPARATOR))
+            .filter(|s| !s.is_empty()),
+        narrator: tag.as_ref()
+            .map(|t| {
+                let custom: Vec<&str> = t.get_strings(&crate::tags::narrator_freeform_key(t.tag_type())).collect();
+                if custom.is_empty() {
+                    t.get_strings(&ItemKey::Composer).collect::<Vec<_>>().join(crate::tags::NARRATOR_SEPARATOR)
+                } else {
+                    custom.join(crate::tags::NARRATOR_SEPARATOR)
+                }
+            })
+            .filter(|s| !s.is_empty()),
+        genre: tag.as_ref().and_then(|t| t.genre().map(|s| s.to_string())),
+        year: tag.as_ref().and_then(|t| t.year().map(|y| y.to_string())),
+        track: None,
+        comment: tag.as_ref().and_then(|t| t.comment().map(|s| s.to_string())),
+        subtitle: tag.as_ref().and_then(|t| t.get_string(&ItemKey::TrackSubtitle).map(|s| s.to_string())),
+        publisher: tag.as_ref().and_then(|t| t.get_string(&ItemKey::Publisher).map(|s| s.to_string())),
+        series: tag.as_ref().and_then(|t| t.get_string(&ItemKey::Unknown("SERIES".to_string())).map(|s| s.to_string())),
+        sequence: tag.as_ref().and_then(|t| t.get_string(&ItemKey::Unknown("SERIES-PART".to_string())).map(|s| s.to_string())),
+        movement: tag.as_ref().and_then(|t| t.get_string(&ItemKey::Movement).map(|s| s.to_string())),
+        movement_number: tag.as_ref().and_then(|t| t.get_string(&ItemKey::MovementNumber).map(|s| s.to_string())),
+        isbn: tag.as_ref().and_then(|t| t.get_string(&crate::tags::isbn_item_key(t.tag_type())).map(|s| s.to_string())),
+        asin: tag.as_ref().and_then(|t| t.get_string(&crate::tags::asin_item_key(t.tag_type())).map(|s| s.to_string())),
+        // TSOT/TSOP in ID3, sonm/soar in MP4, TITLESORT/ARTISTSORT in Vorbis
+        title_sort: tag.as_ref().and_then(|t| t.get_string(&ItemKey::TrackTitleSort).map(|s| s.to_string())),
+        author_sort: tag.as_ref().and_then(|t| t.get_string(&ItemKey::TrackArtistSort).map(|s| s.to_string())),
+        locked: tag.as_ref()
+            .map(|t| t.get_string(&crate::tags::lock_item_key(t.tag_type())).is_some())
+            .unwrap_or(false),
+        read_only: false,
+        has_cover: tagged.as_ref().is_some_and(|t| t.tags().iter().any(|tag| !tag.pictures().is_empty())),
+    };
+    
+    (tags, duration_secs)
+}
+async fn process_groups_with_gpt(
+    files: Vec<RawFileData>, 
+    llm: Option<crate::llm::LlmAccess>,
+    overrides: HashMap<String, BookOverride>,
+    _skip_unchanged: bool,
+    summary_only: bool,
+    progress_callback: Option<Box<dyn Fn(crate::progress::ScanProgress) + Send + Sync>>
+) -> Vec<BookGroup> {
+    set_cancellation_flag(false);
+    
+    let total_files = files.len();
+    let start_time = Instant::now();
+    
+    let config = crate::config::load_config().ok();
+    let max_workers = config.as_ref().map(|c| c.max_workers).unwrap_or(10);
+    let review_threshold = config.as_ref()
+        .map(|c| c.review_threshold)
+        .unwrap_or(crate::config::DEFAULT_REVIEW_THRESHOLD);
+    let overrides = Arc::new(overrides);
+    let templates = CommentTemplates::from_config(config.as_ref());
+    let processed_rules = ProcessedRules::from_config(config.as_ref());
+    
+    println!("🚀 Processing {} files with {} parallel workers...", total_files, max_workers);
+    
+    let grouping = config.as_ref().map(|c| c.grouping_strategy).unwrap_or_default();
+    let merge_discs = config.as_ref().map(|c| c.merge_disc_folders).unwrap_or(true);
+    let series_patterns = SeriesPatterns::from_config(config.as_ref());
+    let mut folder_map = group_files(files, grouping, merge_discs, &series_patterns);
+    
+    // Locked books keep their hand-edited tags, so pull them out before any lookups
+    let mut locked_keys: Vec<String> = folder_map.iter()
+        .filter(|(_, files)| find_best_sample_file(files).tags.locked)
+        .map(|(k, _)| k.clone())
+        .collect();
+    locked_keys.sort();
+    let mut locked_groups = Vec::new();
+    for (idx, key) in locked_keys.into_iter().enumerate() {
+        if let Some(files) = folder_map.remove(&key) {
+            println!("🔒 Skipping locked book: {}", key);
+            locked_groups.push(locked_group(&key, &files, idx, &templates));
+        }
     }
     
     let mut groups = Vec::new();
     let mut group_id = 0;
     let total_groups = folder_map.len();
-    let mut progress = crate::progress::ScanProgress::new(total_groups);
+    // Progress counts books, so the ETA is per book too
+    crate::progress::set_total_files(total_groups);
     let mut processed = 0;
     
+    // Shared by both paths below; series books only use its negative entries
+    let cache = crate::cache::MetadataCache::new().ok();
+    
     let series_groups: Vec<(String, Vec<RawFileData>)> = folder_map
     .iter()
-    .filter(|(name, _)| {
-        let lower = name.to_lowercase();
-        lower.contains("book #") || lower.contains("book#") || lower.contains("(book ")
-    })
+    .filter(|(name, _)| matches!(series_patterns.parse(name), Some(FolderSeries { sequence: Some(_), .. })))
     .map(|(k, v)| (k.clone(), v.clone()))
     .collect();
 
@@ -344,8 +1556,10 @@ if series_groups.len() > 5 {
             break;
         }
         
-        let api_key_clone = api_key.clone();
+        let llm_clone = llm.clone();
         let config_clone = config.clone();
+        let overrides_clone = Arc::clone(&overrides);
+        let cache_clone = cache.clone();
         let sem = Arc::clone(&semaphore);
         
         let handle = tokio::spawn(async move {
@@ -357,38 +1571,45 @@ if series_groups.len() > 5 {
             
             println!("\n📖 Processing: {}", folder_name);
             
-            let (book_title, book_author) = extract_book_info_with_gpt(
+            let book_override = overrides_clone.get(&folder_name);
+            
+            crate::progress::set_phase(crate::progress::ScanPhase::ExtractingInfo, &folder_name);
+            let (book_title, book_author) = resolve_book_info(
                 sample_file,
                 &folder_name,
-                api_key_clone.as_deref()
+                llm_clone.as_ref(),
+                book_override,
+                config_clone.as_ref(),
             ).await;
             
-            let audible_data = if let Some(ref cfg) = config_clone {
-                if cfg.audible_enabled && !cfg.audible_cli_path.is_empty() {
-                    crate::audible::search_audible(&book_title, &book_author, &cfg.audible_cli_path)
-                        .await.ok().flatten()
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            
-            let google_data = crate::metadata::fetch_from_google_books(&book_title, &book_author)
-                .await.ok().flatten();
+            let (audible_data, google_data) = lookup_sources(
+                &book_title,
+                &book_author,
+                cache_clone.as_ref(),
+                search_audible_for(config_clone.as_ref(), &book_title, &book_author),
+                crate::metadata::fetch_from_google_books(&book_title, &book_author),
+                &folder_name,
+            ).await;
             
-            let final_metadata = merge_all_with_gpt_retry(
+            crate::progress::set_phase(crate::progress::ScanPhase::Merging, &folder_name);
+            let (final_metadata, quality_score) = merge_all_with_gpt_retry(
                 &folder_files,
                 &folder_name,
                 &book_title,
                 &book_author,
                 google_data,
                 audible_data,
-                api_key_clone.as_deref(),
+                llm_clone.as_ref(),
+                config_clone.as_ref(),
+                book_override,
                 3
             ).await;
             
-            (folder_name, folder_files, final_metadata)
+            let mut final_metadata = fill_series_from_filename(final_metadata, &folder_files);
+            let default_genre = config_clone.as_ref().and_then(|c| c.default_genre.as_deref());
+            let used_default_genre = apply_default_genre(&mut final_metadata, default_genre);
+            
+            (folder_name, folder_files, final_metadata, quality_score, used_default_genre)
         });
         
         handles.push(handle);
@@ -400,62 +1621,16 @@ if series_groups.len() > 5 {
             break;
         }
         
-        if let Ok((folder_name, folder_files, final_metadata)) = handle.await {
-            let audio_files: Vec<AudioFile> = folder_files.iter().map(|f| {
-                let mut changes = HashMap::new();
-                
-                if let Some(old_title) = &f.tags.title {
-                    if old_title != &final_metadata.title {
-                        changes.insert("title".to_string(), FieldChange {
-                            old: old_title.clone(),
-                            new: final_metadata.title.clone(),
-                        });
-                    }
-                }
-                
-                if let Some(old_artist) = &f.tags.artist {
-                    if old_artist != &final_metadata.author {
-                        changes.insert("author".to_string(), FieldChange {
-                            old: old_artist.clone(),
-                            new: final_metadata.author.clone(),
-                        });
-                    }
-                }
-                
-                if let Some(narrator) = &final_metadata.narrator {
-                    changes.insert("narrator".to_string(), FieldChange {
-                        old: f.tags.comment.clone().unwrap_or_default(),
-                        new: format!("Narrated by {}", narrator),
-                    });
-                }
-                
-                if !final_metadata.genres.is_empty() {
-                    let new_genre = final_metadata.genres.join(", ");
-                    if let Some(old_genre) = &f.tags.genre {
-                        if old_genre != &new_genre {
-                            changes.insert("genre".to_string(), FieldChange {
-                                old: old_genre.clone(),
-                                new: new_genre,
-                            });
-                        }
-                    } else {
-                        changes.insert("genre".to_string(), FieldChange {
-                            old: String::new(),
-                            new: new_genre,
-                        });
-                    }
-                }
-                
-                AudioFile {
-                    id: f.id.clone(),
-                    path: f.path.clone(),
-                    filename: f.filename.clone(),
-                    status: if changes.is_empty() { "unchanged" } else { "changed" }.to_string(),
-                    changes,
-                }
-            }).collect();
+        if let Ok((folder_name, folder_files, final_metadata, quality_score, used_default_genre)) = handle.await {
+            let mut audio_files = build_audio_files(&folder_files, &final_metadata, &templates);
+            if used_default_genre {
+                mark_default_genre(&mut audio_files);
+            }
             
-            let total_changes = audio_files.iter().filter(|f| !f.changes.is_empty()).count();
+            let total_changes = count_changed_files(&audio_files);
+            if summary_only {
+                drop_change_maps(&mut audio_files);
+            }
             
             groups.push(BookGroup {
                 id: group_id.to_string(),
@@ -464,6 +1639,10 @@ if series_groups.len() > 5 {
                 files: audio_files,
                 metadata: final_metadata,
                 total_changes,
+                quality_score,
+                needs_review: quality_score < review_threshold,
+                warnings: mismatch_warnings(&folder_files),
+                cover_path: folder_cover_for(&folder_files),
             });
             
             group_id += 1;
@@ -474,23 +1653,27 @@ if series_groups.len() > 5 {
 }
 let remaining_groups: Vec<_> = folder_map.into_iter().collect();
 
-// Create cache instance for parallel processing
-let cache = crate::cache::MetadataCache::new().ok();
-
 if !remaining_groups.is_empty() {
     println!("🚀 Processing {} groups in parallel (max {} concurrent)", 
              remaining_groups.len(), max_workers);
     
     let semaphore = Arc::new(Semaphore::new(max_workers));
     let mut handles = Vec::new();
+    let mut group_warnings = HashMap::new();
+    let mut group_covers = HashMap::new();
     
     for (folder_name, folder_files) in remaining_groups {
         if is_cancelled() {
             break;
         }
         
-        let api_key_clone = api_key.clone();
+        group_warnings.insert(group_id, mismatch_warnings(&folder_files));
+        group_covers.insert(group_id, folder_cover_for(&folder_files));
+        let llm_clone = llm.clone();
         let config_clone = config.clone();
+        let overrides_clone = Arc::clone(&overrides);
+        let templates_clone = templates.clone();
+        let rules_clone = processed_rules.clone();
         let cache_clone = cache.clone();
         let sem = Arc::clone(&semaphore);
         let group_id_clone = group_id;
@@ -503,216 +1686,93 @@ if !remaining_groups.is_empty() {
             
             let sample_file = find_best_sample_file(&folder_files);
             
+            // Overridden books always go through lookup so the override takes effect
+            let book_override = overrides_clone.get(&folder_name);
+            
             // Check if already processed
-            let already_processed = is_already_processed(&sample_file.tags);
+            let already_processed = book_override.is_none() && is_already_processed(&sample_file.tags, &rules_clone);
             
             if already_processed {
-                let final_metadata = BookMetadata {
-                    title: sample_file.tags.title.clone().unwrap_or_else(|| folder_name.clone()),
-                    subtitle: None,
-                    author: sample_file.tags.artist.clone().unwrap_or_else(|| "Unknown".to_string()),
-                    narrator: sample_file.tags.comment.as_ref()
-                        .and_then(|c| {
-                            if c.starts_with("Narrated by ") {
-                                Some(c.trim_start_matches("Narrated by ").to_string())
-                            } else if c.starts_with("Read by ") {
-                                Some(c.trim_start_matches("Read by ").to_string())
-                            } else {
-                                None
-                            }
-                        }),
-                    series: None,
-                    sequence: None,
-                    genres: sample_file.tags.genre.as_ref()
-                        .map(|g| g.split(',').map(|s| s.trim().to_string()).collect())
-                        .unwrap_or_default(),
-                    publisher: None,
-                    year: sample_file.tags.year.clone(),
-                    description: None,
-                    isbn: None,
-                };
-                
-                let audio_files: Vec<AudioFile> = folder_files.iter().map(|f| {
-                    AudioFile {
-                        id: f.id.clone(),
-                        path: f.path.clone(),
-                        filename: f.filename.clone(),
-                        status: "unchanged".to_string(),
-                        changes: HashMap::new(),
-                    }
-                }).collect();
+                crate::progress::record_cache_hit(&folder_name);
+                let final_metadata = metadata_from_processed_tags(&sample_file.tags, &folder_name, &templates_clone);
+                let audio_files = build_audio_files(&folder_files, &final_metadata, &templates_clone);
+                let total_changes = count_changed_files(&audio_files);
                 
-                return (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, 0);
+                return (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, total_changes, 100);
             }
             
             // Check cache
             let quick_title = sample_file.tags.title.as_deref().unwrap_or(&folder_name);
             let quick_author = sample_file.tags.artist.as_deref().unwrap_or("Unknown");
             
-            if let Some(ref cache_db) = cache_clone {
-                if let Some(cached) = cache_db.get(quick_title, quick_author) {
-                    let final_metadata = cached.final_metadata;
+            if book_override.is_none() {
+                let record_hit = || crate::progress::record_cache_hit(&folder_name);
+                let weights = config_clone.as_ref().map(|c| c.quality_weights.clone()).unwrap_or_default();
+                if let Some((mut final_metadata, quality_score)) = cached_metadata(cache_clone.as_ref(), quick_title, quick_author, &weights, record_hit) {
+                    let default_genre = config_clone.as_ref().and_then(|c| c.default_genre.as_deref());
+                    let used_default_genre = apply_default_genre(&mut final_metadata, default_genre);
                     
-                    let audio_files: Vec<AudioFile> = folder_files.iter().map(|f| {
-                        let mut changes = HashMap::new();
-                        
-                        if let Some(old_title) = &f.tags.title {
-                            if old_title != &final_metadata.title {
-                                changes.insert("title".to_string(), FieldChange {
-                                    old: old_title.clone(),
-                                    new: final_metadata.title.clone(),
-                                });
-                            }
-                        }
-                        
-                        if let Some(old_artist) = &f.tags.artist {
-                            if old_artist != &final_metadata.author {
-                                changes.insert("author".to_string(), FieldChange {
-                                    old: old_artist.clone(),
-                                    new: final_metadata.author.clone(),
-                                });
-                            }
-                        }
-                        
-                        if let Some(narrator) = &final_metadata.narrator {
-                            changes.insert("narrator".to_string(), FieldChange {
-                                old: f.tags.comment.clone().unwrap_or_default(),
-                                new: format!("Narrated by {}", narrator),
-                            });
-                        }
-                        
-                        if !final_metadata.genres.is_empty() {
-                            let new_genre = final_metadata.genres.join(", ");
-                            if let Some(old_genre) = &f.tags.genre {
-                                if old_genre != &new_genre {
-                                    changes.insert("genre".to_string(), FieldChange {
-                                        old: old_genre.clone(),
-                                        new: new_genre,
-                                    });
-                                }
-                            } else {
-                                changes.insert("genre".to_string(), FieldChange {
-                                    old: String::new(),
-                                    new: new_genre,
-                                });
-                            }
-                        }
-                        
-                        AudioFile {
-                            id: f.id.clone(),
-                            path: f.path.clone(),
-                            filename: f.filename.clone(),
-                            status: if changes.is_empty() { "unchanged" } else { "changed" }.to_string(),
-                            changes,
-                        }
-                    }).collect();
+                    let mut audio_files = build_audio_files(&folder_files, &final_metadata, &templates_clone);
+                    if used_default_genre {
+                        mark_default_genre(&mut audio_files);
+                    }
                     
-                    let total_changes = audio_files.iter().filter(|f| !f.changes.is_empty()).count();
+                    let total_changes = count_changed_files(&audio_files);
                     
-                    return (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, total_changes);
+                    return (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, total_changes, quality_score);
                 }
             }
             
             // Full processing
-            let (book_title, book_author) = extract_book_info_with_gpt(
+            crate::progress::set_phase(crate::progress::ScanPhase::ExtractingInfo, &folder_name);
+            let (book_title, book_author) = resolve_book_info(
                 sample_file,
                 &folder_name,
-                api_key_clone.as_deref()
+                llm_clone.as_ref(),
+                book_override,
+                config_clone.as_ref(),
             ).await;
             
-            let audible_data = if let Some(ref cfg) = config_clone {
-                if cfg.audible_enabled && !cfg.audible_cli_path.is_empty() {
-                    crate::audible::search_audible(&book_title, &book_author, &cfg.audible_cli_path)
-                        .await.ok().flatten()
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            
-            let google_data = crate::metadata::fetch_from_google_books(&book_title, &book_author)
-                .await.ok().flatten();
+            let (audible_data, google_data) = lookup_sources(
+                &book_title,
+                &book_author,
+                cache_clone.as_ref(),
+                search_audible_for(config_clone.as_ref(), &book_title, &book_author),
+                crate::metadata::fetch_from_google_books(&book_title, &book_author),
+                &folder_name,
+            ).await;
             
-            let final_metadata = merge_all_with_gpt_retry(
+            crate::progress::set_phase(crate::progress::ScanPhase::Merging, &folder_name);
+            let (final_metadata, quality_score) = merge_all_with_gpt_retry(
                 &folder_files,
                 &folder_name,
                 &book_title,
                 &book_author,
                 google_data,
                 audible_data,
-                api_key_clone.as_deref(),
+                llm_clone.as_ref(),
+                config_clone.as_ref(),
+                book_override,
                 3
             ).await;
             
-            // Cache it
-            if let Some(ref cache_db) = cache_clone {
-                let _ = cache_db.set(&book_title, &book_author, crate::cache::CachedMetadata {
-                    final_metadata: final_metadata.clone(),
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs(),
-                });
-            }
+            let final_metadata = fill_series_from_filename(final_metadata, &folder_files);
             
-            let audio_files: Vec<AudioFile> = folder_files.iter().map(|f| {
-                let mut changes = HashMap::new();
-                
-                if let Some(old_title) = &f.tags.title {
-                    if old_title != &final_metadata.title {
-                        changes.insert("title".to_string(), FieldChange {
-                            old: old_title.clone(),
-                            new: final_metadata.title.clone(),
-                        });
-                    }
-                }
-                
-                if let Some(old_artist) = &f.tags.artist {
-                    if old_artist != &final_metadata.author {
-                        changes.insert("author".to_string(), FieldChange {
-                            old: old_artist.clone(),
-                            new: final_metadata.author.clone(),
-                        });
-                    }
-                }
-                
-                if let Some(narrator) = &final_metadata.narrator {
-                    changes.insert("narrator".to_string(), FieldChange {
-                        old: f.tags.comment.clone().unwrap_or_default(),
-                        new: format!("Narrated by {}", narrator),
-                    });
-                }
-                
-                if !final_metadata.genres.is_empty() {
-                    let new_genre = final_metadata.genres.join(", ");
-                    if let Some(old_genre) = &f.tags.genre {
-                        if old_genre != &new_genre {
-                            changes.insert("genre".to_string(), FieldChange {
-                                old: old_genre.clone(),
-                                new: new_genre,
-                            });
-                        }
-                    } else {
-                        changes.insert("genre".to_string(), FieldChange {
-                            old: String::new(),
-                            new: new_genre,
-                        });
-                    }
-                }
-                
-                AudioFile {
-                    id: f.id.clone(),
-                    path: f.path.clone(),
-                    filename: f.filename.clone(),
-                    status: if changes.is_empty() { "unchanged" } else { "changed" }.to_string(),
-                    changes,
-                }
-            }).collect();
+            cache_metadata(cache_clone.as_ref(), &book_title, &book_author, &final_metadata);
+            
+            // Applied after caching (and again on cache hits) so the cache never stores the default
+            let mut final_metadata = final_metadata;
+            let default_genre = config_clone.as_ref().and_then(|c| c.default_genre.as_deref());
+            let used_default_genre = apply_default_genre(&mut final_metadata, default_genre);
+            
+            let mut audio_files = build_audio_files(&folder_files, &final_metadata, &templates_clone);
+            if used_default_genre {
+                mark_default_genre(&mut audio_files);
+            }
             
-            let total_changes = audio_files.iter().filter(|f| !f.changes.is_empty()).count();
+            let total_changes = count_changed_files(&audio_files);
             
-            (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, total_changes)
+            (group_id_clone, folder_name, GroupType::Chapters, audio_files, final_metadata, total_changes, quality_score)
         });
         
         handles.push(handle);
@@ -724,7 +1784,10 @@ if !remaining_groups.is_empty() {
             break;
         }
         
-        if let Ok((id, name, group_type, files, metadata, total_changes)) = handle.await {
+        if let Ok((id, name, group_type, mut files, metadata, total_changes, quality_score)) = handle.await {
+            if summary_only {
+                drop_change_maps(&mut files);
+            }
             groups.push(BookGroup {
                 id: id.to_string(),
                 group_name: name,
@@ -732,11 +1795,16 @@ if !remaining_groups.is_empty() {
                 files,
                 metadata,
                 total_changes,
+                quality_score,
+                needs_review: quality_score < review_threshold,
+                warnings: group_warnings.remove(&id).unwrap_or_default(),
+                cover_path: group_covers.remove(&id).flatten(),
             });
         }
     }
 }
 
+groups.extend(locked_groups);
 groups.sort_by(|a, b| a.group_name.cmp(&b.group_name));
 
 let elapsed = start_time.elapsed();
@@ -745,76 +1813,463 @@ println!("\n⚡ Performance: {:.1} files/sec, total time: {:?}", rate, elapsed);
 
     groups
 }
-// Add this function before extract_book_info_with_gpt
-fn find_best_sample_file(files: &[RawFileData]) -> &RawFileData {
-    for file in files {
-        if let Some(title) = &file.tags.title {
-            let lower = title.to_lowercase();
-            if lower.starts_with("track") || 
-               lower.starts_with("chapter") || 
-               lower.starts_with("part") ||
-               lower.chars().filter(|c| c.is_numeric()).count() > 3 {
-                continue;
-            }
-            if title.len() > 10 {
-                return file;
-            }
+/// User-configurable text written into the comment field
+#[derive(Debug, Clone)]
+pub struct CommentTemplates {
+    pub narrator: String,
+    pub description: String,
+}
+
+impl Default for CommentTemplates {
+    fn default() -> Self {
+        Self {
+            narrator: crate::config::DEFAULT_NARRATOR_COMMENT_TEMPLATE.to_string(),
+            description: crate::config::DEFAULT_DESCRIPTION_TEMPLATE.to_string(),
         }
     }
-    &files[0]
 }
 
-fn extract_book_number_from_folder(folder_name: &str) -> Option<String> {
-    use regex::Regex;
+impl CommentTemplates {
+    pub fn from_config(config: Option<&crate::config::Config>) -> Self {
+        match config {
+            Some(c) => Self {
+                narrator: c.narrator_comment_template.clone(),
+                description: c.description_template.clone(),
+            },
+            None => Self::default(),
+        }
+    }
     
-    let patterns = [
-        r"(?i)\(Book\s*#?(\d+)\)",
-        r"(?i)\[Book\s*#?(\d+)\]",
-        r"(?i)Book\s*#?(\d+)",
-        r"(?i)#(\d+)",
-    ];
+    pub fn narrator_comment(&self, narrator: &str) -> String {
+        self.narrator.replace("{narrator}", narrator)
+    }
     
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(caps) = re.captures(folder_name) {
-                if let Some(num) = caps.get(1) {
-                    return Some(num.as_str().to_string());
+    pub fn description_comment(&self, description: &str) -> String {
+        self.description.replace("{description}", description)
+    }
+    
+    /// Recover the narrator from a comment written with this template (or
+    /// the legacy "Narrated by"/"Read by" forms).
+    pub fn narrator_from_comment(&self, comment: &str) -> Option<String> {
+        if let Some((prefix, suffix)) = self.narrator.split_once("{narrator}") {
+            if !prefix.is_empty() || !suffix.is_empty() {
+                if let Some(name) = comment.strip_prefix(prefix).and_then(|c| c.strip_suffix(suffix)) {
+                    if !name.trim().is_empty() {
+                        return Some(name.trim().to_string());
+                    }
                 }
             }
         }
+        
+        ["Narrated by ", "Read by "].iter()
+            .find_map(|prefix| comment.strip_prefix(prefix))
+            .map(|name| name.to_string())
     }
-    
-    None
 }
 
-async fn extract_book_info_with_gpt(
-    sample_file: &RawFileData,
+/// Audible search for a book, or no result when Audible is switched off
+async fn search_audible_for(
+    config: Option<&crate::config::Config>,
+    title: &str,
+    author: &str,
+) -> Result<Option<crate::audible::AudibleMetadata>> {
+    match config.filter(|c| c.audible_enabled && !c.audible_cli_path.is_empty()) {
+        Some(cfg) => crate::audible::search_audible_regions(title, author, &cfg.audible_cli_path, &cfg.audible_regions, cfg.audible_min_confidence).await,
+        None => Ok(None),
+    }
+}
+
+/// The merged metadata cached for a book and its quality score, if any.
+/// The cache keeps no Audible data, so the score is the one a merge without
+/// it would get. `record_hit` runs only on a hit, so scan progress counts
+/// exactly the groups the cache served.
+fn cached_metadata(
+    cache: Option<&crate::cache::MetadataCache>,
+    title: &str,
+    author: &str,
+    weights: &crate::config::QualityWeights,
+    record_hit: impl FnOnce(),
+) -> Option<(BookMetadata, u32)> {
+    let cached = cache?.get(title, author)?;
+    record_hit();
+    let quality_score = validate_metadata_quality(&cached.final_metadata, title, &None, weights);
+    Some((cached.final_metadata, quality_score))
+}
+
+/// Store a finished merge so the next scan of the book skips lookup and GPT
+fn cache_metadata(cache: Option<&crate::cache::MetadataCache>, title: &str, author: &str, metadata: &BookMetadata) {
+    if let Some(cache_db) = cache {
+        let _ = cache_db.set(title, author, crate::cache::CachedMetadata {
+            final_metadata: metadata.clone(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        });
+    }
+}
+
+/// Audible and Google Books data for a book. A recent negative-cache entry
+/// skips both; a new one is only recorded when every source answered with
+/// no match, since a timeout or API error may well succeed next time.
+async fn lookup_sources(
+    title: &str,
+    author: &str,
+    cache: Option<&crate::cache::MetadataCache>,
+    audible: impl std::future::Future<Output = Result<Option<crate::audible::AudibleMetadata>>>,
+    google: impl std::future::Future<Output = Result<Option<crate::metadata::BookMetadata>>>,
     folder_name: &str,
-    api_key: Option<&str>
-) -> (String, String) {
-    let api_key = match api_key {
-        Some(key) if !key.is_empty() => key,
-        _ => {
-            return (
-                sample_file.tags.title.clone().unwrap_or_else(|| folder_name.to_string()),
-                sample_file.tags.artist.clone().unwrap_or_else(|| String::from("Unknown"))
-            );
+) -> (Option<crate::audible::AudibleMetadata>, Option<crate::metadata::BookMetadata>) {
+    if cache.is_some_and(|c| c.is_known_missing(title, author)) {
+        println!("   💾 Negative cache hit - skipping Audible/Google for '{}'", title);
+        return (None, None);
+    }
+    
+    crate::progress::set_phase(crate::progress::ScanPhase::QueryingAudible, folder_name);
+    let audible = audible.await;
+    crate::progress::set_phase(crate::progress::ScanPhase::QueryingGoogle, folder_name);
+    let google = google.await;
+    
+    if matches!((&audible, &google), (Ok(None), Ok(None))) {
+        if let Some(cache_db) = cache {
+            let _ = cache_db.set_missing(title, author);
+        }
+    }
+    for error in [audible.as_ref().err(), google.as_ref().err()].into_iter().flatten() {
+        println!("   ⚠️  Lookup failed for '{}', not caching it as missing: {}", title, error);
+    }
+    
+    (audible.ok().flatten(), google.ok().flatten())
+}
+
+/// Diff a file's existing tags against the merged metadata.
+pub fn build_changes(file: &RawFileData, metadata: &BookMetadata, templates: &CommentTemplates) -> HashMap<String, FieldChange> {
+    let mut changes = HashMap::new();
+    
+    if let Some(old_title) = &file.tags.title {
+        if old_title != &metadata.title {
+            changes.insert("title".to_string(), FieldChange {
+                old: old_title.clone(),
+                new: metadata.title.clone(),
+            });
+        }
+    }
+    
+    if let Some(old_artist) = &file.tags.artist {
+        if old_artist != &metadata.author {
+            changes.insert("author".to_string(), FieldChange {
+                old: old_artist.clone(),
+                new: metadata.author.clone(),
+            });
         }
+    }
+    
+    // Sort names follow their field: a rewritten title or author gets a fresh
+    // one, a tagged field missing its sort name gets one, and an existing sort
+    // tag on a field left alone is kept as it is
+    let sort_for = |field: &str, current: &Option<String>, sort: &Option<String>, value: &str| {
+        let refresh = changes.contains_key(field) || (current.is_some() && sort.is_none());
+        refresh.then_some(value).filter(|v| !v.trim().is_empty())
     };
+    let title_sort = sort_for("title", &file.tags.title, &file.tags.title_sort, &metadata.title)
+        .map(crate::metadata::sort_title);
+    let author_sort = sort_for("author", &file.tags.artist, &file.tags.author_sort, &metadata.author)
+        .map(crate::metadata::sort_author);
+    diff_optional(&mut changes, "title_sort", &file.tags.title_sort, &title_sort);
+    diff_optional(&mut changes, "author_sort", &file.tags.author_sort, &author_sort);
+    
+    // The narrator's name goes to the narrator fields, co-narrators as
+    // separate values; the templated line goes to the comment
+    let narrators = metadata.narrator_names();
+    let narrator_field = if narrators.len() > 1 { "narrators" } else { "narrator" };
+    let new_narrators = (!narrators.is_empty()).then(|| narrators.join(crate::tags::NARRATOR_SEPARATOR));
+    diff_optional(&mut changes, narrator_field, &file.tags.narrator, &new_narrators);
+    let narrator_comment = (!narrators.is_empty()).then(|| templates.narrator_comment(&narrators.join(", ")));
+    
+    if !metadata.genres.is_empty() {
+        let new_genre = metadata.genres.join(", ");
+        if let Some(old_genre) = &file.tags.genre {
+            if old_genre != &new_genre {
+                changes.insert("genre".to_string(), FieldChange {
+                    old: old_genre.clone(),
+                    new: new_genre,
+                });
+            }
+        } else {
+            changes.insert("genre".to_string(), FieldChange {
+                old: String::new(),
+                new: new_genre,
+            });
+        }
+    }
+    
+    diff_optional(&mut changes, "subtitle", &file.tags.subtitle, &metadata.subtitle);
+    diff_optional(&mut changes, "publisher", &file.tags.publisher, &metadata.publisher);
+    // There's one comment field: the description takes it when there is one
+    let description_comment = metadata.description.as_deref().map(|d| templates.description_comment(d));
+    if description_comment.is_some() {
+        diff_optional(&mut changes, "description", &file.tags.comment, &description_comment);
+    } else {
+        diff_optional(&mut changes, "narrator_comment", &file.tags.comment, &narrator_comment);
+    }
+    diff_optional(&mut changes, "year", &file.tags.year, &metadata.year);
+    diff_optional(&mut changes, "isbn", &file.tags.isbn, &metadata.isbn);
+    diff_optional(&mut changes, "asin", &file.tags.asin, &metadata.asin);
+    diff_optional(&mut changes, "series", &file.tags.series, &metadata.series);
+    diff_optional(&mut changes, "sequence", &file.tags.sequence, &metadata.sequence);
+    
+    changes
+}
+
+fn diff_optional(
+    changes: &mut HashMap<String, FieldChange>,
+    field: &str,
+    old: &Option<String>,
+    new: &Option<String>,
+) {
+    if let Some(new_value) = new {
+        if old.as_deref() != Some(new_value.as_str()) {
+            changes.insert(field.to_string(), FieldChange {
+                old: old.clone().unwrap_or_default(),
+                new: new_value.clone(),
+            });
+        }
+    }
+}
+
+pub fn build_audio_files(files: &[RawFileData], metadata: &BookMetadata, templates: &CommentTemplates) -> Vec<AudioFile> {
+    files.iter().map(|f| {
+        if f.tags.read_only {
+            return AudioFile {
+                id: f.id.clone(),
+                path: f.path.clone(),
+                filename: f.filename.clone(),
+                status: READ_ONLY_STATUS.to_string(),
+                changes: HashMap::new(),
+            };
+        }
+        let changes = build_changes(f, metadata, templates);
+        AudioFile {
+            id: f.id.clone(),
+            path: f.path.clone(),
+            filename: f.filename.clone(),
+            status: if changes.is_empty() { "unchanged" } else { "changed" }.to_string(),
+            changes,
+        }
+    }).collect()
+}
+
+/// Change-diff entry flagging that the genre came from config.default_genre.
+/// tags.rs ignores it on write.
+pub const DEFAULT_GENRE_CHANGE: &str = "genre_default";
+
+/// Canonical spelling of an approved genre, matched case-insensitively
+pub fn approved_genre(genre: &str) -> Option<&'static str> {
+    crate::genres::APPROVED_GENRES.iter()
+        .find(|g| g.eq_ignore_ascii_case(genre.trim()))
+        .copied()
+}
+
+/// Give a book with no genres the configured default. Returns true if applied.
+fn apply_default_genre(metadata: &mut BookMetadata, default_genre: Option<&str>) -> bool {
+    if !metadata.genres.is_empty() {
+        return false;
+    }
+    match default_genre.and_then(approved_genre) {
+        Some(genre) => {
+            println!("   🏷️  No genres found - using default '{}'", genre);
+            metadata.genres = vec![genre.to_string()];
+            true
+        }
+        None => false,
+    }
+}
+
+/// Merge candidate genres from each source in priority order, keeping only
+/// approved genres (at most three). Empty when no source had an approved
+/// genre, so the default genre can still apply.
+fn genres_from_sources(
+    order: &[crate::config::GenreSource],
+    candidates: impl Fn(crate::config::GenreSource) -> Vec<String>,
+) -> Vec<String> {
+    let merged: Vec<String> = order.iter()
+        .flat_map(|&source| candidates(source))
+        .filter_map(|genre| crate::genres::map_genre_basic(&genre))
+        .collect();
+    if merged.is_empty() {
+        return merged;
+    }
+    crate::genres::enforce_genre_policy_basic(&merged)
+}
+
+/// Replace the merge result's genres with those picked per `genre_sources`
+fn apply_genre_sources(
+    metadata: &mut BookMetadata,
+    sources: &MergeSources,
+    files: &[RawFileData],
+    order: &[crate::config::GenreSource],
+) {
+    use crate::config::GenreSource;
+    
+    let merged_genres = std::mem::take(&mut metadata.genres);
+    metadata.genres = genres_from_sources(order, |source| match source {
+        GenreSource::Gpt => merged_genres.clone(),
+        GenreSource::Audible => sources.audible.as_ref().map(|a| a.genres.clone()).unwrap_or_default(),
+        GenreSource::Google => sources.google.as_ref().map(|g| g.genres.clone()).unwrap_or_default(),
+        GenreSource::OpenLibrary => sources.open_library.as_ref().map(|o| o.genres.clone()).unwrap_or_default(),
+        GenreSource::FileTags => files.iter()
+            .filter_map(|f| f.tags.genre.as_deref())
+            .flat_map(|g| g.split([',', ';']).map(|s| s.trim().to_string()))
+            .collect(),
+    });
+}
+
+fn mark_default_genre(files: &mut [AudioFile]) {
+    for file in files {
+        if let Some(genre) = file.changes.get("genre").map(|c| c.new.clone()) {
+            file.changes.insert(DEFAULT_GENRE_CHANGE.to_string(), FieldChange {
+                old: String::new(),
+                new: format!("Default genre applied: {}", genre),
+            });
+        }
+    }
+}
+
+/// A summary-only scan keeps each file's status but not its change map, so
+/// a large library's diffs are never all held at once
+fn drop_change_maps(files: &mut [AudioFile]) {
+    for file in files {
+        file.changes = HashMap::new();
+    }
+}
+
+pub fn count_changed_files(files: &[AudioFile]) -> usize {
+    files.iter().filter(|f| !f.changes.is_empty()).count()
+}
+
+/// Rebuild metadata from tags this app already wrote.
+pub fn metadata_from_processed_tags(tags: &FileTags, folder_name: &str, templates: &CommentTemplates) -> BookMetadata {
+    BookMetadata {
+        title: tags.title.clone().unwrap_or_else(|| folder_name.to_string()),
+        subtitle: None,
+        author: tags.artist.clone().unwrap_or_else(|| "Unknown".to_string()),
+        narrator: tags.narrator.as_ref()
+            .and_then(|n| n.split(crate::tags::NARRATOR_SEPARATOR).next())
+            .map(str::to_string)
+            .or_else(|| tags.comment.as_ref().and_then(|c| templates.narrator_from_comment(c))),
+        narrators: tags.narrator.as_ref()
+            .map(|n| n.split(crate::tags::NARRATOR_SEPARATOR).map(str::to_string).collect::<Vec<_>>())
+            .filter(|names| names.len() > 1)
+            .unwrap_or_default(),
+        series: None,
+        sequence: None,
+        genres: tags.genre.as_ref()
+            .map(|g| g.split(',').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default(),
+        publisher: None,
+        year: tags.year.clone(),
+        description: None,
+        isbn: None,
+        asin: tags.asin.clone(),
+    }
+}
+
+// Add this function before extract_book_info_with_gpt
+// A tag this far from the group's majority value marks a stray file
+const OUTLIER_SIMILARITY: f64 = 0.5;
+
+/// Files whose artist, album or title disagrees with the rest of the group,
+/// usually a stray from another book. A field is only judged when more than
+/// half the group shares one value, so chapter titles that all differ pass.
+fn mismatched_files(files: &[RawFileData]) -> Vec<(usize, String)> {
+    let mut found: Vec<(usize, String)> = Vec::new();
+    if files.len() < 3 {
+        return found;
+    }
+    
+    let fields: [(&str, fn(&FileTags) -> Option<&String>); 3] = [
+        ("artist", |t| t.artist.as_ref()),
+        ("album", |t| t.album.as_ref()),
+        ("title", |t| t.title.as_ref()),
+    ];
+    for (field, get) in fields {
+        let values: Vec<Option<String>> = files.iter()
+            .map(|f| get(&f.tags).map(|v| crate::metadata::normalize_for_match(v)).filter(|v| !v.is_empty()))
+            .collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values.iter().flatten() {
+            *counts.entry(value.as_str()).or_default() += 1;
+        }
+        let Some((majority, count)) = counts.into_iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0))) else {
+            continue;
+        };
+        if count * 2 <= files.len() {
+            continue;
+        }
+        
+        for (idx, value) in values.iter().enumerate() {
+            let Some(value) = value else { continue };
+            if found.iter().any(|(i, _)| *i == idx) || crate::metadata::similarity(value, majority) >= OUTLIER_SIMILARITY {
+                continue;
+            }
+            let original = get(&files[idx].tags).map(String::as_str).unwrap_or_default();
+            found.push((idx, format!(
+                "{}: {} \"{}\" doesn't match the rest of the group",
+                files[idx].filename, field, original
+            )));
+        }
+    }
     
+    found.sort_by_key(|(idx, _)| *idx);
+    found
+}
+
+fn mismatch_warnings(files: &[RawFileData]) -> Vec<String> {
+    mismatched_files(files).into_iter().map(|(_, message)| message).collect()
+}
+
+fn find_best_sample_file(files: &[RawFileData]) -> &RawFileData {
+    // A stray file would send the lookup after the wrong book
+    let outliers: Vec<usize> = mismatched_files(files).into_iter().map(|(idx, _)| idx).collect();
+    for (idx, file) in files.iter().enumerate() {
+        if outliers.contains(&idx) {
+            continue;
+        }
+        if let Some(title) = &file.tags.title {
+            let lower = title.to_lowercase();
+            if lower.starts_with("track") || 
+               lower.starts_with("chapter") || 
+               lower.starts_with("part") ||
+               lower.chars().filter(|c| c.is_numeric()).count() > 3 {
+                continue;
+            }
+            if title.len() > 10 {
+                return file;
+            }
+        }
+    }
+    (0..files.len())
+        .find(|idx| !outliers.contains(idx))
+        .map(|idx| &files[idx])
+        .unwrap_or(&files[0])
+}
+
+/// The exact prompt extract_book_info_with_gpt sends, shared with preview_extract_prompt
+fn extract_prompt_for(sample_file: &RawFileData, folder_name: &str, config: Option<&crate::config::Config>) -> String {
     let clean_title = sample_file.tags.title.as_ref()
         .map(|t| t.replace(" - Part 1", "").replace(" - Part 2", "").trim().to_string());
     let clean_artist = sample_file.tags.artist.as_ref()
         .map(|a| a.to_string());
     
-    let book_number = extract_book_number_from_folder(folder_name);
+    let series_patterns = SeriesPatterns::from_config(config);
+    let book_number = series_patterns.parse(folder_name).and_then(|found| found.sequence);
     let book_hint = if let Some(num) = &book_number {
         format!("\nBOOK NUMBER DETECTED: This is Book #{} in a series", num)
     } else {
         String::new()
     };
     
-    let prompt = format!(
+    format!(
 
 r#"You are extracting the actual book title and author from audiobook tags.
 
@@ -853,10 +2308,29 @@ JSON:"#,
         clean_artist,
         sample_file.tags.album,
         book_hint
-    );
+    )
+}
+
+async fn extract_book_info_with_gpt(
+    sample_file: &RawFileData,
+    folder_name: &str,
+    llm: Option<&crate::llm::LlmAccess>,
+    config: Option<&crate::config::Config>,
+) -> (String, String) {
+    let llm = match llm {
+        Some(llm) => llm,
+        None => {
+            return (
+                sample_file.tags.title.clone().unwrap_or_else(|| folder_name.to_string()),
+                sample_file.tags.artist.clone().unwrap_or_else(|| String::from("Unknown"))
+            );
+        }
+    };
+    
+    let prompt = extract_prompt_for(sample_file, folder_name, config);
     
     for attempt in 1..=2 {
-        match call_gpt_extract_book_info(&prompt, api_key).await {
+        match call_gpt_extract_book_info(&prompt, llm, config).await {
             Ok(json_str) => {
                 match serde_json::from_str::<serde_json::Value>(&json_str) {
                     Ok(json) => {
@@ -890,7 +2364,7 @@ JSON:"#,
             }
             Err(e) => {
                 println!("   ⚠️  GPT extraction error (attempt {}): {}", attempt, e);
-                if attempt == 2 {
+                if attempt == 2 || e.downcast_ref::<GptError>().is_some() {
                     return (
                         sample_file.tags.title.clone().unwrap_or_else(|| folder_name.to_string()),
                         sample_file.tags.artist.clone().unwrap_or_else(|| String::from("Unknown"))
@@ -906,78 +2380,73 @@ JSON:"#,
     )
 }
 
-async fn merge_all_with_gpt(
-    files: &[RawFileData],
-    folder_name: &str,
-    extracted_title: &str,
-    extracted_author: &str,
-    google_data: Option<crate::metadata::BookMetadata>,
-    audible_data: Option<crate::audible::AudibleMetadata>,
-    api_key: Option<&str>
-) -> BookMetadata {
-    let sample_comments: Vec<String> = files.iter()
-        .filter_map(|f| f.tags.comment.clone())
-        .collect();
-    
-    // PRE-EXTRACT reliable year from sources (don't let GPT override this)
-    let reliable_year = audible_data.as_ref()
+/// Year from Audible/Google, which GPT must not override
+fn source_year(
+    google_data: Option<&crate::metadata::BookMetadata>,
+    audible_data: Option<&crate::audible::AudibleMetadata>,
+) -> Option<String> {
+    audible_data
         .and_then(|d| d.release_date.clone())
         .and_then(|date| {
             // Extract just the year from date strings like "2021-01-02"
             date.split('-').next().map(|s| s.to_string())
         })
         .or_else(|| {
-            google_data.as_ref()
+            google_data
                 .and_then(|d| d.publish_date.clone())
                 .and_then(|date| {
                     date.split('-').next().map(|s| s.to_string())
                 })
-        });
-    
-    let google_summary = if let Some(ref data) = google_data {
-        format!(
-            "Title: {:?}, Authors: {:?}, Publisher: {:?}, Date: {:?}",
-            data.title, data.authors, data.publisher, data.publish_date
-        )
-    } else {
-        "No data".to_string()
-    };
-    
-    let audible_summary = if let Some(ref data) = audible_data {
-        format!(
-            "Title: {:?}, Authors: {:?}, Narrators: {:?}, Series: {:?}, Publisher: {:?}, Release Date: {:?}, ASIN: {:?}",
-            data.title, data.authors, data.narrators, data.series, data.publisher, data.release_date, data.asin
-        )
-    } else {
-        "No data".to_string()
-    };
+        })
+}
+
+/// The extraction prompt a scan would send for this file, without calling the LLM
+pub fn preview_extract_prompt(file: &RawFileData, folder_name: &str, config: Option<&crate::config::Config>) -> String {
+    extract_prompt_for(file, folder_name, config)
+}
+
+/// Runs the same Audible/Google lookups as a scan and renders the merge
+/// prompt without calling the LLM. Extraction needs the LLM, so the title
+/// and author come from the file's tags (the no-API-key fallback).
+pub async fn preview_merge_prompt(file: &RawFileData, folder_name: &str) -> String {
+    let config = crate::config::load_config().ok();
+    let title = file.tags.title.clone().unwrap_or_else(|| folder_name.to_string());
+    let author = file.tags.artist.clone().unwrap_or_else(|| String::from("Unknown"));
     
-    let api_key = match api_key {
-        Some(key) if !key.is_empty() => key,
-        _ => {
-            return BookMetadata {
-                title: extracted_title.to_string(),
-                subtitle: None,
-                author: extracted_author.to_string(),
-                narrator: None,
-                series: None,
-                sequence: None,
-                genres: vec![],
-                publisher: google_data.as_ref().and_then(|d| d.publisher.clone()),
-                year: reliable_year,
-                description: google_data.as_ref().and_then(|d| d.description.clone()),
-                isbn: None,
-            };
+    let audible_data = match config.as_ref() {
+        Some(cfg) if cfg.audible_enabled && !cfg.audible_cli_path.is_empty() => {
+            crate::audible::search_audible_regions(&title, &author, &cfg.audible_cli_path, &cfg.audible_regions, cfg.audible_min_confidence)
+                .await.ok().flatten()
         }
+        _ => None,
     };
+    let google_data = crate::metadata::fetch_from_google_books(&title, &author)
+        .await.ok().flatten();
     
-    let year_instruction = if let Some(ref year) = reliable_year {
-        format!("CRITICAL: Use EXACTLY this year: {} (from Audible/Google Books - DO NOT CHANGE)", year)
-    } else {
-        "year: If not found in sources, return null".to_string()
-    };
-    
-    let prompt = format!(
+    merge_prompt_for(
+        std::slice::from_ref(file),
+        folder_name,
+        &title,
+        &author,
+        google_data.as_ref(),
+        audible_data.as_ref(),
+        config.as_ref().and_then(|c| c.merge_prompt_template.as_deref()),
+        None,
+    )
+}
+
+/// The built-in merge prompt. Sources arrive pre-summarized so this stays a
+/// pure function of its inputs.
+fn build_merge_prompt(
+    folder: &str,
+    title: &str,
+    author: &str,
+    google: &str,
+    audible: &str,
+    comments: &[String],
+    year_instruction: &str,
+) -> String {
+    format!(
 r#"
 You are an audiobook metadata specialist. Combine information from all sources to produce the most accurate metadata.
 
@@ -1037,98 +2506,311 @@ Return ONLY valid JSON:
 }}
 
 JSON:"#,
-        folder_name,
-        extracted_title,
-        extracted_author,
-        google_summary,
-        audible_summary,
-        sample_comments,
+        folder,
+        title,
+        author,
+        google,
+        audible,
+        comments,
         crate::genres::APPROVED_GENRES.join(", "),
         year_instruction
-    );
-    
-    match call_gpt_merge_metadata(&prompt, api_key).await {
-        Ok(json_str) => {
-            match serde_json::from_str::<BookMetadata>(&json_str) {
-                Ok(mut metadata) => {
-                    // FORCE the reliable year back in (in case GPT changed it)
-                    if let Some(year) = reliable_year {
-                        metadata.year = Some(year);
-                    }
-                    
-                    println!("   ✅ Final: title='{}', author='{}', narrator={:?}", 
-                        metadata.title, metadata.author, metadata.narrator);
-                    println!("            genres={:?}, publisher={:?}, year={:?}",
-                        metadata.genres, metadata.publisher, metadata.year);
-                    metadata
-                }
-                Err(e) => {
-                    println!("   ⚠️  GPT parse error: {}", e);
-                    println!("   ⚠️  Using fallback with available data");
-                    
-                    BookMetadata {
-                        title: extracted_title.to_string(),
-                        subtitle: google_data.as_ref().and_then(|d| d.subtitle.clone()),
-                        author: extracted_author.to_string(),
-                        narrator: audible_data.as_ref()
-                            .and_then(|d| d.narrators.first().cloned()),
-                        series: audible_data.as_ref()
-                            .and_then(|d| d.series.first().map(|s| s.name.clone())),
-                        sequence: audible_data.as_ref()
-                            .and_then(|d| d.series.first().and_then(|s| s.position.clone())),
-                        genres: google_data.as_ref()
-                            .map(|d| d.genres.clone())
-                            .unwrap_or_default(),
-                        publisher: google_data.as_ref().and_then(|d| d.publisher.clone())
-                            .or_else(|| audible_data.as_ref().and_then(|d| d.publisher.clone())),
-                        year: reliable_year,
-                        description: google_data.as_ref().and_then(|d| d.description.clone())
-                            .or_else(|| audible_data.as_ref().and_then(|d| d.description.clone())),
-                        isbn: google_data.as_ref()
-                            .and_then(|d| d.isbn.clone()),
-                    }
-                }
-            }
-        }
-        Err(e) => {
-            println!("   ⚠️  GPT merge error: {}", e);
-            println!("   ⚠️  Using fallback with available data");
-            
-            BookMetadata {
-                title: extracted_title.to_string(),
-                subtitle: google_data.as_ref().and_then(|d| d.subtitle.clone()),
-                author: extracted_author.to_string(),
-                narrator: audible_data.as_ref()
-                    .and_then(|d| d.narrators.first().cloned()),
-                series: audible_data.as_ref()
-                    .and_then(|d| d.series.first().map(|s| s.name.clone())),
-                sequence: audible_data.as_ref()
-                    .and_then(|d| d.series.first().and_then(|s| s.position.clone())),
-                genres: google_data.as_ref()
-                    .map(|d| d.genres.clone())
-                    .unwrap_or_default(),
-                publisher: google_data.as_ref().and_then(|d| d.publisher.clone())
-                    .or_else(|| audible_data.as_ref().and_then(|d| d.publisher.clone())),
-                year: reliable_year,
-                description: google_data.as_ref().and_then(|d| d.description.clone())
-                    .or_else(|| audible_data.as_ref().and_then(|d| d.description.clone())),
-                isbn: google_data.as_ref()
-                    .and_then(|d| d.isbn.clone()),
-            }
-        }
-    }
+    )
 }
 
-async fn call_gpt_extract_book_info(prompt: &str, api_key: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-    
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&serde_json::json!({
-            "model": "gpt-5-nano",
-            "messages": [
+/// The exact prompt merge_all_with_gpt sends, shared with preview_merge_prompt
+fn merge_prompt_for(
+    files: &[RawFileData],
+    folder_name: &str,
+    extracted_title: &str,
+    extracted_author: &str,
+    google_data: Option<&crate::metadata::BookMetadata>,
+    audible_data: Option<&crate::audible::AudibleMetadata>,
+    prompt_template: Option<&str>,
+    confirmed: Option<&BookOverride>,
+) -> String {
+    let sample_comments: Vec<String> = files.iter()
+        .filter_map(|f| f.tags.comment.clone())
+        .collect();
+    
+    let reliable_year = source_year(google_data, audible_data);
+    
+    let google_summary = if let Some(data) = google_data {
+        format!(
+            "Title: {:?}, Authors: {:?}, Publisher: {:?}, Date: {:?}",
+            data.title, data.authors, data.publisher, data.publish_date
+        )
+    } else {
+        "No data".to_string()
+    };
+    
+    let audible_summary = if let Some(data) = audible_data {
+        format!(
+            "Title: {:?}, Authors: {:?}, Narrators: {:?}, Series: {:?}, Publisher: {:?}, Release Date: {:?}, ASIN: {:?}",
+            data.title, data.authors, data.narrators, data.series, data.publisher, data.release_date, data.asin
+        )
+    } else {
+        "No data".to_string()
+    };
+    
+    let year_instruction = if let Some(ref year) = reliable_year {
+        format!("CRITICAL: Use EXACTLY this year: {} (from Audible/Google Books - DO NOT CHANGE)", year)
+    } else {
+        "year: If not found in sources, return null".to_string()
+    };
+    
+    let custom_prompt = prompt_template.and_then(|template| render_merge_prompt_template(template, &[
+        ("folder", folder_name.to_string()),
+        ("title", extracted_title.to_string()),
+        ("author", extracted_author.to_string()),
+        ("google", google_summary.clone()),
+        ("audible", audible_summary.clone()),
+        ("comments", format!("{:?}", sample_comments)),
+        ("genres", crate::genres::APPROVED_GENRES.join(", ")),
+        ("year_instruction", year_instruction.clone()),
+    ]));
+    
+    let prompt = custom_prompt.unwrap_or_else(|| build_merge_prompt(
+        folder_name,
+        extracted_title,
+        extracted_author,
+        &google_summary,
+        &audible_summary,
+        &sample_comments,
+        &year_instruction,
+    ));
+    
+    // Movement frames came from a deliberate tagger, not a guess
+    let movement_series = files.iter()
+        .filter(|f| f.tags.series_from_movement())
+        .find_map(|f| f.tags.series.as_ref().map(|s| (s, f.tags.sequence.as_deref())));
+    let prompt = match movement_series {
+        Some((series, sequence)) => format!(
+            "{}\nAUTHORITATIVE (from the file's movement tags, keep unless the sources clearly contradict it): series='{}', sequence='{}'\n",
+            prompt,
+            series,
+            sequence.unwrap_or("unknown")
+        ),
+        None => prompt,
+    };
+    
+    let prompt = match confirmed {
+        Some(confirmed) => format!(
+            "{}\nAUTHORITATIVE (confirmed by the user, do not change): title='{}', author='{}'\n",
+            prompt,
+            confirmed.title.as_deref().unwrap_or(extracted_title),
+            confirmed.author.as_deref().unwrap_or(extracted_author)
+        ),
+        None => prompt,
+    };
+    
+    prompt
+}
+
+async fn merge_all_with_gpt(
+    files: &[RawFileData],
+    folder_name: &str,
+    extracted_title: &str,
+    extracted_author: &str,
+    google_data: Option<crate::metadata::BookMetadata>,
+    audible_data: Option<crate::audible::AudibleMetadata>,
+    llm: Option<&crate::llm::LlmAccess>,
+    config: Option<&crate::config::Config>,
+    prompt_template: Option<&str>,
+    confirmed: Option<&BookOverride>,
+    model: Option<&str>,
+) -> BookMetadata {
+    // PRE-EXTRACT reliable year from sources (don't let GPT override this)
+    let reliable_year = source_year(google_data.as_ref(), audible_data.as_ref());
+    
+    let llm = match llm {
+        Some(llm) => llm,
+        None => {
+            return BookMetadata {
+                title: extracted_title.to_string(),
+                subtitle: None,
+                author: extracted_author.to_string(),
+                narrator: None,
+                narrators: vec![],
+                series: None,
+                sequence: None,
+                genres: vec![],
+                publisher: google_data.as_ref().and_then(|d| d.publisher.clone()),
+                year: reliable_year,
+                description: google_data.as_ref().and_then(|d| d.description.clone()),
+                isbn: None,
+                asin: audible_data.as_ref().and_then(|d| d.asin.clone()),
+            };
+        }
+    };
+    
+    let prompt = merge_prompt_for(
+        files,
+        folder_name,
+        extracted_title,
+        extracted_author,
+        google_data.as_ref(),
+        audible_data.as_ref(),
+        prompt_template,
+        confirmed,
+    );
+    
+    let reply = call_gpt_merge_metadata(&prompt, llm, model.unwrap_or(crate::llm::OPENAI_MODEL), config).await;
+    metadata_from_gpt_reply(reply, extracted_title, extracted_author, google_data.as_ref(), audible_data.as_ref(), reliable_year)
+}
+
+/// Turn the merge reply into metadata, falling back to the source data when
+/// the call failed, the model refused, or the JSON doesn't parse
+fn metadata_from_gpt_reply(
+    reply: Result<String>,
+    extracted_title: &str,
+    extracted_author: &str,
+    google_data: Option<&crate::metadata::BookMetadata>,
+    audible_data: Option<&crate::audible::AudibleMetadata>,
+    reliable_year: Option<String>,
+) -> BookMetadata {
+    let json_str = match reply {
+        Ok(json_str) => json_str,
+        Err(e) => {
+            match e.downcast_ref::<GptError>() {
+                Some(gpt_error) => println!("   ⚠️  GPT gave no usable answer: {}", gpt_error),
+                None => println!("   ⚠️  GPT merge error: {}", e),
+            }
+            println!("   ⚠️  Using fallback with available data");
+            return fallback_metadata(extracted_title, extracted_author, google_data, audible_data, reliable_year);
+        }
+    };
+    
+    match serde_json::from_str::<BookMetadata>(&json_str) {
+        Ok(mut metadata) => {
+            // FORCE the reliable year back in (in case GPT changed it)
+            if let Some(year) = reliable_year {
+                metadata.year = Some(year);
+            }
+            // GPT never sees a reason to keep the ASIN; take it from the match
+            if let Some(asin) = audible_data.and_then(|d| d.asin.clone()) {
+                metadata.asin = Some(asin);
+            }
+            fill_narrators_from_audible(&mut metadata, audible_data);
+            
+            println!("   ✅ Final: title='{}', author='{}', narrator={:?}", 
+                metadata.title, metadata.author, metadata.narrator);
+            println!("            genres={:?}, publisher={:?}, year={:?}",
+                metadata.genres, metadata.publisher, metadata.year);
+            metadata
+        }
+        Err(e) => {
+            println!("   ⚠️  GPT parse error: {}", e);
+            println!("   ⚠️  Using fallback with available data");
+            fallback_metadata(extracted_title, extracted_author, google_data, audible_data, reliable_year)
+        }
+    }
+}
+
+/// GPT returns one narrator; when it's one of Audible's, keep Audible's full
+/// list so co-narrators aren't dropped
+fn fill_narrators_from_audible(metadata: &mut BookMetadata, audible_data: Option<&crate::audible::AudibleMetadata>) {
+    let Some(audible_narrators) = audible_data.map(|d| &d.narrators).filter(|n| !n.is_empty()) else {
+        return;
+    };
+    let matches_audible = match &metadata.narrator {
+        Some(narrator) => audible_narrators.iter().any(|n| n.eq_ignore_ascii_case(narrator.trim())),
+        None => true,
+    };
+    if matches_audible {
+        metadata.narrators = audible_narrators.clone();
+        metadata.narrator = metadata.narrator.take().or_else(|| audible_narrators.first().cloned());
+    }
+}
+
+/// Metadata straight from Audible/Google when GPT can't be used
+fn fallback_metadata(
+    extracted_title: &str,
+    extracted_author: &str,
+    google_data: Option<&crate::metadata::BookMetadata>,
+    audible_data: Option<&crate::audible::AudibleMetadata>,
+    reliable_year: Option<String>,
+) -> BookMetadata {
+    BookMetadata {
+        title: extracted_title.to_string(),
+        subtitle: google_data.and_then(|d| d.subtitle.clone()),
+        author: extracted_author.to_string(),
+        narrator: audible_data
+            .and_then(|d| d.narrators.first().cloned()),
+        narrators: audible_data
+            .map(|d| d.narrators.clone())
+            .unwrap_or_default(),
+        series: audible_data
+            .and_then(|d| d.series.first().map(|s| s.name.clone())),
+        sequence: audible_data
+            .and_then(|d| d.series.first().and_then(|s| s.position.clone())),
+        genres: google_data
+            .map(|d| d.genres.clone())
+            .unwrap_or_default(),
+        publisher: google_data.and_then(|d| d.publisher.clone())
+            .or_else(|| audible_data.and_then(|d| d.publisher.clone())),
+        year: reliable_year,
+        description: google_data.and_then(|d| d.description.clone())
+            .or_else(|| audible_data.and_then(|d| d.description.clone())),
+        isbn: google_data
+            .and_then(|d| d.isbn.clone()),
+        asin: audible_data.and_then(|d| d.asin.clone()),
+    }
+}
+
+// Placeholders a custom merge prompt must contain to be usable
+pub const MERGE_PROMPT_REQUIRED_PLACEHOLDERS: &[&str] = &["{folder}", "{google}", "{audible}", "{genres}"];
+
+pub fn validate_merge_prompt_template(template: &str) -> Result<(), String> {
+    let missing: Vec<&str> = MERGE_PROMPT_REQUIRED_PLACEHOLDERS.iter()
+        .copied()
+        .filter(|p| !template.contains(p))
+        .collect();
+    
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Merge prompt template is missing required placeholders: {}", missing.join(", ")))
+    }
+}
+
+/// Fill a user's merge prompt template. Returns None (use the built-in
+/// prompt) when the template is malformed.
+fn render_merge_prompt_template(template: &str, values: &[(&str, String)]) -> Option<String> {
+    if let Err(e) = validate_merge_prompt_template(template) {
+        println!("   ⚠️  {} - using built-in prompt", e);
+        return None;
+    }
+    
+    let mut rendered = template.to_string();
+    for (name, value) in values {
+        rendered = rendered.replace(&format!("{{{}}}", name), value);
+    }
+    Some(rendered)
+}
+
+async fn call_gpt_extract_book_info(prompt: &str, llm: &crate::llm::LlmAccess, config: Option<&crate::config::Config>) -> Result<String> {
+    let api_key = match llm {
+        crate::llm::LlmAccess::OpenAi { api_key } => api_key,
+        crate::llm::LlmAccess::Ollama(ollama) => {
+            let content = crate::llm::ollama_chat(
+                ollama,
+                "Extract book info. Return JSON: {\"book_title\":\"...\",\"author\":\"...\"}",
+                prompt,
+                true
+            ).await?;
+            return Ok(clean_json_content(&content));
+        }
+    };
+    
+    crate::llm::throttle_openai(config).await?;
+    let client = reqwest::Client::new();
+    
+    let response = crate::llm::openai_account_headers(client.post("https://api.openai.com/v1/chat/completions"), config)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({
+            "model": crate::llm::OPENAI_MODEL,
+            "messages": [
                 {
                     "role": "system",
                     "content": "Extract book info. Return JSON: {\"book_title\":\"...\",\"author\":\"...\"}"
@@ -1156,29 +2838,68 @@ async fn call_gpt_extract_book_info(prompt: &str, api_key: &str) -> Result<Strin
     parse_gpt_response(&response_text)
 }
 
-async fn call_gpt_merge_metadata(prompt: &str, api_key: &str) -> Result<String> {
+async fn call_gpt_merge_metadata(prompt: &str, llm: &crate::llm::LlmAccess, model: &str, config: Option<&crate::config::Config>) -> Result<String> {
+    let api_key = match llm {
+        crate::llm::LlmAccess::OpenAi { api_key } => api_key,
+        crate::llm::LlmAccess::Ollama(ollama) => {
+            let content = crate::llm::ollama_chat(
+                ollama,
+                "You are an audiobook metadata expert. Return valid JSON only.",
+                prompt,
+                true
+            ).await?;
+            return Ok(clean_json_content(&content));
+        }
+    };
+    
+    let base_url = crate::llm::OPENAI_BASE_URL;
+    match request_gpt_merge(base_url, prompt, api_key, model, true, config).await {
+        // Older models reject JSON mode outright - retry with a plain request
+        Err(e) if e.to_string().contains("response_format") => {
+            println!("   ⚠️  Model does not support JSON mode, retrying without response_format");
+            request_gpt_merge(base_url, prompt, api_key, model, false, config).await
+        }
+        other => other,
+    }
+}
+
+async fn request_gpt_merge(
+    base_url: &str,
+    prompt: &str,
+    api_key: &str,
+    model: &str,
+    json_mode: bool,
+    config: Option<&crate::config::Config>,
+) -> Result<String> {
+    crate::llm::throttle_openai(config).await?;
     let client = reqwest::Client::new();
     
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
+    let mut body = serde_json::json!({
+        "model": model,
+        "messages": [
+            {
+                "role": "system",
+                "content": "You are an audiobook metadata expert. Return valid JSON only."
+            },
+            {
+                "role": "user",
+                "content": prompt
+            }
+        ],
+        "max_completion_tokens": 4000,
+        "verbosity": "low",
+        "reasoning_effort": "minimal"
+    });
+    
+    if json_mode {
+        body["response_format"] = serde_json::json!({ "type": "json_object" });
+    }
+    
+    let url = format!("{}/v1/chat/completions", base_url);
+    let response = crate::llm::openai_account_headers(client.post(&url), config)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&serde_json::json!({
-            "model": "gpt-5-nano",
-            "messages": [
-                {
-                    "role": "system",
-                    "content": "You are an audiobook metadata expert. Return valid JSON only."
-                },
-                {
-                    "role": "user",
-                    "content": prompt
-                }
-            ],
-            "max_completion_tokens": 4000,
-            "verbosity": "low",
-            "reasoning_effort": "minimal"
-        }))
+        .json(&body)
         .send()
         .await?;
     
@@ -1194,7 +2915,7 @@ async fn call_gpt_merge_metadata(prompt: &str, api_key: &str) -> Result<String>
 }
 
 fn parse_gpt_response(response_text: &str) -> Result<String> {
-    println!("             🔍 DEBUG: Raw API response (first 500 chars): {}", &response_text[..response_text.len().min(500)]);
+    println!("             🔍 DEBUG: Raw API response (first 500 chars): {}", crate::util::truncate_utf8(response_text, 500));
     
     #[derive(serde::Deserialize)]
     struct Response {
@@ -1204,36 +2925,60 @@ fn parse_gpt_response(response_text: &str) -> Result<String> {
     #[derive(serde::Deserialize)]
     struct Choice {
         message: Message,
+        #[serde(default)]
+        finish_reason: Option<String>,
     }
     
+    // Newer models may send null content with the answer withheld
     #[derive(serde::Deserialize)]
     struct Message {
-        content: String,
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        refusal: Option<String>,
     }
     
     let result: Response = serde_json::from_str(response_text)?;
     
     println!("             🔍 DEBUG: Number of choices: {}", result.choices.len());
     
-    let content = result.choices.first()
-        .ok_or_else(|| anyhow::anyhow!("No choices"))?
-        .message.content.trim();
+    let choice = result.choices.first()
+        .ok_or_else(|| anyhow::anyhow!("No choices"))?;
+    let content = choice.message.content.as_deref().unwrap_or("").trim();
     
-    println!("             🔍 DEBUG: Content length: {}, Content preview: {}", content.len(), &content[..content.len().min(100)]);
+    println!("             🔍 DEBUG: Content length: {}, finish_reason: {:?}, Content preview: {}",
+        content.len(), choice.finish_reason, crate::util::truncate_utf8(content, 100));
     
     if content.is_empty() {
-        anyhow::bail!("GPT returned empty content");
+        if let Some(refusal) = choice.message.refusal.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+            return Err(GptError::Refused(refusal.to_string()).into());
+        }
+        return Err(GptError::EmptyContent { finish_reason: choice.finish_reason.clone() }.into());
     }
     
-    let json_str = content
+    Ok(clean_json_content(content))
+}
+
+fn clean_json_content(content: &str) -> String {
+    // JSON mode guarantees a bare object
+    if serde_json::from_str::<serde_json::Value>(content).is_ok() {
+        return content.to_string();
+    }
+    
+    // Fallback for models without response_format support
+    let json_str = strip_json_fences(content);
+    
+    println!("             🔍 DEBUG: Final JSON (first 200 chars): {}", crate::util::truncate_utf8(json_str, 200));
+    
+    json_str.to_string()
+}
+
+fn strip_json_fences(content: &str) -> &str {
+    content
         .trim_start_matches("```json")
         .trim_start_matches("```")
         .trim_end_matches("```")
-        .trim();
-    
-    println!("             🔍 DEBUG: Final JSON (first 200 chars): {}", &json_str[..json_str.len().min(200)]);
-    
-    Ok(json_str.to_string())
+        .trim()
 }
 
 fn detect_group_type(files: &[RawFileData]) -> GroupType {
@@ -1274,6 +3019,34 @@ fn detect_group_type(files: &[RawFileData]) -> GroupType {
 // RETRY LOGIC WITH QUALITY VALIDATION
 // ============================================================================
 
+/// How sources are widened between merge attempts. Retrying an identical
+/// prompt rarely helps, so each retry brings in something new.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RetryStrategy {
+    /// The sources as originally fetched
+    AsFound,
+    AddOpenLibrary,
+    RelaxAudibleConfidence,
+    AlternateTitle,
+}
+
+const RETRY_STRATEGIES: &[RetryStrategy] = &[
+    RetryStrategy::AsFound,
+    RetryStrategy::AddOpenLibrary,
+    RetryStrategy::RelaxAudibleConfidence,
+    RetryStrategy::AlternateTitle,
+];
+
+/// What a merge attempt is built from
+#[derive(Debug, Clone)]
+struct MergeSources {
+    title: String,
+    author: String,
+    google: Option<crate::metadata::BookMetadata>,
+    audible: Option<crate::audible::AudibleMetadata>,
+    open_library: Option<crate::metadata::BookMetadata>,
+}
+
 async fn merge_all_with_gpt_retry(
     files: &[RawFileData],
     folder_name: &str,
@@ -1281,57 +3054,282 @@ async fn merge_all_with_gpt_retry(
     extracted_author: &str,
     google_data: Option<crate::metadata::BookMetadata>,
     audible_data: Option<crate::audible::AudibleMetadata>,
-    api_key: Option<&str>,
+    llm: Option<&crate::llm::LlmAccess>,
+    config: Option<&crate::config::Config>,
+    confirmed: Option<&BookOverride>,
     max_retries: u32,
-) -> BookMetadata {
-    for attempt in 1..=max_retries {
-        if attempt > 1 {
-            println!("   🔄 Retry attempt {}/{}", attempt, max_retries);
+) -> (BookMetadata, u32) {
+    let prompt_template = config.and_then(|c| c.merge_prompt_template.as_deref());
+    let defaults = crate::config::Config::default();
+    let config_or_default = config.unwrap_or(&defaults);
+    
+    // Ollama has no model to switch to; the fallback is an OpenAI model
+    let fallback_model = config_or_default.fallback_model.as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && config_or_default.llm_backend == crate::config::LlmBackend::OpenAi);
+    let genre_sources = config_or_default.genre_sources.as_slice();
+    
+    let sources = MergeSources {
+        title: extracted_title.to_string(),
+        author: extracted_author.to_string(),
+        google: google_data,
+        audible: audible_data,
+        open_library: open_library_for_genres(genre_sources, || {
+            crate::metadata::fetch_from_open_library(extracted_title, extracted_author)
+        }).await,
+    };
+    
+    let (metadata, quality_score, _) = retry_with_widening(
+        sources,
+        max_retries,
+        &config_or_default.quality_weights,
+        config_or_default.quality_threshold,
+        fallback_model,
+        move |s: MergeSources, model: Option<String>| async move {
+            let mut metadata = merge_all_with_gpt(
+                files, folder_name, &s.title, &s.author, s.google.clone(), s.audible.clone(), llm, config, prompt_template, confirmed, model.as_deref()
+            ).await;
+            apply_genre_sources(&mut metadata, &s, files, genre_sources);
+            enforce_override(metadata, confirmed)
+        },
+        move |strategy, s| widen_sources(strategy, s, folder_name, config),
+    ).await;
+    (metadata, quality_score)
+}
+
+/// Open Library up front, but only when it's one of the `genre_sources`;
+/// otherwise it's left for the AddOpenLibrary retry to bring in
+async fn open_library_for_genres<F, Fut>(
+    order: &[crate::config::GenreSource],
+    fetch: F,
+) -> Option<crate::metadata::BookMetadata>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<Option<crate::metadata::BookMetadata>>>,
+{
+    if !order.contains(&crate::config::GenreSource::OpenLibrary) {
+        return None;
+    }
+    match fetch().await {
+        Ok(found) => found,
+        Err(e) => {
+            println!("   ⚠️  Open Library lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Merge, score, and on a low score widen the sources with the next strategy
+/// and merge again. Strategies that add nothing are skipped without spending
+/// an attempt. With a fallback model, the final attempt is kept for it and
+/// runs on the widest sources once the primary model has failed. Returns the
+/// best-scoring result and the strategy behind it.
+async fn retry_with_widening<Merge, MergeFut, Widen, WidenFut>(
+    mut sources: MergeSources,
+    max_attempts: u32,
+    weights: &crate::config::QualityWeights,
+    threshold: u32,
+    fallback_model: Option<&str>,
+    mut merge: Merge,
+    mut widen: Widen,
+) -> (BookMetadata, u32, RetryStrategy)
+where
+    Merge: FnMut(MergeSources, Option<String>) -> MergeFut,
+    MergeFut: std::future::Future<Output = BookMetadata>,
+    Widen: FnMut(RetryStrategy, MergeSources) -> WidenFut,
+    WidenFut: std::future::Future<Output = Option<MergeSources>>,
+{
+    let primary_model = crate::llm::OPENAI_MODEL;
+    let budget = max_attempts.max(1);
+    let fallback_model = fallback_model.filter(|_| budget > 1);
+    let primary_attempts = budget - fallback_model.is_some() as u32;
+    let mut best: Option<(BookMetadata, u32, RetryStrategy, &str)> = None;
+    let mut attempts = 0;
+    let mut last_strategy = RetryStrategy::AsFound;
+    
+    for &strategy in RETRY_STRATEGIES {
+        if attempts >= primary_attempts {
+            break;
+        }
+        if strategy != RetryStrategy::AsFound {
+            match widen(strategy, sources.clone()).await {
+                Some(widened) => sources = widened,
+                None => {
+                    println!("   ⏭️  {:?} found nothing new", strategy);
+                    continue;
+                }
+            }
+            println!("   🔄 Retry attempt {}/{} ({:?})", attempts + 1, max_attempts, strategy);
         }
+        attempts += 1;
+        last_strategy = strategy;
         
-        let metadata = merge_all_with_gpt(
-            files,
-            folder_name,
-            extracted_title,
-            extracted_author,
-            google_data.clone(),
-            audible_data.clone(),
-            api_key
-        ).await;
+        let metadata = merge(sources.clone(), None).await;
+        let quality_score = validate_metadata_quality(&metadata, &sources.title, &sources.audible, weights);
         
-        let quality_score = validate_metadata_quality(&metadata, extracted_title, &audible_data);
+        if quality_score >= threshold {
+            println!("   ✅ Quality: {}% - PASSED ({:?}, {})", quality_score, strategy, primary_model);
+            return (metadata, quality_score, strategy);
+        }
+        println!("   ⚠️  Quality: {}% - RETRY", quality_score);
         
-        if quality_score >= 80 {
-            println!("   ✅ Quality: {}% - PASSED", quality_score);
-            return metadata;
-        } else {
-            println!("   ⚠️  Quality: {}% - RETRY", quality_score);
+        let improved = match &best {
+            Some((_, best_score, _, _)) => quality_score > *best_score,
+            None => true,
+        };
+        if improved {
+            best = Some((metadata, quality_score, strategy, primary_model));
+        }
+    }
+    
+    if let Some(model) = fallback_model {
+        println!("   🔄 Retry attempt {}/{} (fallback model {})", attempts + 1, max_attempts, model);
+        let metadata = merge(sources.clone(), Some(model.to_string())).await;
+        let quality_score = validate_metadata_quality(&metadata, &sources.title, &sources.audible, weights);
+        
+        if quality_score >= threshold {
+            println!("   ✅ Quality: {}% - PASSED ({:?}, {})", quality_score, last_strategy, model);
+            return (metadata, quality_score, last_strategy);
+        }
+        println!("   ⚠️  Quality: {}% - fallback model didn't pass either", quality_score);
+        let improved = match &best {
+            Some((_, best_score, _, _)) => quality_score > *best_score,
+            None => true,
+        };
+        if improved {
+            best = Some((metadata, quality_score, last_strategy, model));
+        }
+    }
+    
+    let (metadata, quality_score, strategy, model) = best.expect("the unwidened attempt always runs");
+    println!("   ⚠️  No strategy passed, using best result ({}% from {:?}, {})", quality_score, strategy, model);
+    (metadata, quality_score, strategy)
+}
+
+/// Apply one widening strategy. None when it turned up nothing the sources
+/// didn't already have.
+async fn widen_sources(
+    strategy: RetryStrategy,
+    mut sources: MergeSources,
+    folder_name: &str,
+    config: Option<&crate::config::Config>,
+) -> Option<MergeSources> {
+    let audible_config = config.filter(|c| c.audible_enabled && !c.audible_cli_path.is_empty());
+    
+    match strategy {
+        RetryStrategy::AsFound => None,
+        RetryStrategy::AddOpenLibrary => {
+            // Already fetched as a genre source; only the Google fill is new
+            let open_library = match sources.open_library.clone() {
+                Some(open_library) => open_library,
+                None => crate::metadata::fetch_from_open_library(&sources.title, &sources.author)
+                    .await.ok().flatten()?,
+            };
+            sources.open_library = Some(open_library.clone());
+            sources.google = Some(crate::metadata::fill_missing(sources.google, open_library));
+            Some(sources)
+        }
+        RetryStrategy::RelaxAudibleConfidence => {
+            if sources.audible.is_some() {
+                return None;
+            }
+            let cfg = audible_config?;
+            sources.audible = crate::audible::search_audible_regions(
+                &sources.title, &sources.author, &cfg.audible_cli_path, &cfg.audible_regions, cfg.audible_min_confidence / 2
+            ).await.ok().flatten();
+            sources.audible.is_some().then_some(sources)
+        }
+        RetryStrategy::AlternateTitle => {
+            let title = alternate_title(&sources.title, folder_name)?;
+            println!("   🔤 Trying alternate title '{}'", title);
+            
+            let google = crate::metadata::fetch_from_google_books(&title, &sources.author)
+                .await.ok().flatten();
+            let audible = match audible_config {
+                Some(cfg) => crate::audible::search_audible_regions(
+                    &title, &sources.author, &cfg.audible_cli_path, &cfg.audible_regions, cfg.audible_min_confidence
+                ).await.ok().flatten(),
+                None => None,
+            };
+            if google.is_none() && audible.is_none() {
+                return None;
+            }
+            
+            sources.title = title;
+            sources.google = google.or(sources.google);
+            sources.audible = audible.or(sources.audible);
+            Some(sources)
+        }
+    }
+}
+
+/// A different guess at the title: the extracted title without its series
+/// suffix, or else the cleaned-up folder name
+fn alternate_title(title: &str, folder_name: &str) -> Option<String> {
+    let (base_title, series, _) = crate::metadata::extract_series_from_title(title);
+    let candidate = if series.is_some() {
+        base_title
+    } else {
+        crate::metadata::clean_title(folder_name)
+    };
+    
+    let candidate = candidate.trim().to_string();
+    (!candidate.is_empty() && !candidate.eq_ignore_ascii_case(title.trim())).then_some(candidate)
+}
+
+fn enforce_override(mut metadata: BookMetadata, confirmed: Option<&BookOverride>) -> BookMetadata {
+    if let Some(confirmed) = confirmed {
+        if let Some(ref title) = confirmed.title {
+            metadata.title = title.clone();
         }
+        if let Some(ref author) = confirmed.author {
+            metadata.author = author.clone();
+        }
+    }
+    metadata
+}
+
+/// Title/author for a group: user overrides win, and a full override skips
+/// GPT extraction entirely.
+async fn resolve_book_info(
+    sample_file: &RawFileData,
+    folder_name: &str,
+    llm: Option<&crate::llm::LlmAccess>,
+    book_override: Option<&BookOverride>,
+    config: Option<&crate::config::Config>,
+) -> (String, String) {
+    let book_override = book_override.cloned().unwrap_or_default();
+    
+    if let (Some(title), Some(author)) = (&book_override.title, &book_override.author) {
+        println!("   ✋ Using override: '{}' by {}", title, author);
+        return (title.clone(), author.clone());
     }
     
-    println!("   ⚠️  All retries exhausted, using last result");
-    merge_all_with_gpt(files, folder_name, extracted_title, extracted_author, google_data, audible_data, api_key).await
+    let (title, author) = extract_book_info_with_gpt(sample_file, folder_name, llm, config).await;
+    (book_override.title.unwrap_or(title), book_override.author.unwrap_or(author))
 }
 
+/// 0-100: the share of the configured field weights this metadata earns
 fn validate_metadata_quality(
     metadata: &BookMetadata,
     extracted_title: &str,
     audible_data: &Option<crate::audible::AudibleMetadata>,
+    weights: &crate::config::QualityWeights,
 ) -> u32 {
     let mut score = 0;
     
     // Title must include the extracted title (e.g., "Dinosaurs Before Dark")
     if metadata.title.contains(extracted_title) {
-        score += 30;
+        score += weights.title;
     } else {
         println!("      ❌ Title doesn't contain '{}'", extracted_title);
     }
     
-    // Narrator must exist if Audible has it
+    // Narrator must exist if Audible has it (weighted by how sure the match is)
     if let Some(aud) = audible_data {
         if !aud.narrators.is_empty() {
             if metadata.narrator.is_some() {
-                score += 20;
+                score += weights.narrator * aud.match_score.min(100) / 100;
             } else {
                 println!("      ❌ Missing narrator (Audible has: {:?})", aud.narrators);
             }
@@ -1341,24 +3339,1723 @@ fn validate_metadata_quality(
     // Description should exist and be substantial
     if let Some(ref desc) = metadata.description {
         if desc.len() >= 100 && desc.len() <= 1000 {
-            score += 20;
+            score += weights.description;
         }
     }
     
     // Genres should be valid
     if !metadata.genres.is_empty() && metadata.genres.len() <= 3 {
-        score += 15;
+        score += weights.genres;
     }
     
     // Series/sequence should match if present
     if metadata.series.is_some() && metadata.sequence.is_some() {
-        score += 10;
+        score += weights.series;
     }
     
     // Has publication info
     if metadata.publisher.is_some() || metadata.year.is_some() {
-        score += 5;
+        score += weights.publication;
+    }
+    
+    match weights.total() {
+        0 => 0,
+        total => score * 100 / total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::temp_dir;
+    
+    fn raw_file(title: Option<&str>, artist: Option<&str>, genre: Option<&str>, comment: Option<&str>) -> RawFileData {
+        RawFileData {
+            id: "1".to_string(),
+            path: "/library/Book/01.m4b".to_string(),
+            filename: "01.m4b".to_string(),
+            tags: FileTags {
+                title: title.map(String::from),
+                artist: artist.map(String::from),
+                genre: genre.map(String::from),
+                comment: comment.map(String::from),
+                ..FileTags::default()
+            },
+            drm_protected: false,
+            duration_secs: None,
+            size_bytes: 0,
+            warning: None,
+        }
+    }
+    
+    fn book_metadata() -> BookMetadata {
+        BookMetadata {
+            title: "The Hobbit".to_string(),
+            subtitle: None,
+            author: "J.R.R. Tolkien".to_string(),
+            narrator: Some("Andy Serkis".to_string()),
+            narrators: vec![],
+            series: None,
+            sequence: None,
+            genres: vec!["Fantasy".to_string(), "Classic".to_string()],
+            publisher: None,
+            year: None,
+            description: None,
+            isbn: None,
+            asin: None,
+        }
     }
     
-    score
+    #[tokio::test]
+    async fn test_fallback_model_takes_final_attempt_after_junk() {
+        let mut server = mockito::Server::new_async().await;
+        let valid = serde_json::json!({
+            "title": "The Hobbit",
+            "author": "J.R.R. Tolkien",
+            "series": "Middle-earth",
+            "sequence": "1",
+            "genres": ["Fantasy"],
+            "publisher": "Allen & Unwin",
+            "description": "Bilbo Baggins is swept into a quest. ".repeat(4),
+        }).to_string();
+        let primary = server.mock("POST", "/v1/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": crate::llm::OPENAI_MODEL})))
+            .with_status(200)
+            .with_body(chat_response("Sure! Here is the metadata you asked for."))
+            .expect(1)
+            .create_async()
+            .await;
+        let fallback = server.mock("POST", "/v1/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"model": "gpt-5"})))
+            .with_status(200)
+            .with_body(chat_response(&valid))
+            .expect(1)
+            .create_async()
+            .await;
+        let url = server.url();
+        let sources = MergeSources {
+            title: "The Hobbit".to_string(),
+            author: "J.R.R. Tolkien".to_string(),
+            google: None,
+            audible: None,
+            open_library: None,
+        };
+        let mut models = Vec::new();
+        
+        let (metadata, score, _) = retry_with_widening(
+            sources,
+            3,
+            &crate::config::QualityWeights::default(),
+            crate::config::DEFAULT_QUALITY_THRESHOLD,
+            Some("gpt-5"),
+            |s: MergeSources, model: Option<String>| {
+                models.push(model.clone());
+                let url = url.clone();
+                async move {
+                    let model = model.unwrap_or_else(|| crate::llm::OPENAI_MODEL.to_string());
+                    let reply = request_gpt_merge(&url, "merge prompt", "test-key", &model, true, None).await;
+                    metadata_from_gpt_reply(reply, &s.title, &s.author, None, None, None)
+                }
+            },
+            |_, _: MergeSources| async { None },
+        ).await;
+        
+        primary.assert_async().await;
+        fallback.assert_async().await;
+        assert_eq!(models, vec![None, Some("gpt-5".to_string())]);
+        assert_eq!(metadata.series.as_deref(), Some("Middle-earth"));
+        assert!(score >= crate::config::DEFAULT_QUALITY_THRESHOLD);
+    }
+    
+    #[tokio::test]
+    async fn test_retry_widens_sources_instead_of_repeating() {
+        let sources = MergeSources {
+            title: "The Hobbit".to_string(),
+            author: "J.R.R. Tolkien".to_string(),
+            google: None,
+            audible: None,
+            open_library: None,
+        };
+        let mut merges = 0;
+        let mut tried = Vec::new();
+        
+        let (metadata, score, strategy) = retry_with_widening(
+            sources,
+            3,
+            &crate::config::QualityWeights::default(),
+            crate::config::DEFAULT_QUALITY_THRESHOLD,
+            None,
+            |s: MergeSources, _model| {
+                merges += 1;
+                async move {
+                    let google = s.google.unwrap_or_else(|| crate::metadata::BookMetadata {
+                        title: None, subtitle: None, authors: vec![], narrator: None, series: None, sequence: None,
+                        genres: vec![], publisher: None, publish_date: None, description: None, isbn: None, language: None,
+                    });
+                    BookMetadata {
+                        description: google.description,
+                        publisher: google.publisher,
+                        series: google.series,
+                        sequence: google.sequence,
+                        ..book_metadata()
+                    }
+                }
+            },
+            |strategy, mut s: MergeSources| {
+                tried.push(strategy);
+                async move {
+                    if strategy != RetryStrategy::AddOpenLibrary {
+                        return None;
+                    }
+                    s.google = Some(crate::metadata::BookMetadata {
+                        title: Some("The Hobbit".to_string()),
+                        subtitle: None,
+                        authors: vec!["J.R.R. Tolkien".to_string()],
+                        narrator: None,
+                        series: Some("Middle-earth".to_string()),
+                        sequence: Some("1".to_string()),
+                        genres: vec![],
+                        publisher: Some("Allen & Unwin".to_string()),
+                        publish_date: None,
+                        description: Some("Bilbo Baggins is swept into a quest. ".repeat(4)),
+                        isbn: None,
+                        language: None,
+                    });
+                    Some(s)
+                }
+            },
+        ).await;
+        
+        assert_eq!(merges, 2);
+        assert_eq!(tried, vec![RetryStrategy::AddOpenLibrary]);
+        assert_eq!(strategy, RetryStrategy::AddOpenLibrary);
+        assert!(score >= 80);
+        assert_eq!(metadata.series.as_deref(), Some("Middle-earth"));
+    }
+    
+    #[test]
+    fn test_co_narrators_kept_from_audible_and_written() {
+        let audible = crate::audible::AudibleMetadata {
+            title: Some("The Eye of the World".to_string()),
+            subtitle: None,
+            authors: vec!["Robert Jordan".to_string()],
+            narrators: vec!["Kate Reading".to_string(), "Michael Kramer".to_string()],
+            series: vec![],
+            publisher: None,
+            release_date: None,
+            description: None,
+            asin: None,
+            region: None,
+            match_score: 100,
+            cover_url: None,
+            genres: vec![],
+        };
+        let reply = r#"{"title":"The Eye of the World","author":"Robert Jordan","narrator":"Michael Kramer",
+            "series":null,"sequence":null,"genres":["Fantasy"],"publisher":null,"year":null,"description":null,
+            "subtitle":null,"isbn":null}"#;
+        let metadata = metadata_from_gpt_reply(Ok(reply.to_string()), "The Eye of the World", "Robert Jordan", None, Some(&audible), None);
+        
+        assert_eq!(metadata.narrator.as_deref(), Some("Michael Kramer"));
+        assert_eq!(metadata.narrators, vec!["Kate Reading", "Michael Kramer"]);
+        
+        let file = raw_file(Some("The Eye of the World"), Some("Robert Jordan"), None, None);
+        let changes = build_changes(&file, &metadata, &CommentTemplates::default());
+        assert_eq!(changes["narrator_comment"].new, "Narrated by Kate Reading, Michael Kramer");
+        assert_eq!(changes["narrators"].new, "Kate Reading; Michael Kramer");
+        assert!(!changes.contains_key("narrator"));
+        
+        // A single narrator produces no separate multi-value change
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        assert!(!changes.contains_key("narrators"));
+        assert_eq!(changes["narrator"].new, "Andy Serkis");
+    }
+    
+    #[test]
+    fn test_alternate_title_drops_series_suffix_or_uses_folder() {
+        assert_eq!(alternate_title("Dune, Book 1", "whatever").as_deref(), Some("Dune"));
+        assert_eq!(alternate_title("Dune", "Dune (Unabridged)").as_deref(), None);
+        assert_eq!(alternate_title("Track 01", "Dune [Retail]").as_deref(), Some("Dune"));
+    }
+    
+    #[test]
+    fn test_build_changes_title_and_author() {
+        let file = raw_file(Some("Hobbit (Unabridged)"), Some("Tolkien"), None, None);
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        
+        assert_eq!(changes["title"].old, "Hobbit (Unabridged)");
+        assert_eq!(changes["title"].new, "The Hobbit");
+        assert_eq!(changes["author"].old, "Tolkien");
+        assert_eq!(changes["author"].new, "J.R.R. Tolkien");
+    }
+    
+    #[test]
+    fn test_build_changes_sort_names_follow_title_and_author() {
+        let mut file = raw_file(Some("Hobbit (Unabridged)"), Some("Tolkien"), None, None);
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        assert_eq!(changes["title_sort"].new, "Hobbit");
+        assert_eq!(changes["author_sort"].new, "Tolkien, J.R.R.");
+        
+        // A missing sort tag is filled in even when its field is unchanged
+        file.tags.title = Some("The Hobbit".to_string());
+        file.tags.artist = Some("J.R.R. Tolkien".to_string());
+        file.tags.title_sort = Some("Hobbit, The".to_string());
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        assert!(!changes.contains_key("title_sort"));
+        assert_eq!(changes["author_sort"].old, "");
+        assert_eq!(changes["author_sort"].new, "Tolkien, J.R.R.");
+        
+        // An existing sort tag is preserved while its field is unchanged
+        file.tags.author_sort = Some("Tolkien, John Ronald Reuel".to_string());
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        assert!(!changes.contains_key("author_sort"));
+    }
+    
+    #[test]
+    fn test_build_changes_narrator_and_genre() {
+        let file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), None, Some("Old comment"));
+        let changes = build_changes(&file, &book_metadata(), &CommentTemplates::default());
+        
+        assert!(!changes.contains_key("title"));
+        assert!(!changes.contains_key("author"));
+        assert_eq!(changes["narrator"].old, "");
+        assert_eq!(changes["narrator"].new, "Andy Serkis");
+        assert_eq!(changes["narrator_comment"].old, "Old comment");
+        assert_eq!(changes["narrator_comment"].new, "Narrated by Andy Serkis");
+        assert_eq!(changes["genre"].old, "");
+        assert_eq!(changes["genre"].new, "Fantasy, Classic");
+    }
+    
+    #[test]
+    fn test_build_changes_unchanged_genre() {
+        let file = raw_file(None, None, Some("Fantasy, Classic"), None);
+        let mut metadata = book_metadata();
+        metadata.narrator = None;
+        
+        let files = build_audio_files(&[file], &metadata, &CommentTemplates::default());
+        assert!(files[0].changes.is_empty());
+        assert_eq!(files[0].status, "unchanged");
+    }
+    
+    #[test]
+    fn test_build_changes_extended_fields() {
+        let mut file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), None, None);
+        file.tags.year = Some("1937".to_string());
+        file.tags.publisher = Some("Old Publisher".to_string());
+        file.tags.title_sort = Some("Hobbit".to_string());
+        file.tags.author_sort = Some("Tolkien, J.R.R.".to_string());
+        
+        let mut metadata = book_metadata();
+        metadata.narrator = None;
+        metadata.genres.clear();
+        metadata.subtitle = Some("There and Back Again".to_string());
+        metadata.publisher = Some("HarperCollins".to_string());
+        metadata.description = Some("A hobbit goes on an adventure.".to_string());
+        metadata.year = Some("2012".to_string());
+        metadata.isbn = Some("9780007458424".to_string());
+        metadata.series = Some("Middle-earth".to_string());
+        metadata.sequence = Some("1".to_string());
+        
+        let changes = build_changes(&file, &metadata, &CommentTemplates::default());
+        
+        assert_eq!(changes["subtitle"], FieldChange { old: String::new(), new: "There and Back Again".to_string() });
+        assert_eq!(changes["publisher"], FieldChange { old: "Old Publisher".to_string(), new: "HarperCollins".to_string() });
+        assert_eq!(changes["description"].new, "A hobbit goes on an adventure.");
+        assert_eq!(changes["year"], FieldChange { old: "1937".to_string(), new: "2012".to_string() });
+        assert_eq!(changes["isbn"].new, "9780007458424");
+        assert_eq!(changes["series"].new, "Middle-earth");
+        assert_eq!(changes["sequence"].new, "1");
+        assert_eq!(changes.len(), 7);
+    }
+    
+    #[test]
+    fn test_build_changes_extended_fields_unchanged() {
+        let mut file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), None, None);
+        file.tags.series = Some("Middle-earth".to_string());
+        file.tags.isbn = Some("9780007458424".to_string());
+        file.tags.title_sort = Some("Hobbit".to_string());
+        file.tags.author_sort = Some("Tolkien, J.R.R.".to_string());
+        
+        let mut metadata = book_metadata();
+        metadata.narrator = None;
+        metadata.genres.clear();
+        metadata.series = Some("Middle-earth".to_string());
+        metadata.isbn = Some("9780007458424".to_string());
+        
+        assert!(build_changes(&file, &metadata, &CommentTemplates::default()).is_empty());
+    }
+    
+    #[test]
+    fn test_custom_narrator_template_end_to_end() {
+        let templates = CommentTemplates {
+            narrator: "Read by {narrator}".to_string(),
+            description: "{description}".to_string(),
+        };
+        let file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), Some("Fantasy, Classic"), Some("Narrated by Andy Serkis"));
+        
+        let changes = build_changes(&file, &book_metadata(), &templates);
+        assert_eq!(changes["narrator_comment"].new, "Read by Andy Serkis");
+        
+        // Once written, the custom form reads back as the same narrator with nothing to change
+        let mut written = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), Some("Fantasy, Classic"), Some("Read by Andy Serkis"));
+        written.tags.narrator = Some("Andy Serkis".to_string());
+        written.tags.title_sort = Some("Hobbit".to_string());
+        written.tags.author_sort = Some("Tolkien, J.R.R.".to_string());
+        let from_tags = metadata_from_processed_tags(&written.tags, "The Hobbit", &templates);
+        assert_eq!(from_tags.narrator.as_deref(), Some("Andy Serkis"));
+        assert!(build_changes(&written, &book_metadata(), &templates).is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_custom_narrator_template_written_to_comment() {
+        let templates = CommentTemplates {
+            narrator: "Read by {narrator}".to_string(),
+            description: "{description}".to_string(),
+        };
+        let dir = temp_dir("scan-narrator-template");
+        let path = dir.join("Hobbit.flac");
+        write_vorbis_tags(&path, "The Hobbit", "J.R.R. Tolkien", None, Some("Fantasy"));
+        let metadata = BookMetadata { genres: vec!["Fantasy".to_string()], ..book_metadata() };
+        
+        let changes = build_changes(&read_raw_file(&path), &metadata, &templates);
+        crate::tags::write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        let written = read_raw_file(&path);
+        
+        assert_eq!(written.tags.comment.as_deref(), Some("Read by Andy Serkis"));
+        assert_eq!(written.tags.narrator.as_deref(), Some("Andy Serkis"));
+        assert!(build_changes(&written, &metadata, &templates).is_empty());
+    }
+    
+    #[test]
+    fn test_description_template() {
+        let templates = CommentTemplates {
+            narrator: "Narrated by {narrator}".to_string(),
+            description: "Summary: {description}".to_string(),
+        };
+        let file = raw_file(None, None, None, None);
+        let mut metadata = book_metadata();
+        metadata.narrator = None;
+        metadata.description = Some("A hobbit goes on an adventure.".to_string());
+        
+        let changes = build_changes(&file, &metadata, &templates);
+        assert_eq!(changes["description"].new, "Summary: A hobbit goes on an adventure.");
+    }
+    
+    fn chat_response(content: &str) -> String {
+        serde_json::json!({
+            "choices": [{ "message": { "content": content } }]
+        }).to_string()
+    }
+    
+    #[test]
+    fn test_parse_gpt_response_json_mode() {
+        let content = r#"{"title":"Dune","author":"Frank Herbert"}"#;
+        let parsed = parse_gpt_response(&chat_response(content)).unwrap();
+        assert_eq!(parsed, content);
+    }
+    
+    #[test]
+    fn test_parse_gpt_response_fenced_fallback() {
+        let content = "```json\n{\"title\":\"Dune\",\"author\":\"Frank Herbert\"}\n```";
+        let parsed = parse_gpt_response(&chat_response(content)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&parsed).unwrap();
+        assert_eq!(value["title"], "Dune");
+    }
+    
+    #[test]
+    fn test_empty_gpt_content_falls_back_to_sources() {
+        let truncated = serde_json::json!({
+            "choices": [{ "message": { "content": null }, "finish_reason": "length" }]
+        }).to_string();
+        let reply = parse_gpt_response(&truncated);
+        assert_eq!(
+            reply.as_ref().unwrap_err().downcast_ref::<GptError>(),
+            Some(&GptError::EmptyContent { finish_reason: Some("length".to_string()) })
+        );
+        
+        let audible = crate::audible::AudibleMetadata {
+            title: Some("Dune".to_string()),
+            subtitle: None,
+            authors: vec!["Frank Herbert".to_string()],
+            narrators: vec!["Scott Brick".to_string()],
+            series: vec![],
+            publisher: None,
+            release_date: None,
+            description: None,
+            asin: Some("B002V1OF70".to_string()),
+            region: None,
+            match_score: 100,
+            cover_url: None,
+            genres: vec![],
+        };
+        let metadata = metadata_from_gpt_reply(reply, "Dune", "Frank Herbert", None, Some(&audible), Some("1965".to_string()));
+        assert_eq!(metadata.title, "Dune");
+        assert_eq!(metadata.narrator.as_deref(), Some("Scott Brick"));
+        assert_eq!(metadata.asin.as_deref(), Some("B002V1OF70"));
+        assert_eq!(metadata.year.as_deref(), Some("1965"));
+    }
+    
+    #[tokio::test]
+    async fn test_empty_ollama_merge_falls_back_like_openai() {
+        let mut server = mockito::Server::new_async().await;
+        let chat = server.mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(r#"{"model":"llama3.1","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let llm = crate::llm::LlmAccess::Ollama(crate::llm::OllamaSettings {
+            base_url: server.url(),
+            model: "llama3.1".to_string(),
+        });
+        
+        let reply = call_gpt_merge_metadata("Merge Dune", &llm, "llama3.1", None).await;
+        assert_eq!(
+            reply.as_ref().unwrap_err().downcast_ref::<GptError>(),
+            Some(&GptError::EmptyContent { finish_reason: Some("stop".to_string()) })
+        );
+        let metadata = metadata_from_gpt_reply(reply, "Dune", "Frank Herbert", None, None, Some("1965".to_string()));
+        
+        chat.assert_async().await;
+        assert_eq!(metadata.title, "Dune");
+        assert_eq!(metadata.author, "Frank Herbert");
+        assert_eq!(metadata.year.as_deref(), Some("1965"));
+    }
+    
+    #[test]
+    fn test_gpt_refusal_is_reported() {
+        let refused = serde_json::json!({
+            "choices": [{ "message": { "content": null, "refusal": "I can't help with that." }, "finish_reason": "stop" }]
+        }).to_string();
+        let err = parse_gpt_response(&refused).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<GptError>(),
+            Some(&GptError::Refused("I can't help with that.".to_string()))
+        );
+    }
+    
+    #[test]
+    fn test_render_merge_prompt_template() {
+        let template = "Folder {folder}; Google {google}; Audible {audible}; pick from {genres}. Return {\"title\": ...}";
+        let rendered = render_merge_prompt_template(template, &[
+            ("folder", "Dune".to_string()),
+            ("google", "No data".to_string()),
+            ("audible", "Title: Dune".to_string()),
+            ("genres", "Fantasy, Science Fiction".to_string()),
+        ]).unwrap();
+        
+        assert_eq!(rendered, "Folder Dune; Google No data; Audible Title: Dune; pick from Fantasy, Science Fiction. Return {\"title\": ...}");
+    }
+    
+    #[test]
+    fn test_build_merge_prompt_snapshot() {
+        let prompt = build_merge_prompt(
+            "Dune (1965) [Unabridged]",
+            "Dune",
+            "Frank Herbert",
+            r#"Title: Some("Dune"), Authors: ["Frank Herbert"], Publisher: Some("Ace"), Date: Some("1990-09-01")"#,
+            r#"Title: "Dune", Authors: ["Frank Herbert"], Narrators: ["Scott Brick", "Simon Vance"], Series: [], Publisher: Some("Macmillan Audio"), Release Date: Some("2007-01-01"), ASIN: Some("B002V1OF70")"#,
+            &["Narrated by Scott Brick".to_string()],
+            "CRITICAL: Use EXACTLY this year: 2007 (from Audible/Google Books - DO NOT CHANGE)",
+        );
+        
+        // Regenerate snapshots/merge_prompt.snap only for intended prompt changes
+        assert_eq!(prompt, include_str!("snapshots/merge_prompt.snap"));
+    }
+    
+    #[test]
+    fn test_merge_prompt_template_requires_placeholders() {
+        let err = validate_merge_prompt_template("Folder {folder} only").unwrap_err();
+        assert!(err.contains("{google}"));
+        assert!(render_merge_prompt_template("Folder {folder} only", &[]).is_none());
+    }
+    
+    #[test]
+    fn test_build_changes_same_output_for_every_path() {
+        // A file this app already wrote: the already-processed path must agree
+        // with the cache/full paths that it has nothing to change
+        let mut file = raw_file(
+            Some("The Hobbit"),
+            Some("J.R.R. Tolkien"),
+            Some("Fantasy, Classic"),
+            Some("Narrated by Andy Serkis"),
+        );
+        file.tags.narrator = Some("Andy Serkis".to_string());
+        file.tags.title_sort = Some("Hobbit".to_string());
+        file.tags.author_sort = Some("Tolkien, J.R.R.".to_string());
+        
+        let from_tags = metadata_from_processed_tags(&file.tags, "The Hobbit", &CommentTemplates::default());
+        assert!(build_changes(&file, &from_tags, &CommentTemplates::default()).is_empty());
+        assert!(build_changes(&file, &book_metadata(), &CommentTemplates::default()).is_empty());
+        assert_eq!(build_changes(&file, &from_tags, &CommentTemplates::default()), build_changes(&file, &book_metadata(), &CommentTemplates::default()));
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_entries_are_reported_not_dropped() {
+        let dir = temp_dir("scan-path-errors");
+        crate::test_fixtures::write_flac(&dir.join("Readable.flac"), 1);
+        // A dangling link fails the walk even when running as root
+        std::os::unix::fs::symlink(dir.join("gone"), dir.join("Broken Link")).unwrap();
+        
+        let (files, errors) = collect_audio_files_reporting(dir.to_str().unwrap(), &[], None);
+        
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "Readable.flac");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path.ends_with("Broken Link"));
+        assert!(!errors[0].error.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_scan_directory_keeps_going_past_missing_path() {
+        let readable = temp_dir("scan-partial-scan");
+        let missing = readable.join("no-such-folder");
+        let paths = vec![
+            missing.to_string_lossy().to_string(),
+            readable.to_string_lossy().to_string(),
+        ];
+        
+        let result = scan_directory(&paths, None, HashMap::new(), false, false, None).await;
+        
+        let (groups, _, path_errors) = result.unwrap();
+        assert!(groups.is_empty());
+        assert_eq!(path_errors.len(), 1);
+        assert_eq!(path_errors[0].path, paths[0]);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_wma_read_through_ffprobe_is_read_only() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let dir = temp_dir("scan-ffprobe");
+        let wma = dir.join("Hobbit.wma");
+        std::fs::write(&wma, b"not something lofty can read").unwrap();
+        // Stand-in for ffprobe that prints what it would for an ASF file
+        let ffprobe = dir.join("ffprobe");
+        std::fs::write(&ffprobe, concat!(
+            "#!/bin/sh\n",
+            "echo '{\"format\": {\"duration\": \"42.0\", \"tags\": {\"title\": \"The Hobbit\", \"Author\": \"J.R.R. Tolkien\"}}}'\n",
+        )).unwrap();
+        std::fs::set_permissions(&ffprobe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        
+        let (tags, duration) = extract_tags_with(&wma, || Some(ffprobe.to_string_lossy().to_string()));
+        let (untouched, _) = extract_tags_with(&wma, || None);
+        let untagged_flac = dir.join("Untagged.flac");
+        crate::test_fixtures::write_flac(&untagged_flac, 1);
+        let (untagged, _) = extract_tags_with(&untagged_flac, || Some(ffprobe.to_string_lossy().to_string()));
+        
+        assert_eq!(tags.title.as_deref(), Some("The Hobbit"));
+        assert_eq!(tags.artist.as_deref(), Some("J.R.R. Tolkien"));
+        assert_eq!(duration, Some(42.0));
+        assert!(tags.read_only);
+        assert!(untouched.title.is_none());
+        assert!(!untagged.read_only);
+        assert!(untagged.title.is_none());
+        
+        let mut file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), None, None);
+        file.tags = tags;
+        let audio_files = build_audio_files(&[file], &book_metadata(), &CommentTemplates::default());
+        assert_eq!(audio_files[0].status, READ_ONLY_STATUS);
+        assert!(audio_files[0].changes.is_empty());
+    }
+    
+    #[test]
+    fn test_folder_cover_used_when_nothing_is_embedded() {
+        let dir = temp_dir("scan-folder-cover");
+        crate::test_fixtures::write_flac(&dir.join("01.flac"), 1);
+        crate::test_fixtures::write_flac(&dir.join("02.flac"), 1);
+        std::fs::write(dir.join("Folder.JPG"), b"jpeg").unwrap();
+        std::fs::write(dir.join("cover.png"), b"png").unwrap();
+        let mut files = vec![read_raw_file(&dir.join("01.flac")), read_raw_file(&dir.join("02.flac"))];
+        
+        let cover = folder_cover_for(&files);
+        files[1].tags.has_cover = true;
+        let with_embedded = folder_cover_for(&files);
+        
+        assert!(!files[0].tags.has_cover);
+        assert_eq!(cover, Some(dir.join("cover.png").to_string_lossy().to_string()));
+        assert_eq!(with_embedded, None);
+    }
+    
+    #[test]
+    fn test_aax_file_gets_drm_status() {
+        let dir = temp_dir("scan-aax");
+        // Not a real aax - lofty would choke on it if we tried to read tags
+        std::fs::write(dir.join("Encrypted Book.aax"), b"not really audio").unwrap();
+        
+        let files = collect_audio_files(dir.to_str().unwrap(), &[], None).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].drm_protected);
+        
+        let group = drm_protected_group(&files[0], 0);
+        assert_eq!(group.files[0].status, DRM_STATUS);
+        assert_eq!(group.group_name, "Encrypted Book");
+        assert_eq!(group.total_changes, 0);
+    }
+    
+    #[test]
+    fn test_decrypted_aax_replaces_drm_file() {
+        let dir = temp_dir("scan-decrypt");
+        std::fs::write(dir.join("Owned Book.aax"), b"not really audio").unwrap();
+        std::fs::write(dir.join("Still Locked.aax"), b"not really audio").unwrap();
+        
+        let collected = collect_audio_files(dir.to_str().unwrap(), &[], None).unwrap();
+        let (drm_files, mut files): (Vec<_>, Vec<_>) = collected.into_iter().partition(|f| f.drm_protected);
+        let drm_paths: Vec<String> = drm_files.iter().map(|f| f.path.clone()).collect();
+        
+        // Stand-in for ffmpeg: "decrypt" one file by writing the m4b
+        let report = crate::audible::decrypt_all(&drm_paths, |p| {
+            if p.ends_with("Still Locked.aax") {
+                anyhow::bail!("bad activation bytes");
+            }
+            let out = crate::audible::decrypted_path(p);
+            std::fs::write(&out, b"decrypted")?;
+            Ok(out)
+        });
+        let remaining = add_decrypted_files(drm_files, &mut files, &report);
+        
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "Owned Book.m4b");
+        assert!(!files[0].drm_protected);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].filename, "Still Locked.aax");
+    }
+    
+    #[test]
+    fn test_skipped_folders_are_excluded() {
+        let dir = temp_dir("scan-skip");
+        for sub in ["Book", "Book/Sample", "Extras", "__MACOSX/Book"] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        crate::test_fixtures::write_flac(&dir.join("Book/Chapter 1.flac"), 1);
+        crate::test_fixtures::write_flac(&dir.join("Book/Sample/Preview.flac"), 1);
+        crate::test_fixtures::write_flac(&dir.join("Extras/Interview.flac"), 1);
+        crate::test_fixtures::write_flac(&dir.join("__MACOSX/Book/Chapter 1.flac"), 1);
+        
+        let skip = vec![
+            "sample".to_string(),
+            format!("{}/Extras/*", dir.to_str().unwrap()),
+        ];
+        let files = collect_audio_files(dir.to_str().unwrap(), &skip, None).unwrap();
+        
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("Book/Chapter 1.flac"));
+        assert!(!files[0].path.contains("__MACOSX"));
+    }
+    
+    fn write_id3_text_frames(path: &Path, frames: &[(&'static str, &str)]) {
+        use lofty::id3::v2::{Frame, FrameFlags, Id3v2Tag, TextInformationFrame};
+        use lofty::tag::TagExt;
+        
+        let mut tag = Id3v2Tag::new();
+        for (id, value) in frames {
+            let text = TextInformationFrame { encoding: lofty::TextEncoding::UTF8, value: value.to_string() };
+            tag.insert(Frame::new(*id, text, FrameFlags::default()).unwrap());
+        }
+        tag.insert_user_text("SERIES".to_string(), "Folder Guess".to_string());
+        tag.save_to_path(path, lofty::config::WriteOptions::default()).unwrap();
+    }
+    
+    #[test]
+    fn test_movement_frames_are_read_as_series() {
+        let dir = temp_dir("scan-movement-read");
+        let path = dir.join("Dune Messiah.mp3");
+        crate::test_fixtures::write_mp3(&path, 20);
+        write_id3_text_frames(&path, &[("MVNM", "Dune Chronicles"), ("MVIN", "2")]);
+        
+        let (mut tags, _) = extract_tags(&path);
+        
+        // SERIES is used unless movement frames are switched on
+        assert_eq!(tags.series.as_deref(), Some("Folder Guess"));
+        assert!(!tags.series_from_movement());
+        let mut file = raw_file(Some("Dune Messiah"), Some("Frank Herbert"), None, None);
+        file.tags = tags.clone();
+        let prompt = merge_prompt_for(&[file.clone()], "Dune Messiah", "Dune Messiah", "Frank Herbert", None, None, None, None);
+        assert!(!prompt.contains("AUTHORITATIVE"));
+        
+        tags.prefer_movement_series();
+        assert_eq!(tags.series.as_deref(), Some("Dune Chronicles"));
+        assert_eq!(tags.sequence.as_deref(), Some("2"));
+        file.tags = tags;
+        let prompt = merge_prompt_for(&[file], "Dune Messiah", "Dune Messiah", "Frank Herbert", None, None, None, None);
+        assert!(prompt.contains("series='Dune Chronicles', sequence='2'"));
+    }
+    
+    #[tokio::test]
+    async fn test_movement_frames_round_trip() {
+        let dir = temp_dir("scan-movement-write");
+        let path = dir.join("Dune Messiah.mp3");
+        crate::test_fixtures::write_mp3(&path, 20);
+        
+        let change = |new: &str| FieldChange { old: String::new(), new: new.to_string() };
+        let mut changes = HashMap::from([
+            ("series".to_string(), change("Dune Chronicles")),
+            ("sequence".to_string(), change("2")),
+        ]);
+        crate::tags::add_movement_changes(&mut changes);
+        crate::tags::write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        
+        let mut file = std::fs::File::open(&path).unwrap();
+        let mpeg = <lofty::mpeg::MpegFile as lofty::file::AudioFile>::read_from(&mut file, lofty::config::ParseOptions::new()).unwrap();
+        let ids: Vec<String> = mpeg.id3v2().unwrap().into_iter().map(|f| f.id_str().to_string()).collect();
+        let (tags, _) = extract_tags(&path);
+        
+        assert!(ids.iter().any(|id| id == "MVNM"));
+        assert!(ids.iter().any(|id| id == "MVIN"));
+        assert_eq!(tags.series.as_deref(), Some("Dune Chronicles"));
+        assert_eq!(tags.sequence.as_deref(), Some("2"));
+        assert_eq!(tags.movement.as_deref(), Some("Dune Chronicles"));
+        assert_eq!(tags.movement_number.as_deref(), Some("2"));
+        
+        let mut fractional = HashMap::from([("sequence".to_string(), change("1.5"))]);
+        crate::tags::add_movement_changes(&mut fractional);
+        assert!(!fractional.contains_key("movement_number"));
+    }
+    
+    #[tokio::test]
+    async fn test_asin_round_trips_through_file_tags() {
+        let dir = temp_dir("scan-asin-write");
+        let mp3 = dir.join("Dune.mp3");
+        let flac = dir.join("Dune.flac");
+        crate::test_fixtures::write_mp3(&mp3, 20);
+        crate::test_fixtures::write_flac(&flac, 1);
+        
+        let change = |new: &str| FieldChange { old: String::new(), new: new.to_string() };
+        let asin = HashMap::from([("asin".to_string(), change("B002V1OF70"))]);
+        let title = HashMap::from([("title".to_string(), change("Dune"))]);
+        for path in [&mp3, &flac] {
+            crate::tags::write_file_tags(path.to_str().unwrap(), &asin, false, false, &[], false, false).await.unwrap();
+            // A later write of other fields must keep it
+            crate::tags::write_file_tags(path.to_str().unwrap(), &title, false, false, &[], false, false).await.unwrap();
+        }
+        
+        let mut file = std::fs::File::open(&mp3).unwrap();
+        let mpeg = <lofty::mpeg::MpegFile as lofty::file::AudioFile>::read_from(&mut file, lofty::config::ParseOptions::new()).unwrap();
+        let user_text = mpeg.id3v2().unwrap().get_user_text("ASIN").map(str::to_string);
+        let (mp3_tags, _) = extract_tags(&mp3);
+        let (flac_tags, _) = extract_tags(&flac);
+        
+        assert_eq!(user_text.as_deref(), Some("B002V1OF70"));
+        assert_eq!(mp3_tags.asin.as_deref(), Some("B002V1OF70"));
+        assert_eq!(mp3_tags.title.as_deref(), Some("Dune"));
+        assert_eq!(flac_tags.asin.as_deref(), Some("B002V1OF70"));
+    }
+    
+    #[tokio::test]
+    async fn test_written_book_has_nothing_left_to_change() {
+        let dir = temp_dir("scan-rescan");
+        let mp3 = dir.join("Hobbit.mp3");
+        let m4b = dir.join("Hobbit.m4b");
+        crate::test_fixtures::write_mp3(&mp3, 20);
+        crate::test_fixtures::write_m4b(&m4b, 60);
+        let metadata = BookMetadata {
+            genres: vec!["Fantasy".to_string()],
+            description: Some("A hobbit goes on an adventure.".to_string()),
+            isbn: Some("9780007458424".to_string()),
+            ..book_metadata()
+        };
+        let templates = CommentTemplates::default();
+        
+        let mut rescans = Vec::new();
+        for path in [&mp3, &m4b] {
+            let changes = build_changes(&read_raw_file(path), &metadata, &templates);
+            assert!(changes.contains_key("narrator") && changes.contains_key("isbn"));
+            crate::tags::write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+            rescans.push(build_changes(&read_raw_file(path), &metadata, &templates));
+        }
+        let mut file = std::fs::File::open(&mp3).unwrap();
+        let mpeg = <lofty::mpeg::MpegFile as lofty::file::AudioFile>::read_from(&mut file, lofty::config::ParseOptions::new()).unwrap();
+        let isbn_frame = mpeg.id3v2().unwrap().get_user_text("ISBN").map(str::to_string);
+        
+        assert_eq!(isbn_frame.as_deref(), Some("9780007458424"));
+        for rescan in rescans {
+            assert!(rescan.is_empty(), "{:?}", rescan);
+        }
+    }
+    
+    fn write_vorbis_tags(path: &Path, title: &str, artist: &str, comment: Option<&str>, genre: Option<&str>) {
+        use lofty::prelude::*;
+        use lofty::tag::{Tag, TagType};
+        
+        crate::test_fixtures::write_flac(path, 60);
+        let mut tag = Tag::new(TagType::VorbisComments);
+        tag.set_title(title.to_string());
+        tag.set_artist(artist.to_string());
+        if let Some(comment) = comment {
+            tag.set_comment(comment.to_string());
+        }
+        if let Some(genre) = genre {
+            tag.set_genre(genre.to_string());
+        }
+        tag.save_to_path(path, lofty::config::WriteOptions::default()).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_only_definitive_misses_are_negative_cached() {
+        let cache_path = temp_dir("negative");
+        let cache = crate::cache::MetadataCache::open(&cache_path).unwrap();
+        let no_audible = || async { Ok::<Option<crate::audible::AudibleMetadata>, anyhow::Error>(None) };
+        let no_google = || async { Ok::<Option<crate::metadata::BookMetadata>, anyhow::Error>(None) };
+        
+        // A timed-out source might have had the book
+        let timed_out = async { Err::<Option<crate::audible::AudibleMetadata>, _>(anyhow::anyhow!("audible CLI timed out after 30.0s")) };
+        lookup_sources("Dune", "Frank Herbert", Some(&cache), timed_out, no_google(), "Dune").await;
+        let after_error = cache.is_known_missing("Dune", "Frank Herbert");
+        
+        lookup_sources("Dune", "Frank Herbert", Some(&cache), no_audible(), no_google(), "Dune").await;
+        let after_miss = cache.is_known_missing("Dune", "Frank Herbert");
+        
+        // Once cached, the sources aren't asked again
+        let asked = std::sync::atomic::AtomicBool::new(false);
+        let google = async {
+            asked.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(None)
+        };
+        let (audible, google_data) = lookup_sources("Dune", "Frank Herbert", Some(&cache), no_audible(), google, "Dune").await;
+        drop(cache);
+        
+        assert!(!after_error);
+        assert!(after_miss);
+        assert!(!asked.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(audible.is_none() && google_data.is_none());
+    }
+    
+    #[test]
+    fn test_cache_hits_are_tallied_from_a_seeded_cache() {
+        let cache_path = temp_dir("cache-hits");
+        let cache = crate::cache::MetadataCache::open(&cache_path).unwrap();
+        let metadata = BookMetadata {
+            title: "Dune".to_string(),
+            subtitle: None,
+            author: "Frank Herbert".to_string(),
+            narrator: Some("Scott Brick".to_string()),
+            narrators: vec!["Scott Brick".to_string()],
+            series: Some("Dune".to_string()),
+            sequence: Some("1".to_string()),
+            genres: vec!["Science Fiction".to_string()],
+            publisher: None,
+            year: Some("1965".to_string()),
+            description: None,
+            isbn: None,
+            asin: None,
+        };
+        cache_metadata(Some(&cache), "Dune", "Frank Herbert", &metadata);
+        
+        let hits = std::cell::Cell::new(0);
+        let weights = crate::config::QualityWeights::default();
+        let hit = cached_metadata(Some(&cache), "dune", "FRANK HERBERT", &weights, || hits.set(hits.get() + 1));
+        let miss = cached_metadata(Some(&cache), "Emma", "Jane Austen", &weights, || hits.set(hits.get() + 1));
+        let uncached = cached_metadata(None, "Dune", "Frank Herbert", &weights, || hits.set(hits.get() + 1));
+        drop(cache);
+        
+        assert_eq!(hit.map(|(m, _)| m.narrators), Some(vec!["Scott Brick".to_string()]));
+        assert!(miss.is_none());
+        assert!(uncached.is_none());
+        assert_eq!(hits.get(), 1);
+    }
+    
+    #[test]
+    fn test_cache_hit_is_scored_with_configured_weights() {
+        let cache_path = temp_dir("cache-score");
+        let cache = crate::cache::MetadataCache::open(&cache_path).unwrap();
+        let metadata = BookMetadata {
+            title: "Dune".to_string(),
+            subtitle: None,
+            author: "Frank Herbert".to_string(),
+            narrator: None,
+            narrators: vec![],
+            series: None,
+            sequence: None,
+            genres: vec!["Science Fiction".to_string()],
+            publisher: None,
+            year: Some("1965".to_string()),
+            description: None,
+            isbn: None,
+            asin: None,
+        };
+        cache_metadata(Some(&cache), "Dune", "Frank Herbert", &metadata);
+        
+        let score = |weights: &crate::config::QualityWeights| {
+            cached_metadata(Some(&cache), "Dune", "Frank Herbert", weights, || {}).map(|(_, s)| s)
+        };
+        let default_score = score(&crate::config::QualityWeights::default());
+        // Only what this entry has counts, so it scores 100
+        let lenient_score = score(&crate::config::QualityWeights {
+            title: 1, narrator: 0, description: 0, genres: 1, series: 0, publication: 1,
+        });
+        drop(cache);
+        
+        // Title 30 + genres 15 + publication 5 out of 100
+        assert_eq!(default_score, Some(50));
+        assert_eq!(lenient_score, Some(100));
+    }
+    
+    #[tokio::test]
+    async fn test_empty_audible_response_is_negative_cached() {
+        let cache_path = temp_dir("empty-audible");
+        let cache = crate::cache::MetadataCache::open(&cache_path).unwrap();
+        let no_google = || async { Ok::<Option<crate::metadata::BookMetadata>, anyhow::Error>(None) };
+        
+        // A failed CLI call says nothing about whether Audible has the book
+        let cli_failed = async { Err::<Option<crate::audible::AudibleMetadata>, _>(anyhow::anyhow!("audible CLI exited with Some(1)")) };
+        lookup_sources("Dune", "Frank Herbert", Some(&cache), cli_failed, no_google(), "Dune").await;
+        let after_failure = cache.is_known_missing("Dune", "Frank Herbert");
+        
+        let empty = async {
+            crate::audible::match_from_response(r#"{"products": []}"#, "Dune", "Frank Herbert", 70, Some("us"))
+        };
+        lookup_sources("Dune", "Frank Herbert", Some(&cache), empty, no_google(), "Dune").await;
+        let after_empty = cache.is_known_missing("Dune", "Frank Herbert");
+        drop(cache);
+        
+        assert!(!after_failure);
+        assert!(after_empty);
+    }
+    
+    #[test]
+    fn test_estimate_counts_cache_hits_and_misses() {
+        let dir = temp_dir("scan-estimate");
+        for book in ["Dune", "Hobbit", "Emma"] {
+            std::fs::create_dir_all(dir.join(book)).unwrap();
+        }
+        write_vorbis_tags(&dir.join("Dune/01.flac"), "Dune", "Frank Herbert", None, None);
+        write_vorbis_tags(&dir.join("Dune/02.flac"), "Dune", "Frank Herbert", None, None);
+        write_vorbis_tags(&dir.join("Hobbit/hobbit.flac"), "The Hobbit", "J.R.R. Tolkien", None, None);
+        write_vorbis_tags(&dir.join("Emma/emma.flac"), "Emma", "Jane Austen", Some("Narrated by Juliet Stevenson"), Some("Classic"));
+        std::fs::write(dir.join("Locked.aax"), b"not really audio").unwrap();
+        
+        let cache_path = temp_dir("estimate-cache");
+        let cache = crate::cache::MetadataCache::open(&cache_path).unwrap();
+        cache.set("dune", "frank herbert", crate::cache::CachedMetadata {
+            final_metadata: BookMetadata { title: "Dune".to_string(), ..book_metadata() },
+            timestamp: 0,
+        }).unwrap();
+        
+        let config = crate::config::Config { max_workers: 2, ..crate::config::Config::default() };
+        let estimate = estimate_scan(&[dir.to_string_lossy().to_string()], Some(&cache), Some(&config)).unwrap();
+        drop(cache);
+        
+        assert_eq!(estimate.file_count, 5);
+        assert_eq!(estimate.drm_files, 1);
+        assert_eq!(estimate.book_count, 3);
+        assert_eq!(estimate.skipped_books, 1);
+        assert_eq!(estimate.cache_hits, 1);
+        assert_eq!(estimate.cache_misses, 1);
+        assert_eq!(estimate.estimated_gpt_calls, ESTIMATED_GPT_CALLS_PER_LOOKUP);
+        assert_eq!(estimate.estimated_seconds, (ESTIMATED_SECONDS_PER_LOOKUP / 2.0).ceil() as u64);
+    }
+    
+    #[tokio::test]
+    async fn test_second_scan_reuses_index_for_unchanged_files() {
+        let dir = temp_dir("scan-index-rescan");
+        let index_dir = temp_dir("index-rescan");
+        let path = dir.join("Dune.flac");
+        crate::test_fixtures::write_flac(&path, 2);
+        let title = HashMap::from([("title".to_string(), FieldChange { old: String::new(), new: "Dune".to_string() })]);
+        crate::tags::write_file_tags(path.to_str().unwrap(), &title, false, false, &[], false, false).await.unwrap();
+        let index = crate::index::FileIndex::open(&index_dir).unwrap();
+        
+        let first = collect_audio_files(dir.to_str().unwrap(), &[], Some(&index)).unwrap();
+        
+        // Garble the file but keep its size and mtime: only a scan that never
+        // reads it can still see the title
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        let size = std::fs::metadata(&path).unwrap().len() as usize;
+        std::fs::write(&path, vec![0u8; size]).unwrap();
+        let set_modified = |time| std::fs::File::options().write(true).open(&path).unwrap().set_modified(time).unwrap();
+        set_modified(modified);
+        let second = collect_audio_files(dir.to_str().unwrap(), &[], Some(&index)).unwrap();
+        
+        set_modified(modified + std::time::Duration::from_secs(60));
+        let third = collect_audio_files(dir.to_str().unwrap(), &[], Some(&index)).unwrap();
+        drop(index);
+        
+        assert_eq!(first[0].tags.title.as_deref(), Some("Dune"));
+        assert_eq!(second[0].tags.title.as_deref(), Some("Dune"));
+        assert_eq!(second[0].duration_secs, first[0].duration_secs);
+        assert_eq!(third[0].tags.title, None);
+    }
+    
+    #[test]
+    fn test_stripped_file_is_no_longer_processed() {
+        use lofty::prelude::*;
+        
+        let dir = temp_dir("scan-strip");
+        let path = dir.join("Dune.flac");
+        crate::test_fixtures::write_flac(&path, 1);
+        {
+            let mut file = lofty::probe::Probe::open(&path).unwrap().read().unwrap();
+            let tag_type = file.primary_tag_type();
+            file.insert_tag(lofty::tag::Tag::new(tag_type));
+            let tag = file.primary_tag_mut().unwrap();
+            tag.set_comment("Narrated by Scott Brick".to_string());
+            tag.insert_text(lofty::tag::ItemKey::Composer, "Narrated by Scott Brick".to_string());
+            tag.set_genre("Science Fiction, Classic".to_string());
+            file.save_to_path(&path, lofty::config::WriteOptions::default()).unwrap();
+        }
+        
+        let rules = ProcessedRules::default();
+        let (before, _) = extract_tags(&path);
+        let changed = crate::tags::strip_app_formatting(path.to_str().unwrap(), false, &rules, &[]).unwrap();
+        let (after, _) = extract_tags(&path);
+        let changed_again = crate::tags::strip_app_formatting(path.to_str().unwrap(), false, &rules, &[]).unwrap();
+        
+        assert!(is_already_processed(&before, &rules));
+        assert!(changed);
+        assert!(!is_already_processed(&after, &rules));
+        assert_eq!(after.comment, None);
+        assert_eq!(after.composer.as_deref(), Some("Scott Brick"));
+        assert_eq!(after.genre.as_deref(), Some("Science Fiction"));
+        assert!(!changed_again);
+    }
+    
+    #[test]
+    fn test_two_second_stub_is_flagged_not_skipped() {
+        let dir = temp_dir("scan-stub");
+        crate::test_fixtures::write_flac(&dir.join("Truncated.flac"), 2);
+        
+        let mut files = collect_audio_files(dir.to_str().unwrap(), &[], None).unwrap();
+        let warnings = flag_suspect_files(&mut files, 30.0);
+        
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].duration_secs.map(|d| d.round()), Some(2.0));
+        assert!(files[0].size_bytes > 0);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].filename, "Truncated.flac");
+        assert!(warnings[0].message.contains("truncated"));
+        assert_eq!(files[0].warning.as_deref(), Some(warnings[0].message.as_str()));
+    }
+    
+    #[test]
+    fn test_lossless_bitrates_are_not_flagged() {
+        // An hour at CD rate (~176 KB/s) is normal for FLAC, not for MP3
+        let hour = |path: &str| RawFileData {
+            duration_secs: Some(3600.0),
+            size_bytes: 176_400 * 3600,
+            ..library_file(path, None, "Chapter 1")
+        };
+        let mut files = vec![hour("/library/Book/01.flac"), hour("/library/Book/01.mp3")];
+        
+        let warnings = flag_suspect_files(&mut files, 30.0);
+        
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].filename, "01.mp3");
+        assert!(files[0].warning.is_none());
+    }
+    
+    fn library_file(path: &str, album: Option<&str>, title: &str) -> RawFileData {
+        RawFileData {
+            id: path.to_string(),
+            path: path.to_string(),
+            filename: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+            tags: FileTags {
+                title: Some(title.to_string()),
+                album: album.map(String::from),
+                ..FileTags::default()
+            },
+            drm_protected: false,
+            duration_secs: None,
+            size_bytes: 0,
+            warning: None,
+        }
+    }
+    
+    fn sorted_group_names(groups: &HashMap<String, Vec<RawFileData>>) -> Vec<String> {
+        let mut names: Vec<String> = groups.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    
+    #[test]
+    fn test_fuzzy_grouping_splits_flat_folder() {
+        let files = vec![
+            library_file("/library/Audiobooks/dune-01.mp3", Some("Dune"), "Dune - Part 1"),
+            library_file("/library/Audiobooks/dune-02.mp3", Some("Dune"), "Dune - Part 2"),
+            library_file("/library/Audiobooks/emma-01.mp3", None, "Emma, Chapter 1"),
+            library_file("/library/Audiobooks/emma-02.mp3", None, "Emma, Chapter 2"),
+        ];
+        
+        let by_folder = group_files(files.clone(), crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&by_folder), vec!["Audiobooks"]);
+        
+        let fuzzy = group_files(files, crate::config::GroupingStrategy::FuzzyTitle, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&fuzzy), vec!["Dune", "Emma"]);
+        assert_eq!(fuzzy["Dune"].len(), 2);
+    }
+    
+    #[test]
+    fn test_fuzzy_grouping_merges_per_chapter_folders() {
+        let files = vec![
+            library_file("/library/Dune/Chapter 01/01.mp3", Some("Dune"), "Chapter 1"),
+            library_file("/library/Dune/Chapter 02/01.mp3", Some("Dune "), "Chapter 2"),
+            library_file("/library/Dune/Chapter 03/01.mp3", Some("Dune, Disc 3"), "Chapter 3"),
+        ];
+        
+        let by_folder = group_files(files.clone(), crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(by_folder.len(), 3);
+        
+        let by_album = group_files(files.clone(), crate::config::GroupingStrategy::AlbumTag, true, &SeriesPatterns::default());
+        assert_eq!(by_album.len(), 2);
+        
+        let fuzzy = group_files(files, crate::config::GroupingStrategy::FuzzyTitle, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&fuzzy), vec!["Dune"]);
+        assert_eq!(fuzzy["Dune"].len(), 3);
+    }
+    
+    #[test]
+    fn test_disc_subfolders_form_one_book() {
+        let files = vec![
+            library_file("/library/Dune/Disc 2/01 Track.mp3", None, "Track 1"),
+            library_file("/library/Dune/Disc 1/02 Track.mp3", None, "Track 2"),
+            library_file("/library/Dune/Disc 1/01 Track.mp3", None, "Track 1"),
+            library_file("/library/Dune/CD10/01 Track.mp3", None, "Track 1"),
+        ];
+        
+        let merged = group_files(files.clone(), crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&merged), vec!["Dune"]);
+        let order: Vec<&str> = merged["Dune"].iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(order, vec![
+            "/library/Dune/Disc 1/01 Track.mp3",
+            "/library/Dune/Disc 1/02 Track.mp3",
+            "/library/Dune/Disc 2/01 Track.mp3",
+            "/library/Dune/CD10/01 Track.mp3",
+        ]);
+        
+        // Track numbers beat the filename's text order within a disc
+        let mut tagged = vec![
+            library_file("/library/Dune/Disc 1/Track 10.mp3", None, "Track 10"),
+            library_file("/library/Dune/Disc 1/Track 2.mp3", None, "Track 2"),
+            library_file("/library/Dune/Disc 1/9.mp3", None, "Track 9"),
+        ];
+        tagged[0].tags.track = Some("10/12".to_string());
+        tagged[1].tags.track = Some("2".to_string());
+        let merged = group_files(tagged, crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        let order: Vec<&str> = merged["Dune"].iter().map(|f| f.filename.as_str()).collect();
+        assert_eq!(order, vec!["Track 2.mp3", "9.mp3", "Track 10.mp3"]);
+        
+        let per_disc = group_files(files, crate::config::GroupingStrategy::Folder, false, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&per_disc), vec!["CD10", "Disc 1", "Disc 2"]);
+    }
+    
+    fn folder_series(series: &str, sequence: &str, prefix: &str) -> Option<FolderSeries> {
+        Some(FolderSeries {
+            series: Some(series.to_string()),
+            sequence: Some(sequence.to_string()),
+            prefix: prefix.to_string(),
+        })
+    }
+    
+    #[test]
+    fn test_default_series_patterns() {
+        let patterns = SeriesPatterns::default();
+        
+        assert_eq!(
+            patterns.parse("Magic Tree House (Book #46) - Dogs in the Dead of Night"),
+            folder_series("Magic Tree House", "46", "Magic Tree House (Book #46)")
+        );
+        assert_eq!(
+            patterns.parse("Harry Potter Book 01 - Philosopher's Stone"),
+            folder_series("Harry Potter", "01", "Harry Potter Book 01")
+        );
+        assert_eq!(
+            patterns.parse("Saga, Vol. 2"),
+            folder_series("Saga", "2", "Saga, Vol. 2")
+        );
+        assert_eq!(
+            patterns.parse("Discworld #3 - Equal Rites"),
+            folder_series("Discworld", "3", "Discworld #3")
+        );
+        assert_eq!(patterns.parse("Book 7").and_then(|s| s.sequence), Some("7".to_string()));
+        assert_eq!(patterns.parse("The Book Thief"), None);
+    }
+    
+    #[test]
+    fn test_custom_series_pattern() {
+        let patterns = SeriesPatterns::new(&[
+            r"^(?P<sequence>\d+)\.\s*(?P<series>[^-]+?)\s+-".to_string(),
+            "([unclosed".to_string(),
+        ]);
+        
+        assert_eq!(
+            patterns.parse("03. The Expanse - Abaddon's Gate"),
+            folder_series("The Expanse", "03", "03. The Expanse -")
+        );
+        assert_eq!(patterns.parse("Discworld #3"), None);
+    }
+    
+    #[test]
+    fn test_series_folder_parts_group_together() {
+        let files = vec![
+            library_file("/library/Magic Tree House (Book #46) - Part 1/01.mp3", None, "Track 1"),
+            library_file("/library/Magic Tree House (Book #46) - Part 2/01.mp3", None, "Track 1"),
+            library_file("/library/Magic Tree House (Book #47)/01.mp3", None, "Track 1"),
+        ];
+        
+        let groups = group_files(files, crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&groups), vec!["Magic Tree House (Book #46)", "Magic Tree House (Book #47)"]);
+    }
+    
+    #[test]
+    fn test_series_folder_key_keeps_the_title() {
+        let files = vec![
+            library_file("/library/Discworld Book 01 - The Colour of Magic - Part 1/01.mp3", None, "Track 1"),
+            library_file("/library/Discworld Book 01 - The Colour of Magic - Part 2/01.mp3", None, "Track 1"),
+            library_file("/library/Discworld Book 01 - Mort/01.mp3", None, "Track 1"),
+            library_file("/library/Wheel of Time #3 - The Dragon Reborn/01.mp3", None, "Track 1"),
+        ];
+        
+        let groups = group_files(files, crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&groups), vec![
+            "Discworld Book 01 - Mort",
+            "Discworld Book 01 - The Colour of Magic",
+            "Wheel of Time #3 - The Dragon Reborn",
+        ]);
+    }
+    
+    #[test]
+    fn test_non_ascii_series_folders_group_without_panicking() {
+        let files = vec![
+            library_file("/library/Les Misérables – Tome Ü (Book #2) - Partie 1/01.mp3", None, "Piste 1"),
+            library_file("/library/Les Misérables – Tome Ü (Book #2) - Partie 2/01.mp3", None, "Piste 1"),
+            library_file("/library/三体 (Book #2)/01.mp3", None, "第一章"),
+        ];
+        
+        let groups = group_files(files, crate::config::GroupingStrategy::Folder, true, &SeriesPatterns::default());
+        assert_eq!(sorted_group_names(&groups), vec!["Les Misérables – Tome Ü (Book #2)", "三体 (Book #2)"]);
+        
+        let parsed = SeriesPatterns::default().parse("Ærø Saga (Book #2) - Début").unwrap();
+        assert_eq!(parsed.series.as_deref(), Some("Ærø Saga"));
+        assert_eq!(parsed.prefix, "Ærø Saga (Book #2)");
+    }
+    
+    #[test]
+    fn test_gpt_debug_previews_cut_on_char_boundaries() {
+        // One of the two offsets puts byte 500 (and 100) in the middle of an "é"
+        for lead in ["", "x"] {
+            let content = format!("{}{}", lead, "é".repeat(400));
+            assert_eq!(parse_gpt_response(&chat_response(&content)).unwrap(), content);
+        }
+    }
+    
+    #[test]
+    fn test_series_from_filename() {
+        let parsed = series_from_filename("The Expanse 03 - Abaddon's Gate.m4b").unwrap();
+        assert_eq!(parsed, FilenameSeries {
+            series: "The Expanse".to_string(),
+            sequence: "3".to_string(),
+            title: "Abaddon's Gate".to_string(),
+        });
+        
+        let parsed = series_from_filename("Discworld, Book 12 - Witches Abroad.mp3").unwrap();
+        assert_eq!(parsed.series, "Discworld");
+        assert_eq!(parsed.sequence, "12");
+        
+        let parsed = series_from_filename("Mistborn #2.5 - The Alloy of Law.m4b").unwrap();
+        assert_eq!(parsed.sequence, "2.5");
+        
+        assert!(series_from_filename("Abaddon's Gate.m4b").is_none());
+    }
+    
+    #[test]
+    fn test_fill_series_only_for_single_file_books() {
+        let mut file = raw_file(None, None, None, None);
+        file.filename = "The Expanse 03 - Abaddon's Gate.m4b".to_string();
+        
+        let filled = fill_series_from_filename(book_metadata(), std::slice::from_ref(&file));
+        assert_eq!(filled.series.as_deref(), Some("The Expanse"));
+        assert_eq!(filled.sequence.as_deref(), Some("3"));
+        
+        let untouched = fill_series_from_filename(book_metadata(), &[file.clone(), file]);
+        assert!(untouched.series.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_override_bypasses_gpt_extraction() {
+        let sample = raw_file(Some("Track 01"), Some("Unknown Reader"), None, None);
+        let book_override = BookOverride {
+            title: Some("The Name of the Wind".to_string()),
+            author: Some("Patrick Rothfuss".to_string()),
+        };
+        
+        // A bogus key would fail any real GPT call, so only the override can produce this
+        let llm = crate::llm::LlmAccess::OpenAi { api_key: "sk-invalid".to_string() };
+        let (title, author) = resolve_book_info(&sample, "Kingkiller 1", Some(&llm), Some(&book_override), None).await;
+        
+        assert_eq!(title, "The Name of the Wind");
+        assert_eq!(author, "Patrick Rothfuss");
+    }
+    
+    #[tokio::test]
+    async fn test_partial_override_keeps_extracted_author() {
+        let sample = raw_file(Some("Dune"), Some("Frank Herbert"), None, None);
+        let book_override = BookOverride {
+            title: Some("Dune Messiah".to_string()),
+            author: None,
+        };
+        
+        let (title, author) = resolve_book_info(&sample, "Dune 2", None, Some(&book_override), None).await;
+        
+        assert_eq!(title, "Dune Messiah");
+        assert_eq!(author, "Frank Herbert");
+    }
+    
+    #[tokio::test]
+    async fn test_locked_book_is_not_reprocessed() {
+        let mut locked = raw_file(Some("Hand Fixed"), Some("Some Author"), Some("Fantasy"), None);
+        locked.tags.locked = true;
+        
+        let groups = process_groups_with_gpt(vec![locked], None, HashMap::new(), false, false, None).await;
+        
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files[0].status, LOCKED_STATUS);
+        assert_eq!(groups[0].metadata.title, "Hand Fixed");
+        assert_eq!(groups[0].total_changes, 0);
+        assert_eq!(groups[0].quality_score, 100);
+        assert!(!groups[0].needs_review);
+    }
+    
+    #[test]
+    fn test_custom_narrator_prefix_marks_book_processed() {
+        let tags = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), Some("Fantasy"), Some("Performed by Andy Serkis")).tags;
+        
+        let defaults = ProcessedRules::default();
+        assert!(!is_already_processed(&tags, &defaults));
+        
+        let custom = ProcessedRules {
+            narrator_prefixes: vec!["Performed by ".to_string()],
+            ..ProcessedRules::default()
+        };
+        assert!(is_already_processed(&tags, &custom));
+        
+        let forced = ProcessedRules { force_reprocess: true, ..custom };
+        assert!(!is_already_processed(&tags, &forced));
+    }
+    
+    #[test]
+    fn test_default_genre_only_fills_empty_genres() {
+        let templates = CommentTemplates::default();
+        let file = raw_file(Some("The Hobbit"), Some("J.R.R. Tolkien"), None, None);
+        
+        let mut classified = book_metadata();
+        assert!(!apply_default_genre(&mut classified, Some("Fiction")));
+        assert_eq!(classified.genres, vec!["Fantasy", "Classic"]);
+        
+        let mut unclassified = BookMetadata { genres: vec![], ..book_metadata() };
+        assert!(!apply_default_genre(&mut unclassified, Some("Not A Genre")));
+        assert!(unclassified.genres.is_empty());
+        
+        assert!(apply_default_genre(&mut unclassified, Some("fiction")));
+        assert_eq!(unclassified.genres, vec!["Fiction"]);
+        
+        let mut audio_files = build_audio_files(&[file], &unclassified, &templates);
+        mark_default_genre(&mut audio_files);
+        assert_eq!(audio_files[0].changes["genre"].new, "Fiction");
+        assert!(audio_files[0].changes[DEFAULT_GENRE_CHANGE].new.contains("Fiction"));
+    }
+    
+    #[test]
+    fn test_genre_sources_follow_configured_priority() {
+        use crate::config::GenreSource;
+        let candidates = |source: GenreSource| match source {
+            GenreSource::Audible => vec!["Science Fiction & Fantasy".to_string(), "Horror".to_string(), "Thriller".to_string()],
+            GenreSource::Google => vec!["Fiction".to_string(), "Thriller".to_string(), "Mystery".to_string()],
+            _ => vec![],
+        };
+        
+        let audible_first = genres_from_sources(&[GenreSource::Audible, GenreSource::Google], candidates);
+        assert_eq!(audible_first, vec!["Horror", "Thriller", "Fiction"]);
+        
+        let google_first = genres_from_sources(&[GenreSource::Google, GenreSource::Audible], candidates);
+        assert_eq!(google_first, vec!["Fiction", "Thriller", "Mystery"]);
+        
+        assert!(genres_from_sources(&[GenreSource::FileTags], candidates).is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_open_library_genres_fetched_when_configured() {
+        use crate::config::GenreSource;
+        let found = || async {
+            Ok(Some(crate::metadata::BookMetadata {
+                title: Some("The Hobbit".to_string()), subtitle: None, authors: vec![], narrator: None, series: None, sequence: None,
+                genres: vec!["Horror".to_string()], publisher: None, publish_date: None,
+                description: None, isbn: None, language: None,
+            }))
+        };
+        
+        let open_library = open_library_for_genres(&[GenreSource::OpenLibrary], found).await;
+        let sources = MergeSources {
+            title: "The Hobbit".to_string(),
+            author: "J.R.R. Tolkien".to_string(),
+            google: None,
+            audible: None,
+            open_library,
+        };
+        let mut metadata = book_metadata();
+        apply_genre_sources(&mut metadata, &sources, &[], &[GenreSource::OpenLibrary]);
+        assert_eq!(metadata.genres, vec!["Horror"]);
+        
+        let mut fetched = false;
+        let skipped = open_library_for_genres(&[GenreSource::Gpt], || {
+            fetched = true;
+            async { Ok(None) }
+        }).await;
+        assert!(skipped.is_none());
+        assert!(!fetched);
+    }
+    
+    #[test]
+    fn test_quality_score_reflects_missing_fields() {
+        let mut metadata = book_metadata();
+        metadata.description = Some("A".repeat(200));
+        metadata.year = Some("1937".to_string());
+        let weights = crate::config::QualityWeights::default();
+        let complete = validate_metadata_quality(&metadata, "The Hobbit", &None, &weights);
+        
+        metadata.description = None;
+        metadata.genres.clear();
+        let sparse = validate_metadata_quality(&metadata, "The Hobbit", &None, &weights);
+        
+        assert_eq!(complete, 70);
+        assert_eq!(sparse, 35);
+        assert!(sparse < crate::config::DEFAULT_REVIEW_THRESHOLD);
+    }
+    
+    #[test]
+    fn test_custom_quality_weights_change_outcome() {
+        // No narrator, series or publication info - fine for someone who only
+        // cares about titles, descriptions and genres
+        let metadata = BookMetadata {
+            narrator: None,
+            description: Some("A".repeat(200)),
+            ..book_metadata()
+        };
+        let threshold = crate::config::DEFAULT_QUALITY_THRESHOLD;
+        let audible = crate::audible::AudibleMetadata {
+            title: Some("The Hobbit".to_string()),
+            subtitle: None,
+            authors: vec![],
+            narrators: vec!["Andy Serkis".to_string()],
+            series: vec![],
+            publisher: None,
+            release_date: None,
+            description: None,
+            asin: None,
+            region: None,
+            match_score: 100,
+            cover_url: None,
+            genres: vec![],
+        };
+        let audible = Some(audible);
+        
+        let default_score = validate_metadata_quality(&metadata, "The Hobbit", &audible, &crate::config::QualityWeights::default());
+        let lenient = crate::config::QualityWeights {
+            narrator: 0,
+            series: 0,
+            publication: 0,
+            ..crate::config::QualityWeights::default()
+        };
+        let lenient_score = validate_metadata_quality(&metadata, "The Hobbit", &audible, &lenient);
+        
+        assert_eq!(default_score, 65);
+        assert!(default_score < threshold);
+        assert_eq!(lenient_score, 100);
+        assert!(lenient_score >= threshold);
+    }
+    
+    #[test]
+    fn test_scan_summary_is_smaller_than_full_output() {
+        let metadata = book_metadata();
+        let templates = CommentTemplates::default();
+        let files: Vec<RawFileData> = (0..20)
+            .map(|_| raw_file(Some("Old Title"), Some("Old Author"), Some("Horror"), None))
+            .collect();
+        
+        let groups: Vec<BookGroup> = (0..50)
+            .map(|i| {
+                let audio_files = build_audio_files(&files, &metadata, &templates);
+                let total_changes = if i % 5 == 0 { 0 } else { audio_files.iter().map(|f| f.changes.len()).sum() };
+                BookGroup {
+                    id: i.to_string(),
+                    group_name: format!("Book {}", i),
+                    group_type: GroupType::Chapters,
+                    files: audio_files,
+                    metadata: metadata.clone(),
+                    total_changes,
+                    quality_score: 90,
+                    needs_review: i == 3,
+                    warnings: Vec::new(),
+                    cover_path: None,
+                }
+            })
+            .collect();
+        
+        let summary = summarize_groups(&groups);
+        assert_eq!(summary.total_groups, 50);
+        assert_eq!(summary.changed_groups, 40);
+        assert_eq!(summary.total_files, 1000);
+        assert_eq!(summary.needs_review, 1);
+        assert!(summary.total_changes > 0);
+        
+        let full_size = serde_json::to_string(&groups).unwrap().len();
+        let summary_size = serde_json::to_string(&summary).unwrap().len();
+        assert!(summary_size * 100 < full_size, "summary {} vs full {}", summary_size, full_size);
+        
+        // Dropping the change maps leaves the counts and statuses intact
+        let mut summarized = groups;
+        for group in summarized.iter_mut() {
+            drop_change_maps(&mut group.files);
+        }
+        let after = summarize_groups(&summarized);
+        assert_eq!((after.changed_groups, after.total_changes), (summary.changed_groups, summary.total_changes));
+        assert!(summarized.iter().flat_map(|g| &g.files).all(|f| f.changes.is_empty() && f.status == "changed"));
+        assert!(serde_json::to_string(&summarized).unwrap().len() < full_size);
+    }
+    
+    #[test]
+    fn test_stray_file_flagged_and_not_sampled() {
+        let chapter = |path: &str, title: &str, artist: &str, album: &str| {
+            let mut file = library_file(path, Some(album), title);
+            file.tags.artist = Some(artist.to_string());
+            file
+        };
+        let files = vec![
+            chapter("/library/Kings/00.mp3", "It - The Complete Novel", "Stephen King", "It"),
+            chapter("/library/Kings/01.mp3", "Chapter 1", "Brandon Sanderson", "The Way of Kings"),
+            chapter("/library/Kings/02.mp3", "Chapter 2", "Brandon Sanderson", "The Way of Kings"),
+            chapter("/library/Kings/03.mp3", "Chapter 3", "Brandon Sanderson", "The Way of Kings"),
+        ];
+        
+        let warnings = mismatch_warnings(&files);
+        
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("00.mp3: artist \"Stephen King\""));
+        assert_eq!(find_best_sample_file(&files).filename, "01.mp3");
+        // Differing chapter titles have no majority, so they're never flagged
+        assert!(mismatch_warnings(&files[1..]).is_empty());
+    }
+    
+    #[test]
+    fn test_changed_only_excludes_unchanged_groups() {
+        let group = |id: &str, total_changes: usize| BookGroup {
+            id: id.to_string(),
+            group_name: format!("Book {}", id),
+            group_type: GroupType::Single,
+            files: Vec::new(),
+            metadata: book_metadata(),
+            total_changes,
+            quality_score: 90,
+            needs_review: false,
+            warnings: Vec::new(),
+            cover_path: None,
+        };
+        let mut locked = group("e", 0);
+        locked.files.push(AudioFile {
+            id: "e-0".to_string(),
+            path: "/library/Locked/01.mp3".to_string(),
+            filename: "01.mp3".to_string(),
+            status: LOCKED_STATUS.to_string(),
+            changes: HashMap::new(),
+        });
+        let groups = vec![group("a", 0), group("b", 3), group("c", 0), group("d", 1), locked];
+        
+        let (changed, omitted) = changed_groups_only(groups);
+        
+        // The locked book has nothing to write but is still reported
+        let ids: Vec<&str> = changed.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "d", "e"]);
+        assert_eq!(omitted, 2);
+    }
+    
+    /// Three tagged files in `dir/Dune Saga`, grouped as one book whose tags
+    /// already match its metadata
+    fn box_set_group(dir: &Path) -> BookGroup {
+        let folder = dir.join("Dune Saga");
+        std::fs::create_dir_all(&folder).unwrap();
+        let files: Vec<AudioFile> = ["Dune 1 - Dune.flac", "Dune 2 - Dune Messiah.flac", "Dune 3 - Children of Dune.flac"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let path = folder.join(name);
+                write_vorbis_tags(&path, "Dune Saga", "Frank Herbert", None, None);
+                AudioFile {
+                    id: i.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    filename: name.to_string(),
+                    status: "unchanged".to_string(),
+                    changes: HashMap::new(),
+                }
+            })
+            .collect();
+        BookGroup {
+            id: "7".to_string(),
+            group_name: "Dune Saga".to_string(),
+            group_type: GroupType::Chapters,
+            files,
+            metadata: BookMetadata {
+                title: "Dune Saga".to_string(),
+                author: "Frank Herbert".to_string(),
+                narrator: None,
+                genres: Vec::new(),
+                ..book_metadata()
+            },
+            total_changes: 0,
+            quality_score: 80,
+            needs_review: false,
+            warnings: Vec::new(),
+            cover_path: None,
+        }
+    }
+    
+    #[test]
+    fn test_forcing_series_splits_group_per_file() {
+        let dir = temp_dir("scan-split-series");
+        let mut groups = vec![box_set_group(&dir)];
+        
+        let books = regroup(&mut groups, "7", GroupType::Series, &CommentTemplates::default()).unwrap();
+        
+        assert_eq!(books.len(), 3);
+        assert_eq!(groups.len(), 3);
+        assert!(books.iter().all(|b| b.group_type == GroupType::Series && b.files.len() == 1));
+        assert_eq!(books[1].id, "7-2");
+        assert_eq!(books[1].metadata.title, "Dune Messiah");
+        assert_eq!(books[1].metadata.series.as_deref(), Some("Dune"));
+        assert_eq!(books[1].metadata.sequence.as_deref(), Some("2"));
+        assert_eq!(books[1].total_changes, 1);
+        let changes = &books[1].files[0].changes;
+        assert_eq!(changes["title"].new, "Dune Messiah");
+        assert_eq!(changes["series"].new, "Dune");
+        assert_eq!(changes["sequence"].new, "2");
+        assert_eq!(books[1].files[0].status, "changed");
+    }
+    
+    #[test]
+    fn test_forcing_chapters_merges_split_books() {
+        let dir = temp_dir("scan-merge-chapters");
+        let templates = CommentTemplates::default();
+        let mut groups = vec![box_set_group(&dir)];
+        // Another book from the same folder isn't part of the split
+        let mut other = box_set_group(&dir);
+        other.id = "9".to_string();
+        other.files.truncate(1);
+        groups.push(other);
+        regroup(&mut groups, "7", GroupType::Series, &templates).unwrap();
+        
+        let merged = regroup(&mut groups, "7-3", GroupType::Chapters, &templates).unwrap();
+        
+        assert_eq!(merged.len(), 1);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].id, "9");
+        assert_eq!(merged[0].id, "7");
+        assert_eq!(merged[0].group_type, GroupType::Chapters);
+        assert_eq!(merged[0].group_name, "Dune Saga");
+        assert_eq!(merged[0].files.len(), 3);
+        assert_eq!(merged[0].files[0].filename, "Dune 1 - Dune.flac");
+        assert_eq!(merged[0].metadata.title, "Dune");
+        assert_eq!(merged[0].metadata.sequence, None);
+        // Every file is re-diffed against the merged title, not its split one
+        assert!(merged[0].files.iter().all(|f| f.changes["title"].new == "Dune"));
+        assert!(merged[0].files.iter().all(|f| !f.changes.contains_key("sequence")));
+        assert_eq!(merged[0].total_changes, 3);
+    }
 }
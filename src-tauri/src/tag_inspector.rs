@@ -3,6 +3,8 @@ use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::probe::Probe;
 use lofty::tag::{Accessor, ItemKey, ItemValue};
 use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use std::ptr;
 
@@ -14,6 +16,19 @@ pub struct RawTags {
     pub bitrate: Option<u32>,
     pub sample_rate: Option<u32>,
     pub tags: Vec<TagEntry>,
+    #[serde(default)]
+    pub chapters: Vec<ChapterInfo>,
+    /// Embedded pictures across all tags
+    #[serde(default)]
+    pub picture_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterInfo {
+    pub index: usize,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub title: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +56,8 @@ pub fn inspect_file_tags(file_path: &str) -> Result<RawTags> {
     };
     let bitrate = properties.audio_bitrate();
     let sample_rate = properties.sample_rate();
+    let chapters = read_chapters(path, properties.duration().as_secs_f64());
+    let picture_count = tagged_file.tags().iter().map(|t| t.picture_count()).sum();
 
     let mut tags = Vec::new();
 
@@ -189,9 +206,297 @@ pub fn inspect_file_tags(file_path: &str) -> Result<RawTags> {
         bitrate,
         sample_rate,
         tags,
+        chapters,
+        picture_count,
     })
 }
 
+/// Read embedded chapters: ID3v2 CHAP frames (mp3) or Nero `chpl` atoms
+/// (m4b/m4a). QuickTime chapter tracks aren't parsed. Malformed chapter data
+/// yields whatever could be read rather than failing the inspection.
+pub(crate) fn read_chapters(path: &Path, duration_secs: f64) -> Vec<ChapterInfo> {
+    let ext = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    
+    let result = match ext.as_str() {
+        "m4b" | "m4a" | "mp4" => read_mp4_chapters(path, duration_secs),
+        _ => read_id3_chapters(path),
+    };
+    
+    match result {
+        Ok(chapters) => chapters,
+        Err(e) => {
+            println!("⚠️  Could not read chapters from {}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn read_id3_chapters(path: &Path) -> Result<Vec<ChapterInfo>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; 10];
+    if file.read_exact(&mut header).is_err() || &header[..3] != b"ID3" {
+        return Ok(Vec::new());
+    }
+    
+    let size = syncsafe(&header[6..10]) as usize;
+    let mut body = vec![0u8; size];
+    file.read_exact(&mut body)?;
+    
+    Ok(parse_id3_chapters(header[3], header[5], &body))
+}
+
+pub(crate) fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |acc, b| (acc << 7) | (*b as u32 & 0x7f))
+}
+
+fn parse_id3_chapters(version: u8, flags: u8, body: &[u8]) -> Vec<ChapterInfo> {
+    // Unsynchronised tags would need un-escaping first; rare enough to skip
+    if flags & 0x80 != 0 || !(3..=4).contains(&version) {
+        return Vec::new();
+    }
+    
+    let pos = id3_frames_start(version, flags, body);
+    let mut chapters: Vec<ChapterInfo> = id3_frames(version, body.get(pos..).unwrap_or(&[]))
+        .into_iter()
+        .filter(|f| f.id == b"CHAP")
+        .filter_map(|f| parse_chap_frame(version, f.data))
+        .collect();
+    
+    chapters.sort_by(|a, b| a.start_seconds.partial_cmp(&b.start_seconds).unwrap_or(std::cmp::Ordering::Equal));
+    for (idx, chapter) in chapters.iter_mut().enumerate() {
+        chapter.index = idx;
+    }
+    
+    chapters
+}
+
+/// Offset of the first frame in a tag body, skipping any extended header
+pub(crate) fn id3_frames_start(version: u8, flags: u8, body: &[u8]) -> usize {
+    if flags & 0x40 == 0 || body.len() < 4 {
+        return 0;
+    }
+    if version == 4 {
+        syncsafe(&body[..4]) as usize
+    } else {
+        u32::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize + 4
+    }
+}
+
+pub(crate) struct Id3Frame<'a> {
+    pub id: &'a [u8],
+    pub data: &'a [u8],
+    /// Header and payload, for copying the frame through unchanged
+    pub raw: &'a [u8],
+}
+
+/// Split a run of ID3v2 frames, stopping at padding or the first frame whose
+/// size runs past the data.
+pub(crate) fn id3_frames(version: u8, data: &[u8]) -> Vec<Id3Frame<'_>> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+    
+    while pos + 10 <= data.len() {
+        let id = &data[pos..pos + 4];
+        if id[0] == 0 {
+            break;
+        }
+        
+        let size_bytes = &data[pos + 4..pos + 8];
+        let size = if version == 4 {
+            syncsafe(size_bytes) as usize
+        } else {
+            u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]]) as usize
+        };
+        
+        let start = pos + 10;
+        let end = match start.checked_add(size) {
+            Some(end) if end <= data.len() => end,
+            _ => break,
+        };
+        
+        frames.push(Id3Frame {
+            id,
+            data: &data[start..end],
+            raw: &data[pos..end],
+        });
+        pos = end;
+    }
+    
+    frames
+}
+
+fn parse_chap_frame(version: u8, data: &[u8]) -> Option<ChapterInfo> {
+    let id_end = data.iter().position(|b| *b == 0)?;
+    let element_id = String::from_utf8_lossy(&data[..id_end]).to_string();
+    
+    let times = data.get(id_end + 1..id_end + 17)?;
+    let start_ms = u32::from_be_bytes([times[0], times[1], times[2], times[3]]);
+    let end_ms = u32::from_be_bytes([times[4], times[5], times[6], times[7]]);
+    
+    let title = id3_frames(version, &data[id_end + 17..])
+        .into_iter()
+        .find(|f| f.id == b"TIT2")
+        .and_then(|f| decode_id3_text(f.data))
+        .unwrap_or(element_id);
+    
+    Some(ChapterInfo {
+        index: 0,
+        start_seconds: start_ms as f64 / 1000.0,
+        end_seconds: end_ms as f64 / 1000.0,
+        title,
+    })
+}
+
+fn decode_id3_text(data: &[u8]) -> Option<String> {
+    let (&encoding, text) = data.split_first()?;
+    
+    let decoded = match encoding {
+        0 => text.iter().map(|&b| b as char).collect::<String>(),
+        1 | 2 => {
+            let mut bytes = text;
+            let mut big_endian = encoding == 2;
+            if encoding == 1 && bytes.len() >= 2 {
+                match (bytes[0], bytes[1]) {
+                    (0xFF, 0xFE) => bytes = &bytes[2..],
+                    (0xFE, 0xFF) => {
+                        big_endian = true;
+                        bytes = &bytes[2..];
+                    }
+                    _ => {}
+                }
+            }
+            let units: Vec<u16> = bytes.chunks_exact(2)
+                .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(text).to_string(),
+        _ => return None,
+    };
+    
+    let trimmed = decoded.trim_end_matches('\0').trim().to_string();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed)
+    }
+}
+
+fn read_mp4_chapters(path: &Path, duration_secs: f64) -> Result<Vec<ChapterInfo>> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    
+    let moov = match find_atom(&mut file, 0, file_len, b"moov")? {
+        Some(atom) => atom,
+        None => return Ok(Vec::new()),
+    };
+    let udta = match find_atom(&mut file, moov.payload, moov.end, b"udta")? {
+        Some(atom) => atom,
+        None => return Ok(Vec::new()),
+    };
+    let chpl = match find_atom(&mut file, udta.payload, udta.end, b"chpl")? {
+        Some(atom) => atom,
+        None => return Ok(Vec::new()),
+    };
+    
+    let mut payload = vec![0u8; (chpl.end - chpl.payload) as usize];
+    file.seek(SeekFrom::Start(chpl.payload))?;
+    file.read_exact(&mut payload)?;
+    
+    Ok(parse_chpl(&payload, duration_secs))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AtomSpan {
+    pub start: u64,
+    pub payload: u64,
+    pub end: u64,
+}
+
+/// Find a child atom between `start` and `end`
+pub(crate) fn find_atom(file: &mut File, start: u64, end: u64, name: &[u8; 4]) -> Result<Option<AtomSpan>> {
+    let mut pos = start;
+    
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        
+        if size < header_len || pos + size > end {
+            anyhow::bail!("Malformed MP4 atom at offset {}", pos);
+        }
+        
+        if &header[4..8] == name {
+            return Ok(Some(AtomSpan {
+                start: pos,
+                payload: pos + header_len,
+                end: pos + size,
+            }));
+        }
+        pos += size;
+    }
+    
+    Ok(None)
+}
+
+/// Nero chapter list: version(1) flags(3) [reserved(4) in v1] count(1), then
+/// per chapter a start in 100ns units (8), title length (1) and UTF-8 title.
+fn parse_chpl(payload: &[u8], duration_secs: f64) -> Vec<ChapterInfo> {
+    let version = match payload.first() {
+        Some(v) => *v,
+        None => return Vec::new(),
+    };
+    
+    let mut pos = if version == 1 { 8 } else { 4 };
+    let count = match payload.get(pos) {
+        Some(c) => *c as usize,
+        None => return Vec::new(),
+    };
+    pos += 1;
+    
+    let mut entries: Vec<(f64, String)> = Vec::new();
+    for _ in 0..count {
+        let start = match payload.get(pos..pos + 8) {
+            Some(b) => u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f64 / 10_000_000.0,
+            None => break,
+        };
+        let len = match payload.get(pos + 8) {
+            Some(l) => *l as usize,
+            None => break,
+        };
+        let title = match payload.get(pos + 9..pos + 9 + len) {
+            Some(t) => String::from_utf8_lossy(t).trim().to_string(),
+            None => break,
+        };
+        entries.push((start, title));
+        pos += 9 + len;
+    }
+    
+    entries.iter()
+        .enumerate()
+        .map(|(idx, (start, title))| ChapterInfo {
+            index: idx,
+            start_seconds: *start,
+            end_seconds: entries.get(idx + 1).map(|next| next.0).unwrap_or(duration_secs.max(*start)),
+            title: title.clone(),
+        })
+        .collect()
+}
+
 fn item_value_to_string(value: &ItemValue) -> Option<String> {
     match value {
         ItemValue::Text(text) => Some(text.to_string()),
@@ -207,3 +512,78 @@ fn item_value_to_string(value: &ItemValue) -> Option<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn id3v3_frame(id: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+    
+    fn chap_frame(element_id: &str, start_ms: u32, end_ms: u32, title: Option<&str>) -> Vec<u8> {
+        let mut payload = element_id.as_bytes().to_vec();
+        payload.push(0);
+        payload.extend_from_slice(&start_ms.to_be_bytes());
+        payload.extend_from_slice(&end_ms.to_be_bytes());
+        payload.extend_from_slice(&[0xFF; 8]);
+        if let Some(title) = title {
+            let mut text = vec![3u8];
+            text.extend_from_slice(title.as_bytes());
+            payload.extend(id3v3_frame(b"TIT2", &text));
+        }
+        id3v3_frame(b"CHAP", &payload)
+    }
+    
+    #[test]
+    fn test_parse_id3_chapters() {
+        let mut body = chap_frame("ch1", 90_000, 180_000, Some("Chapter Two"));
+        body.extend(chap_frame("ch0", 0, 90_000, None));
+        body.extend_from_slice(&[0; 16]);
+        
+        let chapters = parse_id3_chapters(3, 0, &body);
+        
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].index, 0);
+        assert_eq!(chapters[0].title, "ch0");
+        assert_eq!(chapters[1].title, "Chapter Two");
+        assert_eq!(chapters[1].start_seconds, 90.0);
+        assert_eq!(chapters[1].end_seconds, 180.0);
+    }
+    
+    #[test]
+    fn test_malformed_chapter_frames_are_skipped() {
+        let mut body = chap_frame("ch0", 0, 1_000, Some("Intro"));
+        // Claims far more bytes than remain
+        body.extend_from_slice(b"CHAP");
+        body.extend_from_slice(&10_000u32.to_be_bytes());
+        body.extend_from_slice(&[0, 0, b'x']);
+        
+        let chapters = parse_id3_chapters(3, 0, &body);
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].title, "Intro");
+        
+        assert!(parse_id3_chapters(3, 0, &[]).is_empty());
+    }
+    
+    #[test]
+    fn test_parse_chpl() {
+        let mut payload = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "Opening"), (600_000_000u64, "Part One")] {
+            payload.extend_from_slice(&start.to_be_bytes());
+            payload.push(title.len() as u8);
+            payload.extend_from_slice(title.as_bytes());
+        }
+        
+        let chapters = parse_chpl(&payload, 3600.0);
+        
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].end_seconds, 60.0);
+        assert_eq!(chapters[1].title, "Part One");
+        assert_eq!(chapters[1].end_seconds, 3600.0);
+    }
+}
@@ -1,8 +1,15 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use anyhow::Result;
 use lofty::probe::Probe;
-use lofty::file::{TaggedFileExt, AudioFile};
-use lofty::tag::{Accessor, Tag, ItemKey, ItemValue, TagItem};
+use lofty::file::{TaggedFile, TaggedFileExt, AudioFile, FileType};
+use lofty::tag::{Accessor, Tag, TagExt, TagType, ItemKey, ItemValue, TagItem, SplitTag};
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::id3::v2::{Frame, Id3v2Tag};
+use lofty::mpeg::MpegFile;
+use lofty::aac::AacFile;
+use lofty::iff::aiff::AiffFile;
+use lofty::iff::wav::WavFile;
+use lofty::picture::{Picture, PictureType};
 use serde::{Serialize, Deserialize};
 use tokio::sync::Semaphore;
 use std::sync::Arc;
@@ -12,6 +19,19 @@ pub struct WriteResult {
     pub success: usize,
     pub failed: usize,
     pub errors: Vec<WriteError>,
+    /// Files never attempted because the batch stopped on an error
+    #[serde(default)]
+    pub skipped: usize,
+    /// Written files that lost tag data along the way
+    #[serde(default)]
+    pub warnings: Vec<WriteWarning>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WriteWarning {
+    pub file_id: String,
+    pub path: String,
+    pub message: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,22 +41,148 @@ pub struct WriteError {
     pub error: String,
 }
 
+/// Tag writes allowed on disk at once. Low, since spinning disks slow down
+/// sharply when many files are rewritten together.
+pub const DEFAULT_IO_WORKERS: usize = 2;
+
+/// Hold one disk slot while `write` runs, separately from whatever limits
+/// the surrounding workers
+pub async fn with_io_slot<T>(slots: Arc<Semaphore>, write: impl std::future::Future<Output = T>) -> Result<T> {
+    let _permit = slots.acquire_owned().await?;
+    Ok(write.await)
+}
+
+/// Change whose new value is the path of an image to embed as the front cover
+pub const COVER_CHANGE: &str = "cover";
+
+/// Joins co-narrators in a "narrators" change; each is written as its own value
+pub const NARRATOR_SEPARATOR: &str = "; ";
+
+/// Tag fields the narrator can be written to, by their config name.
+pub const NARRATOR_TARGETS: &[&str] = &["composer", "narrator", "artist_sort"];
+
+pub const NARRATOR_TAG_KEY: &str = "NARRATOR";
+
+/// The custom narrator field: a freeform atom in MP4, TXXX in ID3
+pub fn narrator_freeform_key(tag_type: TagType) -> ItemKey {
+    freeform_item_key(tag_type, NARRATOR_TAG_KEY)
+}
+
+fn narrator_item_key(target: &str, tag_type: TagType) -> Option<ItemKey> {
+    match target.trim().to_lowercase().as_str() {
+        "composer" => Some(ItemKey::Composer),
+        "narrator" => Some(narrator_freeform_key(tag_type)),
+        "artist_sort" => Some(ItemKey::TrackArtistSort),
+        _ => None,
+    }
+}
+
+/// Mirror series/sequence changes onto the movement fields. Movement numbers
+/// are integers in ID3 and MP4, so fractional sequences like "1.5" are skipped.
+pub fn add_movement_changes(changes: &mut std::collections::HashMap<String, crate::scanner::FieldChange>) {
+    if let Some(series) = changes.get("series").cloned() {
+        changes.insert("movement".to_string(), series);
+    }
+    if let Some(sequence) = changes.get("sequence").cloned() {
+        if sequence.new.trim().parse::<u32>().is_ok() {
+            changes.insert("movement_number".to_string(), sequence);
+        }
+    }
+}
+
+/// Drop the changes for fields the user chose to keep as they are. Keeping
+/// the narrator also keeps the co-narrator values and the narrator comment;
+/// keeping the title or author keeps its sort name.
+pub fn drop_kept_fields(
+    changes: &mut std::collections::HashMap<String, crate::scanner::FieldChange>,
+    keep_original: &std::collections::HashSet<String>,
+) {
+    changes.retain(|field, _| {
+        let owner = match field.as_str() {
+            "narrators" | "narrator_comment" => "narrator",
+            "title_sort" => "title",
+            "author_sort" => "author",
+            other => other,
+        };
+        !keep_original.contains(owner)
+    });
+}
+
+pub const PROVENANCE_VERSION_KEY: &str = "TAGGER_VERSION";
+pub const PROVENANCE_TIME_KEY: &str = "TAGGER_TIMESTAMP";
+
+/// Stamp the app version and write time (unix seconds) into their own
+/// freeform fields, so comments are never touched
+pub fn add_provenance_changes(changes: &mut std::collections::HashMap<String, crate::scanner::FieldChange>) {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    for (field, value) in [("tagger_version", env!("CARGO_PKG_VERSION").to_string()), ("tagger_timestamp", now.to_string())] {
+        changes.insert(field.to_string(), crate::scanner::FieldChange { old: String::new(), new: value });
+    }
+}
+
+/// Switch the genre change to the joined style; the writer then picks per
+/// format whether that means one item or several
+pub fn use_joined_genres(changes: &mut std::collections::HashMap<String, crate::scanner::FieldChange>) {
+    if let Some(genre) = changes.remove("genre") {
+        changes.insert("genre_joined".to_string(), genre);
+    }
+}
+
+pub fn validate_narrator_targets(targets: &[String]) -> Result<()> {
+    let unknown: Vec<&str> = targets.iter()
+        .map(|t| t.as_str())
+        .filter(|t| narrator_item_key(t, TagType::VorbisComments).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        anyhow::bail!(
+            "Unknown narrator target(s): {} (expected one of: {})",
+            unknown.join(", "),
+            NARRATOR_TARGETS.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Resolve configured targets to tag keys, dropping unknown names and
+/// duplicates. Falls back to Composer so the narrator is always written.
+fn narrator_item_keys(targets: &[String], tag_type: TagType) -> Vec<ItemKey> {
+    let mut keys = Vec::new();
+    for target in targets {
+        match narrator_item_key(target, tag_type) {
+            Some(key) if !keys.contains(&key) => keys.push(key),
+            Some(_) => {},
+            None => println!("⚠️  Ignoring unknown narrator target '{}'", target),
+        }
+    }
+    if keys.is_empty() {
+        keys.push(ItemKey::Composer);
+    }
+    keys
+}
+
 pub async fn write_files_parallel(
     files: Vec<(String, std::collections::HashMap<String, crate::scanner::FieldChange>)>,
     backup: bool,
     max_concurrent: usize,
+    narrator_targets: Vec<String>,
 ) -> Result<Vec<Result<(), anyhow::Error>>> {
     let semaphore = Arc::new(Semaphore::new(max_concurrent));
+    let narrator_targets = Arc::new(narrator_targets);
     let mut handles = Vec::new();
     
     for (path, changes) in files {
         let sem = Arc::clone(&semaphore);
+        let targets = Arc::clone(&narrator_targets);
         let path_clone = path.clone();
         let changes_clone = changes.clone();
         
         let handle = tokio::spawn(async move {
             let _permit = sem.acquire().await.unwrap();
-            write_file_tags(&path_clone, &changes_clone, backup).await
+            write_file_tags(&path_clone, &changes_clone, backup, false, &targets, false, false).await
+                .map(|_| ())
         });
         
         handles.push(handle);
@@ -54,7 +200,11 @@ pub async fn write_file_tags(
     file_path: &str,
     changes: &std::collections::HashMap<String, crate::scanner::FieldChange>,
     backup: bool,
-) -> Result<()> {
+    verify_audio: bool,
+    narrator_targets: &[String],
+    safe_write: bool,
+    preserve_unknown: bool,
+) -> Result<Option<String>> {
     let path = Path::new(file_path);
     
     if !path.exists() {
@@ -67,12 +217,18 @@ pub async fn write_file_tags(
     }
     
     if backup {
-        let backup_path = path.with_extension(
-            format!("{}.backup", path.extension().unwrap_or_default().to_string_lossy())
-        );
-        std::fs::copy(path, &backup_path)?;
+        backup_file(path)?;
     }
     
+    let duration_before = if verify_audio {
+        Some(audio_duration(path).map_err(|e| anyhow::anyhow!("Cannot read audio before write: {}", e))?)
+    } else {
+        None
+    };
+    
+    // Read before saving: the generic Tag write replaces the whole ID3v2 tag
+    let unknown_frames = unknown_id3v2_frames(path);
+    
     let mut tagged_file = match Probe::open(path) {
         Ok(probe) => probe,
         Err(e) => anyhow::bail!("Cannot open file (may be corrupted): {}", e),
@@ -97,6 +253,28 @@ pub async fn write_file_tags(
         file_content.primary_tag_mut().unwrap()
     };
     
+    // An existing TXXX:ASIN would be saved back as a bogus "ASIN" frame, so
+    // it's pulled out here and rewritten as TXXX after the save
+    let id3_asin = if tag.tag_type() == TagType::Id3v2 {
+        let key = asin_item_key(TagType::Id3v2);
+        let existing = tag.get_string(&key).map(str::to_string);
+        tag.remove_key(&key);
+        changes.get("asin").map(|c| c.new.trim().to_string()).or(existing)
+    } else {
+        None
+    };
+    
+    // Same for the ISBN and the provenance stamp
+    let mut id3_user_text = Vec::new();
+    if tag.tag_type() == TagType::Id3v2 {
+        let key = isbn_item_key(TagType::Id3v2);
+        let existing = tag.get_string(&key).map(str::to_string);
+        tag.remove_key(&key);
+        if let Some(isbn) = changes.get("isbn").map(|c| c.new.trim().to_string()).or(existing).filter(|i| !i.is_empty()) {
+            id3_user_text.push((ISBN_TAG_KEY.to_string(), isbn));
+        }
+    }
+    
     for (field, change) in changes {
         match field.as_str() {
             "title" => {
@@ -107,11 +285,18 @@ pub async fn write_file_tags(
                 tag.remove_key(&ItemKey::TrackArtist);
                 tag.set_artist(change.new.clone());
             },
+            "title_sort" => {
+                tag.insert_text(ItemKey::TrackTitleSort, change.new.clone());
+            },
+            // The artist sort field may be where the narrator lives
+            "author_sort" if !narrator_item_keys(narrator_targets, tag.tag_type()).contains(&ItemKey::TrackArtistSort) => {
+                tag.insert_text(ItemKey::TrackArtistSort, change.new.clone());
+            },
             "album" => {
                 tag.remove_key(&ItemKey::AlbumTitle);
                 tag.set_album(change.new.clone());
             },
-            "genre" => {
+            "genre" | "genre_joined" => {
                 tag.remove_key(&ItemKey::Genre);
                 
                 let genres: Vec<&str> = change.new
@@ -120,24 +305,43 @@ pub async fn write_file_tags(
                     .filter(|s| !s.is_empty())
                     .collect();
                 
-                for genre in &genres {
-                    let item = TagItem::new(
-                        ItemKey::Genre,
-                        ItemValue::Text(genre.to_string())
-                    );
-                    tag.push(item);
+                // Vorbis comments have always meant one GENRE field per genre
+                if field.as_str() == "genre_joined" && tag.tag_type() != TagType::VorbisComments {
+                    tag.set_genre(genres.join(", "));
+                } else {
+                    for genre in &genres {
+                        let item = TagItem::new(
+                            ItemKey::Genre,
+                            ItemValue::Text(genre.to_string())
+                        );
+                        tag.push(item);
+                    }
                 }
             },
             "narrator" => {
-                tag.remove_key(&ItemKey::Composer);
-                tag.insert_text(ItemKey::Composer, change.new.clone());
-                tag.remove_key(&ItemKey::Comment);
+                for key in narrator_item_keys(narrator_targets, tag.tag_type()) {
+                    tag.remove_key(&key);
+                    tag.insert_text(key, change.new.clone());
+                }
             },
-            "description" | "comment" => {
-                if !change.new.to_lowercase().contains("narrated by") {
-                    tag.set_comment(change.new.clone());
+            "narrators" => {
+                let names: Vec<&str> = change.new
+                    .split(NARRATOR_SEPARATOR.trim())
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                
+                for key in narrator_item_keys(narrator_targets, tag.tag_type()) {
+                    tag.remove_key(&key);
+                    for name in &names {
+                        tag.push(TagItem::new(key.clone(), ItemValue::Text(name.to_string())));
+                    }
                 }
             },
+            // Written as rendered: the templates decide what lands in the comment
+            "description" | "comment" | "narrator_comment" => {
+                tag.set_comment(change.new.clone());
+            },
             "year" => {
                 if let Ok(year) = change.new.parse::<u32>() {
                     tag.set_year(year);
@@ -151,11 +355,706 @@ pub async fn write_file_tags(
                 tag.insert_text(ItemKey::Unknown("SERIES-PART".to_string()), change.new.clone());
                 tag.insert_text(ItemKey::Unknown("series-part".to_string()), change.new.clone());
             },
+            "movement" => {
+                tag.insert_text(ItemKey::Movement, change.new.clone());
+            },
+            "movement_number" => {
+                tag.insert_text(ItemKey::MovementNumber, change.new.trim().to_string());
+            },
+            "subtitle" => {
+                tag.insert_text(ItemKey::TrackSubtitle, change.new.clone());
+            },
+            "publisher" => {
+                tag.insert_text(ItemKey::Publisher, change.new.clone());
+            },
+            "isbn" if tag.tag_type() != TagType::Id3v2 => {
+                tag.insert_text(isbn_item_key(tag.tag_type()), change.new.trim().to_string());
+            },
+            "asin" if tag.tag_type() != TagType::Id3v2 => {
+                tag.insert_text(asin_item_key(tag.tag_type()), change.new.trim().to_string());
+            },
+            COVER_CHANGE => {
+                let mut image = std::fs::File::open(change.new.trim())
+                    .map_err(|e| anyhow::anyhow!("Cannot open cover {}: {}", change.new, e))?;
+                let mut picture = Picture::from_reader(&mut image)
+                    .map_err(|e| anyhow::anyhow!("Cannot read cover {}: {}", change.new, e))?;
+                picture.set_pic_type(PictureType::CoverFront);
+                tag.remove_picture_type(PictureType::CoverFront);
+                tag.push_picture(picture);
+            },
+            "tagger_version" | "tagger_timestamp" => {
+                let key = if field == "tagger_version" { PROVENANCE_VERSION_KEY } else { PROVENANCE_TIME_KEY };
+                if tag.tag_type() == TagType::Id3v2 {
+                    id3_user_text.push((key.to_string(), change.new.clone()));
+                } else {
+                    tag.insert_text(freeform_item_key(tag.tag_type(), key), change.new.clone());
+                }
+            },
             _ => {}
         }
     }
     
-    file_content.save_to_path(path, lofty::config::WriteOptions::default())
+    let unknown_ids: Vec<String> = unknown_frames.iter().map(|f| f.id_str().to_string()).collect();
+    let mut warning = None;
+    if !unknown_ids.is_empty() {
+        if preserve_unknown {
+            println!("   ♻️  Restoring {} unknown frame(s): {}", unknown_ids.len(), unknown_ids.join(", "));
+        } else {
+            warning = Some(format!(
+                "{} ID3 frame(s) the tagger can't rewrite were dropped: {}",
+                unknown_ids.len(),
+                unknown_ids.join(", ")
+            ));
+        }
+    }
+    
+    // TXXX frames and restored frames go onto the saved tag directly. With a
+    // safe write they land on the staged copy, so the rename stays the only
+    // change to the real file.
+    let id3v2_passes = move |target: &Path| -> Result<()> {
+        if let Some(asin) = id3_asin.filter(|a| !a.is_empty()) {
+            write_id3v2_asin(target, &asin)?;
+        }
+        if !id3_user_text.is_empty() {
+            write_id3v2_user_text(target, &id3_user_text)?;
+        }
+        if preserve_unknown && !unknown_frames.is_empty() {
+            reattach_id3v2_frames(target, unknown_frames)?;
+        }
+        Ok(())
+    };
+    
+    if safe_write {
+        let tmp_path = stage_safe_write(&file_content, path, id3v2_passes)?;
+        commit_safe_write(&tmp_path, path)?;
+    } else {
+        file_content.save_to_path(path, lofty::config::WriteOptions::default())
+            .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
+        id3v2_passes(path)?;
+    }
+    
+    if let Some(before) = duration_before {
+        verify_audio_unchanged(path, before, backup)?;
+    }
+    
+    Ok(warning)
+}
+
+/// ID3v2 frames with no generic Tag equivalent (chapters, PRIV, RVA2,
+/// described comments...). Writing through the generic Tag drops them.
+/// Only MP3 is checked; other formats come back empty.
+fn unknown_id3v2_frames(path: &Path) -> Vec<Frame<'static>> {
+    let is_mp3 = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("mp3"))
+        .unwrap_or(false);
+    if !is_mp3 {
+        return Vec::new();
+    }
+    
+    let mpeg = match std::fs::File::open(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|mut f| Ok(MpegFile::read_from(&mut f, ParseOptions::new())?))
+    {
+        Ok(mpeg) => mpeg,
+        Err(_) => return Vec::new(),
+    };
+    
+    match mpeg.id3v2() {
+        Some(tag) => {
+            let (remainder, _) = tag.clone().split_tag();
+            Id3v2Tag::from(remainder).into_iter().collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+fn reattach_id3v2_frames(path: &Path, frames: Vec<Frame<'static>>) -> Result<()> {
+    let mut tag = {
+        let mut file = std::fs::File::open(path)?;
+        let mpeg = MpegFile::read_from(&mut file, ParseOptions::new())?;
+        mpeg.id3v2().cloned().unwrap_or_default()
+    };
+    for frame in frames {
+        tag.insert(frame);
+    }
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| anyhow::anyhow!("Failed to restore unknown frames: {}", e))
+}
+
+/// ASIN key for a tag type: the iTunes freeform atom in MP4, a plain
+/// ASIN field elsewhere (TXXX:ASIN when read from ID3v2)
+pub fn asin_item_key(tag_type: TagType) -> ItemKey {
+    match tag_type {
+        TagType::Mp4Ilst => ItemKey::Unknown(MP4_ASIN_KEY.to_string()),
+        _ => ItemKey::Unknown(ASIN_TAG_KEY.to_string()),
+    }
+}
+
+pub const ASIN_TAG_KEY: &str = "ASIN";
+const MP4_ASIN_KEY: &str = "----:com.apple.iTunes:ASIN";
+
+pub const ISBN_TAG_KEY: &str = "ISBN";
+
+/// Like the ASIN, the ISBN is a TXXX frame in ID3 and a freeform atom in MP4
+pub fn isbn_item_key(tag_type: TagType) -> ItemKey {
+    freeform_item_key(tag_type, ISBN_TAG_KEY)
+}
+
+/// Key for a custom text field: an iTunes freeform atom in MP4, the bare
+/// name elsewhere
+fn freeform_item_key(tag_type: TagType, name: &str) -> ItemKey {
+    match tag_type {
+        TagType::Mp4Ilst => ItemKey::Unknown(format!("----:com.apple.iTunes:{}", name)),
+        _ => ItemKey::Unknown(name.to_string()),
+    }
+}
+
+/// The generic Tag turns a four-letter key into a raw frame id, so the
+/// ASIN goes in as a described TXXX frame instead
+pub(crate) fn write_id3v2_asin(path: &Path, asin: &str) -> Result<()> {
+    write_id3v2_user_text(path, &[(ASIN_TAG_KEY.to_string(), asin.to_string())])
+        .map_err(|e| anyhow::anyhow!("Failed to write ASIN: {}", e))
+}
+
+/// Add or replace TXXX frames by description on an already-saved file
+fn write_id3v2_user_text(path: &Path, fields: &[(String, String)]) -> Result<()> {
+    let mut tag = read_id3v2_tag(path)?;
+    for (description, value) in fields {
+        tag.insert_user_text(description.clone(), value.clone());
+    }
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| anyhow::anyhow!("Failed to write TXXX frames: {}", e))
+}
+
+/// The file's ID3v2 tag with every frame intact, or an empty one. Going
+/// through the generic Tag would turn TXXX:ASIN into an "ASIN" frame, so the
+/// concrete file type the probe finds is read instead.
+fn read_id3v2_tag(path: &Path) -> Result<Id3v2Tag> {
+    let file_type = Probe::open(path)?.guess_file_type()?.file_type();
+    let mut file = std::fs::File::open(path)?;
+    let tag = match file_type {
+        Some(FileType::Mpeg) => MpegFile::read_from(&mut file, ParseOptions::new())?.id3v2().cloned(),
+        Some(FileType::Aac) => AacFile::read_from(&mut file, ParseOptions::new())?.id3v2().cloned(),
+        Some(FileType::Wav) => WavFile::read_from(&mut file, ParseOptions::new())?.id3v2().cloned(),
+        Some(FileType::Aiff) => AiffFile::read_from(&mut file, ParseOptions::new())?.id3v2().cloned(),
+        other => anyhow::bail!("{:?} files don't carry an ID3v2 tag", other),
+    };
+    Ok(tag.unwrap_or_default())
+}
+
+/// `<stem>.tagger-tmp.<ext>`: the real extension stays last, since lofty
+/// picks the format from it when the copy is read back
+fn safe_write_temp_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{}.tagger-tmp.{}", stem, ext.to_string_lossy())),
+        None => path.with_file_name(format!("{}.tagger-tmp", stem)),
+    }
+}
+
+/// Tag a sibling copy of the file, running `finish` on it for any writes
+/// that follow the tag save. The original is untouched until
+/// commit_safe_write renames the copy over it.
+fn stage_safe_write(
+    file_content: &TaggedFile,
+    path: &Path,
+    finish: impl FnOnce(&Path) -> Result<()>,
+) -> Result<PathBuf> {
+    let tmp_path = safe_write_temp_path(path);
+    
+    let staged = (|| -> Result<()> {
+        std::fs::copy(path, &tmp_path)?;
+        file_content.save_to_path(&tmp_path, lofty::config::WriteOptions::default())?;
+        finish(&tmp_path)?;
+        std::fs::OpenOptions::new().write(true).open(&tmp_path)?.sync_all()?;
+        Ok(())
+    })();
+    
+    if let Err(e) = staged {
+        let _ = std::fs::remove_file(&tmp_path);
+        anyhow::bail!("Failed to save tags: {}", e);
+    }
+    Ok(tmp_path)
+}
+
+/// Across devices the finished copy is copied over the original instead
+fn commit_safe_write(tmp_path: &Path, path: &Path) -> Result<()> {
+    match std::fs::rename(tmp_path, path) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device(&e) => {
+            println!("⚠️  Cannot rename across devices, writing {} in place", path.display());
+            let copied = std::fs::copy(tmp_path, path);
+            let _ = std::fs::remove_file(tmp_path);
+            copied.map(|_| ()).map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(tmp_path);
+            anyhow::bail!("Failed to replace original file: {}", e)
+        }
+    }
+}
+
+fn is_cross_device(e: &std::io::Error) -> bool {
+    // EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+    let code = if cfg!(windows) { 17 } else { 18 };
+    e.raw_os_error() == Some(code)
+}
+
+fn audio_duration(path: &Path) -> Result<std::time::Duration> {
+    let tagged_file = Probe::open(path)?.read()?;
+    Ok(tagged_file.properties().duration())
+}
+
+/// Isolating the audio stream is format-specific, so settle for checking the
+/// file still decodes and reports the same duration as before the write.
+/// The restore hint is only given when a backup was written.
+fn verify_audio_unchanged(path: &Path, before: std::time::Duration, backed_up: bool) -> Result<()> {
+    let hint = if backed_up { "; restore from backup" } else { "" };
+    let after = audio_duration(path)
+        .map_err(|e| anyhow::anyhow!("File no longer decodes after tag write: {}{}", e, hint))?;
+    
+    let drift = if after > before { after - before } else { before - after };
+    if drift > std::time::Duration::from_millis(100) {
+        anyhow::bail!(
+            "Audio duration changed after tag write ({:.2}s → {:.2}s){}",
+            before.as_secs_f64(),
+            after.as_secs_f64(),
+            hint
+        );
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn backup_file(path: &Path) -> Result<()> {
+    let backup_path = path.with_extension(
+        format!("{}.backup", path.extension().unwrap_or_default().to_string_lossy())
+    );
+    std::fs::copy(path, &backup_path)?;
+    Ok(())
+}
+
+/// A `<name>.<audio ext>.backup` file as written by `backup_file`, with its
+/// original still beside it. Other `.backup` files belong to someone else,
+/// and an orphaned one may be the only copy left, so both are left alone.
+fn is_app_backup(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let Some(original) = name.strip_suffix(".backup") else {
+        return false;
+    };
+    let is_audio = Path::new(original).extension()
+        .and_then(|e| e.to_str())
+        .map(|e| crate::scanner::is_audio_extension(&e.to_lowercase()))
+        .unwrap_or(false);
+    is_audio && path.with_file_name(original).is_file()
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupCleanup {
+    pub count: usize,
+    pub total_bytes: u64,
+    pub paths: Vec<String>,
+    /// Nothing was deleted; the counts are what would be
+    pub dry_run: bool,
+    pub errors: Vec<String>,
+}
+
+/// Find the backups `backup_file` left under `dir_path` and, unless
+/// `dry_run`, delete them.
+pub fn clean_backups(dir_path: &str, dry_run: bool) -> Result<BackupCleanup> {
+    if !Path::new(dir_path).is_dir() {
+        anyhow::bail!("Not a directory: {}", dir_path);
+    }
+    
+    let mut cleanup = BackupCleanup { count: 0, total_bytes: 0, paths: Vec::new(), dry_run, errors: Vec::new() };
+    let mut backups: Vec<PathBuf> = walkdir::WalkDir::new(dir_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_app_backup(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+    backups.sort();
+    
+    for path in backups {
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if !dry_run {
+            if let Err(e) = std::fs::remove_file(&path) {
+                cleanup.errors.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        }
+        cleanup.count += 1;
+        cleanup.total_bytes += size;
+        cleanup.paths.push(path.to_string_lossy().to_string());
+    }
+    
+    println!("🧹 {} {} backup(s), {} bytes",
+        if dry_run { "Found" } else { "Deleted" }, cleanup.count, cleanup.total_bytes);
+    Ok(cleanup)
+}
+
+/// Write files one at a time and stop at the first failure. Files written
+/// before the failure are NOT rolled back; the rest are counted as skipped.
+pub async fn write_files_stop_on_error(
+    files: Vec<(String, String, std::collections::HashMap<String, crate::scanner::FieldChange>)>,
+    backup: bool,
+    verify_audio: bool,
+    narrator_targets: &[String],
+    safe_write: bool,
+    preserve_unknown: bool,
+    on_written: impl Fn(usize),
+) -> WriteResult {
+    let total = files.len();
+    let mut success = 0;
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    
+    for (idx, (file_id, path, changes)) in files.into_iter().enumerate() {
+        let result = write_file_tags(&path, &changes, backup, verify_audio, narrator_targets, safe_write, preserve_unknown).await;
+        on_written(idx + 1);
+        
+        match result {
+            Ok(warning) => {
+                success += 1;
+                if let Some(message) = warning {
+                    warnings.push(WriteWarning { file_id, path, message });
+                }
+            }
+            Err(e) => {
+                println!("🛑 Stopping batch after error on {}: {}", path, e);
+                errors.push(WriteError {
+                    file_id,
+                    path,
+                    error: e.to_string(),
+                });
+                break;
+            }
+        }
+    }
+    
+    WriteResult {
+        success,
+        failed: errors.len(),
+        skipped: total - success - errors.len(),
+        errors,
+        warnings,
+    }
+}
+
+/// Fields `set_group_field` accepts; each takes a single value as written
+pub const GROUP_FIELDS: &[&str] = &[
+    "title", "author", "album", "genre", "narrator", "description", "year", "series",
+    "sequence", "subtitle", "publisher", "isbn", "asin", "title_sort", "author_sort",
+];
+
+/// The change that sets one field to `value`, checked against `GROUP_FIELDS`
+pub fn group_field_changes(
+    field: &str,
+    value: &str,
+) -> Result<std::collections::HashMap<String, crate::scanner::FieldChange>> {
+    let field = field.trim().to_lowercase();
+    if !GROUP_FIELDS.contains(&field.as_str()) {
+        anyhow::bail!("Unknown field '{}' (expected one of: {})", field, GROUP_FIELDS.join(", "));
+    }
+    let change = crate::scanner::FieldChange { old: String::new(), new: value.trim().to_string() };
+    Ok(std::collections::HashMap::from([(field, change)]))
+}
+
+/// Write the same changes to every file in a group. Unlike
+/// `write_files_stop_on_error`, a failed file doesn't stop the rest.
+pub async fn write_group_field(
+    files: &[crate::scanner::AudioFile],
+    changes: &std::collections::HashMap<String, crate::scanner::FieldChange>,
+    backup: bool,
+    narrator_targets: &[String],
+) -> WriteResult {
+    let mut success = 0;
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    
+    for file in files {
+        match write_file_tags(&file.path, changes, backup, false, narrator_targets, false, false).await {
+            Ok(warning) => {
+                success += 1;
+                if let Some(message) = warning {
+                    warnings.push(WriteWarning { file_id: file.id.clone(), path: file.path.clone(), message });
+                }
+            }
+            Err(e) => {
+                println!("   ❌ {}: {}", file.path, e);
+                errors.push(WriteError {
+                    file_id: file.id.clone(),
+                    path: file.path.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    
+    WriteResult {
+        success,
+        failed: errors.len(),
+        skipped: 0,
+        errors,
+        warnings,
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CoverEmbedResult {
+    /// Files the folder cover was written into
+    pub embedded: usize,
+    /// Files left alone because they already had a cover
+    pub skipped: usize,
+    pub errors: Vec<WriteError>,
+    /// Embedded files that lost tag data along the way
+    pub warnings: Vec<WriteWarning>,
+}
+
+/// Write each book's folder cover (cover.jpg, folder.jpg...) into its files.
+/// Files that already have a cover are skipped unless `force`; locked and
+/// read-only files always are.
+pub async fn embed_folder_covers(
+    books: &[(String, Vec<crate::scanner::RawFileData>)],
+    backup: bool,
+    force: bool,
+    narrator_targets: &[String],
+) -> CoverEmbedResult {
+    let mut result = CoverEmbedResult::default();
+    
+    for (name, files) in books {
+        let Some(cover) = crate::scanner::sibling_cover(files) else {
+            continue;
+        };
+        println!("🖼️  {} ← {}", name, cover);
+        
+        let changes = std::collections::HashMap::from([(COVER_CHANGE.to_string(), crate::scanner::FieldChange {
+            old: String::new(),
+            new: cover.clone(),
+        })]);
+        for file in files {
+            if file.tags.locked || file.tags.read_only || (file.tags.has_cover && !force) {
+                result.skipped += 1;
+                continue;
+            }
+            // Chapter frames must survive: most MP3 audiobooks rely on them
+            match write_file_tags(&file.path, &changes, backup, false, narrator_targets, false, true).await {
+                Ok(warning) => {
+                    result.embedded += 1;
+                    if let Some(message) = warning {
+                        result.warnings.push(WriteWarning { file_id: file.id.clone(), path: file.path.clone(), message });
+                    }
+                }
+                Err(e) => {
+                    println!("   ❌ {}: {}", file.path, e);
+                    result.errors.push(WriteError {
+                        file_id: file.id.clone(),
+                        path: file.path.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    
+    result
+}
+
+/// Strip every tag from the file and write a fresh tag of the file's native
+/// type containing only the canonical BookMetadata fields, plus any sort
+/// names the file had. The fields go through write_file_tags, so narrator
+/// targets and co-narrators land where a normal write puts them.
+pub async fn rewrite_clean(
+    file_path: &str,
+    metadata: &crate::scanner::BookMetadata,
+    backup: bool,
+    keep_cover: bool,
+    narrator_targets: &[String],
+) -> Result<()> {
+    let path = Path::new(file_path);
+    
+    if !path.exists() {
+        anyhow::bail!("File does not exist: {}", file_path);
+    }
+    
+    if backup {
+        backup_file(path)?;
+    }
+    
+    let tagged_file = Probe::open(path)
+        .map_err(|e| anyhow::anyhow!("Cannot open file (may be corrupted): {}", e))?
+        .read()
+        .map_err(|e| anyhow::anyhow!("Failed to read file tags: {}", e))?;
+    
+    let tag_type = tagged_file.primary_tag_type();
+    let present_types: Vec<_> = tagged_file.tags().iter().map(|t| t.tag_type()).collect();
+    
+    // Grab the cover art before wiping anything
+    let pictures: Vec<_> = if keep_cover {
+        tagged_file.tags().iter()
+            .flat_map(|t| t.pictures().iter().cloned())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // Sort names aren't part of BookMetadata, so the file's own are carried over
+    let sort_keys = [("title_sort", ItemKey::TrackTitleSort), ("author_sort", ItemKey::TrackArtistSort)];
+    let sort_names: Vec<(&str, String)> = sort_keys.into_iter()
+        .filter_map(|(field, key)| {
+            let value = tagged_file.primary_tag()?.get_string(&key)?;
+            Some((field, value.to_string()))
+        })
+        .collect();
+    
+    for present in present_types {
+        present.remove_from_path(path)
+            .map_err(|e| anyhow::anyhow!("Failed to remove {:?} tag: {}", present, e))?;
+    }
+    
+    if !pictures.is_empty() {
+        let mut tag = Tag::new(tag_type);
+        for picture in pictures.into_iter().take(1) {
+            tag.push_picture(picture);
+        }
+        tag.save_to_path(path, lofty::config::WriteOptions::default())
+            .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
+    }
+    
+    let mut changes = clean_changes(metadata);
+    for (field, value) in sort_names {
+        changes.insert(field.to_string(), crate::scanner::FieldChange { old: String::new(), new: value });
+    }
+    write_file_tags(file_path, &changes, false, false, narrator_targets, false, false).await?;
+    
+    println!("✅ Rewrote clean {:?} tag: {}", tag_type, file_path);
+    
+    Ok(())
+}
+
+/// The write_file_tags changes that set each canonical field `metadata` has
+fn clean_changes(metadata: &crate::scanner::BookMetadata) -> std::collections::HashMap<String, crate::scanner::FieldChange> {
+    let narrators = metadata.narrator_names().join(NARRATOR_SEPARATOR);
+    let genres = metadata.genres.join(", ");
+    let fields = [
+        ("title", Some(metadata.title.clone())),
+        ("album", Some(metadata.title.clone())),
+        ("author", Some(metadata.author.clone())),
+        ("narrators", Some(narrators).filter(|n| !n.is_empty())),
+        ("genre", Some(genres).filter(|g| !g.is_empty())),
+        ("description", metadata.description.clone()),
+        ("year", metadata.year.clone()),
+        ("publisher", metadata.publisher.clone()),
+        ("series", metadata.series.clone()),
+        ("sequence", metadata.sequence.clone()),
+        ("isbn", metadata.isbn.clone()),
+        ("asin", metadata.asin.clone()),
+    ];
+    fields.into_iter()
+        .filter_map(|(field, value)| Some((field.to_string(), crate::scanner::FieldChange { old: String::new(), new: value? })))
+        .collect()
+}
+
+/// Undo the app's output conventions on one file: comment lines using a
+/// narrator prefix are dropped, "Narrated by X" in any narrator target
+/// becomes plain "X", and an approved multi-genre field collapses to
+/// its first genre. Returns whether anything changed; unchanged files aren't saved.
+pub fn strip_app_formatting(
+    file_path: &str,
+    backup: bool,
+    rules: &crate::scanner::ProcessedRules,
+    narrator_targets: &[String],
+) -> Result<bool> {
+    let path = Path::new(file_path);
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let Some(tag) = tagged_file.primary_tag_mut() else {
+        return Ok(false);
+    };
+    let mut changed = false;
+    
+    if let Some(comment) = tag.comment().map(|c| c.to_string()) {
+        if crate::scanner::has_app_narrator_comment(&comment, rules) {
+            let kept: Vec<&str> = comment.lines()
+                .filter(|line| !crate::scanner::has_app_narrator_comment(line, rules))
+                .collect();
+            let kept = kept.join("\n").trim().to_string();
+            tag.remove_key(&ItemKey::Comment);
+            if !kept.is_empty() {
+                tag.set_comment(kept);
+            }
+            changed = true;
+        }
+    }
+    
+    for key in narrator_item_keys(narrator_targets, tag.tag_type()) {
+        let values: Vec<String> = tag.get_strings(&key).map(str::to_string).collect();
+        if !values.iter().any(|v| crate::scanner::has_app_narrator_comment(v, rules)) {
+            continue;
+        }
+        tag.remove_key(&key);
+        for value in values {
+            let name = rules.narrator_prefixes.iter()
+                .filter(|p| !p.is_empty())
+                .find_map(|p| value.split_once(p.as_str()).map(|(_, name)| name.trim().to_string()))
+                .unwrap_or(value);
+            if !name.is_empty() {
+                tag.push(TagItem::new(key.clone(), ItemValue::Text(name)));
+            }
+        }
+        changed = true;
+    }
+    
+    let genres: Vec<String> = tag.get_strings(&ItemKey::Genre)
+        .flat_map(|g| g.split(','))
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+        .collect();
+    if genres.len() > 1 && crate::scanner::is_app_genre_list(&genres.join(", "), rules) {
+        tag.remove_key(&ItemKey::Genre);
+        tag.set_genre(genres[0].clone());
+        changed = true;
+    }
+    
+    if changed {
+        if backup {
+            backup_file(path)?;
+        }
+        tagged_file.save_to_path(path, WriteOptions::default())
+            .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
+        println!("🧹 Stripped app formatting: {}", file_path);
+    }
+    
+    Ok(changed)
+}
+
+/// Custom tag marking a book the user fixed by hand; scans leave it alone.
+pub const LOCK_TAG_KEY: &str = "TAGGER_LOCKED";
+
+/// Where the lock marker lives in a tag of `tag_type`. MP4 only keeps
+/// custom fields as freeform atoms.
+pub fn lock_item_key(tag_type: TagType) -> ItemKey {
+    freeform_item_key(tag_type, LOCK_TAG_KEY)
+}
+
+pub fn set_locked(file_path: &str, locked: bool) -> Result<()> {
+    let path = Path::new(file_path);
+    let mut tagged_file = Probe::open(path)?.read()?;
+    
+    let tag = if let Some(t) = tagged_file.primary_tag_mut() {
+        t
+    } else {
+        let tag_type = tagged_file.primary_tag_type();
+        tagged_file.insert_tag(Tag::new(tag_type));
+        tagged_file.primary_tag_mut().unwrap()
+    };
+    
+    let key = lock_item_key(tag.tag_type());
+    tag.remove_key(&key);
+    if locked {
+        tag.insert_text(key, "1".to_string());
+    }
+    
+    tagged_file.save_to_path(path, lofty::config::WriteOptions::default())
         .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
     
     Ok(())
@@ -171,4 +1070,705 @@ pub fn verify_genres(file_path: &str) -> Result<Vec<String>> {
         .collect();
     
     Ok(genres)
-}
\ No newline at end of file
+}
+
+/// Fields a metadata export records by name so an import can put them back,
+/// including clearing the ones that were empty.
+pub const RESTORABLE_FIELDS: &[&str] = &[
+    "title", "author", "album", "year", "comment", "genre", "narrators", "subtitle",
+    "publisher", "series", "sequence", "isbn", "asin", "title_sort", "author_sort",
+];
+
+/// Where write_file_tags puts `field`, in the order it's read back
+fn field_item_keys(field: &str, tag_type: TagType, narrator_targets: &[String]) -> Vec<ItemKey> {
+    let narrator_keys = narrator_item_keys(narrator_targets, tag_type);
+    match field {
+        "title" => vec![ItemKey::TrackTitle],
+        "author" => vec![ItemKey::TrackArtist],
+        "album" => vec![ItemKey::AlbumTitle],
+        "year" => vec![ItemKey::Year, ItemKey::RecordingDate],
+        "comment" => vec![ItemKey::Comment],
+        "genre" => vec![ItemKey::Genre],
+        "narrators" => narrator_keys,
+        "subtitle" => vec![ItemKey::TrackSubtitle],
+        "publisher" => vec![ItemKey::Publisher],
+        "series" => vec![ItemKey::Unknown("SERIES".to_string()), ItemKey::Unknown("series".to_string())],
+        "sequence" => vec![ItemKey::Unknown("SERIES-PART".to_string()), ItemKey::Unknown("series-part".to_string())],
+        "isbn" => vec![isbn_item_key(tag_type)],
+        "asin" => vec![asin_item_key(tag_type)],
+        "title_sort" => vec![ItemKey::TrackTitleSort],
+        "author_sort" if !narrator_keys.contains(&ItemKey::TrackArtistSort) => vec![ItemKey::TrackArtistSort],
+        _ => vec![],
+    }
+}
+
+/// The current value of every restorable field, empty when unset. Multi-value
+/// fields are joined the way write_file_tags splits them.
+pub fn read_restorable_fields(
+    file_path: &str,
+    narrator_targets: &[String],
+) -> Result<std::collections::BTreeMap<String, String>> {
+    let tagged_file = Probe::open(file_path)?.options(ParseOptions::new().read_properties(false)).read()?;
+    let mut fields = std::collections::BTreeMap::new();
+    
+    for field in RESTORABLE_FIELDS {
+        let value = match tagged_file.primary_tag() {
+            Some(tag) if *field == "year" => tag.year().map(|y| y.to_string()).unwrap_or_default(),
+            Some(tag) => {
+                let separator = match *field {
+                    "genre" => ", ",
+                    "narrators" => NARRATOR_SEPARATOR,
+                    _ => "",
+                };
+                field_item_keys(field, tag.tag_type(), narrator_targets).iter()
+                    .map(|key| tag.get_strings(key).collect::<Vec<_>>())
+                    .find(|values| !values.is_empty())
+                    .map(|values| if separator.is_empty() { values[0].to_string() } else { values.join(separator) })
+                    .unwrap_or_default()
+            }
+            None => String::new(),
+        };
+        fields.insert(field.to_string(), value);
+    }
+    Ok(fields)
+}
+
+/// Remove every value of the given fields from the file's primary tag
+pub fn clear_file_fields(file_path: &str, fields: &[String], narrator_targets: &[String]) -> Result<()> {
+    let path = Path::new(file_path);
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let tag = match tagged_file.primary_tag_mut() {
+        Some(tag) => tag,
+        None => return Ok(()),
+    };
+    
+    let tag_type = tag.tag_type();
+    for field in fields {
+        for key in field_item_keys(field, tag_type, narrator_targets) {
+            tag.remove_key(&key);
+        }
+    }
+    
+    tagged_file.save_to_path(path, lofty::config::WriteOptions::default())
+        .map_err(|e| anyhow::anyhow!("Failed to save tags: {}", e))?;
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_dir, write_flac, write_m4b, write_mp3, write_wav};
+    use std::collections::HashMap;
+    
+    #[tokio::test]
+    async fn test_stop_on_error_halts_after_first_failure() {
+        let dir = temp_dir("tags-missing");
+        let missing = |name: &str| dir.join(format!("{}.m4b", name)).to_string_lossy().to_string();
+        let files = vec![
+            ("1".to_string(), missing("a"), HashMap::new()),
+            ("2".to_string(), missing("b"), HashMap::new()),
+            ("3".to_string(), missing("c"), HashMap::new()),
+        ];
+        
+        let attempted = std::cell::Cell::new(0);
+        let result = write_files_stop_on_error(files, false, false, &[], false, false, |n| attempted.set(n)).await;
+        
+        assert_eq!(attempted.get(), 1);
+        assert_eq!(result.success, 0);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(result.errors[0].file_id, "1");
+    }
+    
+    #[tokio::test]
+    async fn test_verified_write_preserves_duration() {
+        let dir = temp_dir("tags-verify-wav");
+        let path = dir.join("book.wav");
+        write_wav(&path);
+        let before = audio_duration(&path).unwrap();
+        
+        let mut changes = HashMap::new();
+        changes.insert("title".to_string(), crate::scanner::FieldChange {
+            old: String::new(),
+            new: "The Hobbit".to_string(),
+        });
+        
+        write_file_tags(path.to_str().unwrap(), &changes, false, true, &[], false, false).await.unwrap();
+        
+        assert_eq!(audio_duration(&path).unwrap(), before);
+    }
+    
+    #[tokio::test]
+    async fn test_narrator_written_to_multiple_targets() {
+        let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+        let comment = HashMap::from([("comment".to_string(), change("A note"))]);
+        let narrator = HashMap::from([("narrator".to_string(), change("Rob Inglis"))]);
+        let targets = vec!["composer".to_string(), "narrator".to_string(), "Composer".to_string()];
+        let dir = temp_dir("tags-narrator");
+        
+        for ext in ["flac", "m4b"] {
+            let path = dir.join(format!("book.{}", ext));
+            match ext {
+                "flac" => write_flac(&path, 1),
+                _ => write_m4b(&path, 1),
+            }
+            
+            write_file_tags(path.to_str().unwrap(), &comment, false, false, &targets, false, false).await.unwrap();
+            write_file_tags(path.to_str().unwrap(), &narrator, false, false, &targets, false, false).await.unwrap();
+            
+            let tagged = Probe::open(&path).unwrap().read().unwrap();
+            let tag = tagged.primary_tag().unwrap();
+            let composers = tag.get_strings(&ItemKey::Composer).count();
+            let custom = tag.get_string(&narrator_freeform_key(tag.tag_type())).map(str::to_string);
+            let kept_comment = tag.comment().map(|c| c.to_string());
+            let read_back = crate::scanner::read_raw_file(&path).tags.narrator;
+            
+            assert_eq!(tag.get_string(&ItemKey::Composer), Some("Rob Inglis"), "{}", ext);
+            assert_eq!(composers, 1, "{}", ext);
+            assert_eq!(custom.as_deref(), Some("Rob Inglis"), "{}", ext);
+            // The name is written as is and the comment is left alone
+            assert_eq!(kept_comment.as_deref(), Some("A note"), "{}", ext);
+            assert_eq!(read_back.as_deref(), Some("Rob Inglis"), "{}", ext);
+        }
+    }
+    
+    #[test]
+    fn test_clean_backups_only_removes_app_backups() {
+        let dir = temp_dir("tags-backups");
+        std::fs::create_dir_all(dir.join("Book")).unwrap();
+        let book = dir.join("Book").join("01.m4b");
+        std::fs::write(&book, b"audio").unwrap();
+        backup_file(&book).unwrap();
+        std::fs::write(dir.join("Book").join("02.MP3"), b"new").unwrap();
+        std::fs::write(dir.join("Book").join("02.MP3.backup"), b"old").unwrap();
+        std::fs::write(dir.join("Book").join("03.mp3.backup"), b"orphan").unwrap();
+        std::fs::write(dir.join("notes.txt.backup"), b"keep").unwrap();
+        std::fs::write(dir.join("library.backup"), b"keep").unwrap();
+        
+        let preview = clean_backups(dir.to_str().unwrap(), true).unwrap();
+        assert_eq!(preview.count, 2);
+        assert_eq!(preview.total_bytes, 8);
+        assert!(dir.join("Book").join("01.m4b.backup").exists());
+        
+        let cleaned = clean_backups(dir.to_str().unwrap(), false).unwrap();
+        let remaining: Vec<bool> = ["Book/01.m4b", "Book/01.m4b.backup", "Book/02.MP3.backup", "Book/03.mp3.backup", "notes.txt.backup", "library.backup"]
+            .iter()
+            .map(|f| dir.join(f).exists())
+            .collect();
+        
+        assert_eq!(cleaned.count, 2);
+        assert!(cleaned.errors.is_empty());
+        assert_eq!(remaining, vec![true, false, false, true, true, true]);
+    }
+    
+    #[test]
+    fn test_restore_hint_only_with_backup() {
+        let dir = temp_dir("tags-restore-hint");
+        let path = dir.join("book.flac");
+        write_flac(&path, 1);
+        
+        // A "before" far from the real duration stands in for a damaged write
+        let before = std::time::Duration::from_secs(30);
+        let without = verify_audio_unchanged(&path, before, false).unwrap_err().to_string();
+        let with = verify_audio_unchanged(&path, before, true).unwrap_err().to_string();
+        
+        assert!(without.starts_with("Audio duration changed"));
+        assert!(!without.contains("restore from backup"));
+        assert!(with.ends_with("; restore from backup"));
+    }
+    
+    async fn genres_after_write(ext: &str, joined: bool) -> Vec<String> {
+        let dir = temp_dir(&format!("tags-genre-style-{}-{}", ext, joined));
+        let path = dir.join(format!("book.{}", ext));
+        match ext {
+            "flac" => write_flac(&path, 1),
+            _ => write_mp3(&path, 20),
+        }
+        let mut changes = HashMap::new();
+        changes.insert("genre".to_string(), crate::scanner::FieldChange {
+            old: String::new(),
+            new: "Fantasy, Classic".to_string(),
+        });
+        if joined {
+            use_joined_genres(&mut changes);
+        }
+        write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        
+        let raw = crate::tag_inspector::inspect_file_tags(path.to_str().unwrap()).unwrap();
+        raw.tags.into_iter()
+            .filter(|t| t.key.starts_with("Genre #"))
+            .map(|t| t.value)
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn test_genre_write_styles_read_back_through_inspector() {
+        assert_eq!(genres_after_write("mp3", false).await, vec!["Fantasy", "Classic"]);
+        assert_eq!(genres_after_write("mp3", true).await, vec!["Fantasy, Classic"]);
+        // FLAC players expect separate GENRE fields either way
+        assert_eq!(genres_after_write("flac", false).await, vec!["Fantasy", "Classic"]);
+        assert_eq!(genres_after_write("flac", true).await, vec!["Fantasy", "Classic"]);
+    }
+    
+    #[tokio::test]
+    async fn test_provenance_stamp_reads_back_without_touching_comment() {
+        let dir = temp_dir("tags-stamp");
+        for ext in ["mp3", "flac"] {
+            let path = dir.join(format!("book.{}", ext));
+            match ext {
+                "flac" => write_flac(&path, 1),
+                _ => write_mp3(&path, 20),
+            }
+            let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+            
+            let mut first = HashMap::new();
+            first.insert("description".to_string(), change("My own notes"));
+            write_file_tags(path.to_str().unwrap(), &first, false, false, &[], false, false).await.unwrap();
+            
+            let mut second = HashMap::new();
+            second.insert("title".to_string(), change("Dune"));
+            add_provenance_changes(&mut second);
+            write_file_tags(path.to_str().unwrap(), &second, false, false, &[], false, false).await.unwrap();
+            
+            let raw = crate::tag_inspector::inspect_file_tags(path.to_str().unwrap()).unwrap();
+            let value = |key: &str| raw.tags.iter().find(|t| t.key == key).map(|t| t.value.clone());
+            
+            assert_eq!(value("Custom: TAGGER_VERSION").as_deref(), Some(env!("CARGO_PKG_VERSION")), "{}", ext);
+            let stamped: u64 = value("Custom: TAGGER_TIMESTAMP").unwrap().parse().unwrap();
+            assert!(stamped > 1_600_000_000, "{}", ext);
+            assert_eq!(value("Comment").as_deref(), Some("My own notes"), "{}", ext);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_kept_field_is_not_written() {
+        let dir = temp_dir("tags-keep");
+        let path = dir.join("book.flac");
+        write_flac(&path, 1);
+        let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+        let original: HashMap<String, _> = [("title", "Hobit"), ("author", "Tolkien, J.R.R.")].iter()
+            .map(|(k, v)| (k.to_string(), change(v)))
+            .collect();
+        write_file_tags(path.to_str().unwrap(), &original, false, false, &[], false, false).await.unwrap();
+        
+        let mut changes: HashMap<String, _> = [("title", "The Hobbit"), ("author", "J.R.R. Tolkien")].iter()
+            .map(|(k, v)| (k.to_string(), change(v)))
+            .collect();
+        drop_kept_fields(&mut changes, &["author".to_string()].into_iter().collect());
+        write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        
+        let tagged = Probe::open(&path).unwrap().read().unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        let title = tag.title().map(|t| t.to_string());
+        let artist = tag.artist().map(|a| a.to_string());
+        
+        assert_eq!(title.as_deref(), Some("The Hobbit"));
+        assert_eq!(artist.as_deref(), Some("Tolkien, J.R.R."));
+    }
+    
+    #[tokio::test]
+    async fn test_rewrite_clean_writes_like_write_file_tags() {
+        let dir = temp_dir("tags-rewrite-clean");
+        let targets = vec!["composer".to_string(), "narrator".to_string()];
+        let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+        let metadata = crate::scanner::BookMetadata {
+            title: "The Way of Kings".to_string(),
+            subtitle: None,
+            author: "Brandon Sanderson".to_string(),
+            narrator: Some("Kate Reading".to_string()),
+            narrators: vec!["Kate Reading".to_string(), "Michael Kramer".to_string()],
+            series: Some("The Stormlight Archive".to_string()),
+            sequence: Some("1".to_string()),
+            genres: vec!["Fantasy".to_string()],
+            publisher: None,
+            year: Some("2010".to_string()),
+            description: None,
+            isbn: None,
+            asin: Some("B003P2WO5E".to_string()),
+        };
+        
+        for ext in ["flac", "mp3"] {
+            let path = dir.join(format!("book.{}", ext));
+            match ext {
+                "flac" => write_flac(&path, 1),
+                _ => write_mp3(&path, 20),
+            }
+            let old = HashMap::from([
+                ("comment".to_string(), change("Ripped in 2011")),
+                ("title_sort".to_string(), change("Way of Kings")),
+            ]);
+            write_file_tags(path.to_str().unwrap(), &old, false, false, &[], false, false).await.unwrap();
+            
+            rewrite_clean(path.to_str().unwrap(), &metadata, false, true, &targets).await.unwrap();
+            
+            let tagged = Probe::open(&path).unwrap().read().unwrap();
+            let tag = tagged.primary_tag().unwrap();
+            let composers: Vec<&str> = tag.get_strings(&ItemKey::Composer).collect();
+            let custom: Vec<&str> = tag.get_strings(&narrator_freeform_key(tag.tag_type())).collect();
+            let tags = crate::scanner::read_raw_file(&path).tags;
+            
+            assert_eq!(composers, vec!["Kate Reading", "Michael Kramer"], "{}", ext);
+            assert_eq!(custom.first().copied(), Some("Kate Reading"), "{}", ext);
+            assert_eq!(tags.title_sort.as_deref(), Some("Way of Kings"), "{}", ext);
+            assert_eq!(tags.asin.as_deref(), Some("B003P2WO5E"), "{}", ext);
+            assert_eq!(tags.comment, None, "{}", ext);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_strip_app_formatting_follows_narrator_targets() {
+        let dir = temp_dir("tags-strip-targets");
+        let path = dir.join("book.flac");
+        write_flac(&path, 1);
+        let targets = vec!["narrator".to_string()];
+        let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+        let changes = HashMap::from([
+            ("narrators".to_string(), change("Narrated by Kate Reading; Narrated by Michael Kramer")),
+            ("comment".to_string(), change("Narrated by Kate Reading")),
+        ]);
+        write_file_tags(path.to_str().unwrap(), &changes, false, false, &targets, false, false).await.unwrap();
+        
+        let rules = crate::scanner::ProcessedRules::default();
+        let changed = strip_app_formatting(path.to_str().unwrap(), false, &rules, &targets).unwrap();
+        
+        let tagged = Probe::open(&path).unwrap().read().unwrap();
+        let tag = tagged.primary_tag().unwrap();
+        let narrators: Vec<&str> = tag.get_strings(&narrator_freeform_key(tag.tag_type())).collect();
+        assert!(changed);
+        assert_eq!(narrators, vec!["Kate Reading", "Michael Kramer"]);
+        assert_eq!(tag.get_string(&ItemKey::Composer), None);
+        assert_eq!(tag.comment(), None);
+    }
+    
+    #[test]
+    fn test_keeping_narrator_keeps_co_narrators() {
+        let change = crate::scanner::FieldChange { old: String::new(), new: "x".to_string() };
+        let mut changes: HashMap<String, _> = ["narrator", "narrators", "narrator_comment", "title"].iter()
+            .map(|k| (k.to_string(), change.clone()))
+            .collect();
+        drop_kept_fields(&mut changes, &["narrator".to_string()].into_iter().collect());
+        
+        assert_eq!(changes.keys().collect::<Vec<_>>(), vec!["title"]);
+    }
+    
+    #[test]
+    fn test_keeping_title_keeps_its_sort_name() {
+        let change = crate::scanner::FieldChange { old: String::new(), new: "x".to_string() };
+        let mut changes: HashMap<String, _> = ["title", "title_sort", "author", "author_sort"].iter()
+            .map(|k| (k.to_string(), change.clone()))
+            .collect();
+        drop_kept_fields(&mut changes, &["title".to_string()].into_iter().collect());
+        
+        let mut left: Vec<&String> = changes.keys().collect();
+        left.sort();
+        assert_eq!(left, vec!["author", "author_sort"]);
+    }
+    
+    #[tokio::test]
+    async fn test_co_narrators_written_as_separate_values() {
+        let dir = temp_dir("tags-narrators");
+        for ext in ["flac", "mp3"] {
+            let path = dir.join(format!("book.{}", ext));
+            match ext {
+                "flac" => write_flac(&path, 1),
+                _ => write_mp3(&path, 20),
+            }
+            
+            let mut changes = HashMap::new();
+            changes.insert("narrators".to_string(), crate::scanner::FieldChange {
+                old: String::new(),
+                new: "Kate Reading; Michael Kramer".to_string(),
+            });
+            write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+            
+            let tagged = Probe::open(&path).unwrap().read().unwrap();
+            let composers: Vec<&str> = tagged.primary_tag().unwrap().get_strings(&ItemKey::Composer).collect();
+            
+            assert_eq!(composers, vec!["Kate Reading", "Michael Kramer"], "{}", ext);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_io_slots_cap_writes_below_worker_count() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let workers = Arc::new(Semaphore::new(6));
+        let io_slots = Arc::new(Semaphore::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak_workers = Arc::new(AtomicUsize::new(0));
+        let writing = Arc::new(AtomicUsize::new(0));
+        let peak_writes = Arc::new(AtomicUsize::new(0));
+        
+        let handles: Vec<_> = (0..6).map(|_| {
+            let (workers, io_slots) = (Arc::clone(&workers), Arc::clone(&io_slots));
+            let (running, peak_workers) = (Arc::clone(&running), Arc::clone(&peak_workers));
+            let (writing, peak_writes) = (Arc::clone(&writing), Arc::clone(&peak_writes));
+            tokio::spawn(async move {
+                let _permit = workers.acquire().await.unwrap();
+                peak_workers.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                with_io_slot(io_slots, async {
+                    peak_writes.fetch_max(writing.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+                    writing.fetch_sub(1, Ordering::SeqCst);
+                }).await.unwrap();
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        }).collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        
+        assert_eq!(peak_writes.load(Ordering::SeqCst), 2);
+        assert_eq!(peak_workers.load(Ordering::SeqCst), 6);
+    }
+    
+    #[tokio::test]
+    async fn test_sort_names_round_trip() {
+        let dir = temp_dir("tags-sort");
+        for ext in ["flac", "mp3"] {
+            let path = dir.join(format!("book.{}", ext));
+            match ext {
+                "flac" => write_flac(&path, 1),
+                _ => write_mp3(&path, 20),
+            }
+            
+            let change = |new: &str| crate::scanner::FieldChange { old: String::new(), new: new.to_string() };
+            let changes = HashMap::from([
+                ("title".to_string(), change("The Hobbit")),
+                ("title_sort".to_string(), change("Hobbit")),
+                ("author_sort".to_string(), change("Tolkien, J.R.R.")),
+            ]);
+            write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+            
+            let tags = crate::scanner::read_raw_file(&path).tags;
+            
+            assert_eq!(tags.title.as_deref(), Some("The Hobbit"), "{}", ext);
+            assert_eq!(tags.title_sort.as_deref(), Some("Hobbit"), "{}", ext);
+            assert_eq!(tags.author_sort.as_deref(), Some("Tolkien, J.R.R."), "{}", ext);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_group_field_written_to_every_file() {
+        let dir = temp_dir("tags-group-field");
+        let files: Vec<crate::scanner::AudioFile> = (1..=3)
+            .map(|i| {
+                let path = dir.join(format!("{:02}.flac", i));
+                write_flac(&path, 1);
+                crate::scanner::AudioFile {
+                    id: i.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    filename: format!("{:02}.flac", i),
+                    status: "unchanged".to_string(),
+                    changes: HashMap::new(),
+                }
+            })
+            .collect();
+        
+        let changes = group_field_changes("Album", " The Stormlight Archive ").unwrap();
+        let result = write_group_field(&files, &changes, false, &[]).await;
+        let albums: Vec<Option<String>> = files.iter()
+            .map(|f| crate::scanner::read_raw_file(Path::new(&f.path)).tags.album)
+            .collect();
+        
+        assert_eq!(result.success, 3);
+        assert_eq!(result.failed, 0);
+        assert!(albums.iter().all(|a| a.as_deref() == Some("The Stormlight Archive")));
+        assert!(group_field_changes("movement_number", "1").is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_embed_folder_cover_into_coverless_file() {
+        let dir = temp_dir("tags-folder-cover");
+        let path = dir.join("01.mp3");
+        write_mp3_with_private_frame(&path);
+        // JPEG magic is all lofty checks
+        std::fs::write(dir.join("folder.jpg"), [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00, 0xFF, 0xD9]).unwrap();
+        let books = |path: &Path| vec![("Book".to_string(), vec![crate::scanner::read_raw_file(path)])];
+        
+        let before = books(&path);
+        let first = embed_folder_covers(&before, false, false, &[]).await;
+        let after = books(&path);
+        let again = embed_folder_covers(&after, false, false, &[]).await;
+        let forced = embed_folder_covers(&after, false, true, &[]).await;
+        let kept_frames = has_private_frame(&path);
+        
+        assert!(!before[0].1[0].tags.has_cover);
+        assert_eq!(first.embedded, 1);
+        assert!(first.errors.is_empty());
+        assert!(first.warnings.is_empty());
+        assert!(kept_frames);
+        assert!(after[0].1[0].tags.has_cover);
+        assert_eq!((again.embedded, again.skipped), (0, 1));
+        assert_eq!(forced.embedded, 1);
+    }
+    
+    fn write_mp3_with_private_frame(path: &Path) {
+        use lofty::id3::v2::{FrameFlags, FrameValue, PrivateFrame};
+        
+        write_mp3(path, 20);
+        let mut tag = Id3v2Tag::new();
+        tag.set_title("Old Title".to_string());
+        let private = PrivateFrame {
+            owner: "com.example.player".to_string(),
+            private_data: vec![1, 2, 3, 4],
+        };
+        tag.insert(Frame::new("PRIV", FrameValue::Private(private), FrameFlags::default()).unwrap());
+        tag.save_to_path(path, WriteOptions::default()).unwrap();
+    }
+    
+    fn has_private_frame(path: &Path) -> bool {
+        let mut file = std::fs::File::open(path).unwrap();
+        let mpeg = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+        mpeg.id3v2().map(|t| t.into_iter().any(|f| f.id_str() == "PRIV")).unwrap_or(false)
+    }
+    
+    #[tokio::test]
+    async fn test_unknown_frames_survive_when_preserved() {
+        let dir = temp_dir("tags-preserve");
+        let path = dir.join("book.mp3");
+        write_mp3_with_private_frame(&path);
+        let changes = HashMap::from([("title".to_string(), crate::scanner::FieldChange { old: "Old Title".to_string(), new: "The Hobbit".to_string() })]);
+        
+        let warning = write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, true).await.unwrap();
+        
+        assert_eq!(warning, None);
+        assert!(has_private_frame(&path));
+        let written = Probe::open(&path).unwrap().read().unwrap();
+        assert_eq!(written.primary_tag().unwrap().title().as_deref(), Some("The Hobbit"));
+    }
+    
+    #[tokio::test]
+    async fn test_dropped_unknown_frames_are_reported() {
+        let dir = temp_dir("tags-drop");
+        let path = dir.join("book.mp3");
+        write_mp3_with_private_frame(&path);
+        let changes = HashMap::from([("title".to_string(), crate::scanner::FieldChange { old: "Old Title".to_string(), new: "The Hobbit".to_string() })]);
+        
+        let warning = write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        
+        assert!(warning.unwrap().contains("PRIV"));
+        assert!(!has_private_frame(&path));
+    }
+    
+    #[test]
+    fn test_interrupted_safe_write_leaves_original_intact() {
+        let dir = temp_dir("tags-safe");
+        let path = dir.join("book.wav");
+        write_wav(&path);
+        let original = std::fs::read(&path).unwrap();
+        
+        let mut tagged = Probe::open(&path).unwrap().read().unwrap();
+        let tag_type = tagged.primary_tag_type();
+        tagged.insert_tag(Tag::new(tag_type));
+        tagged.primary_tag_mut().unwrap().set_title("The Hobbit".to_string());
+        
+        // Crash after staging: the rename never happens
+        let tmp_path = stage_safe_write(&tagged, &path, |_| Ok(())).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+        assert_eq!(tmp_path.extension().and_then(|e| e.to_str()), Some("wav"));
+        
+        let staged = Probe::open(&tmp_path).unwrap().read().unwrap();
+        assert_eq!(staged.primary_tag().unwrap().title().as_deref(), Some("The Hobbit"));
+        
+        commit_safe_write(&tmp_path, &path).unwrap();
+        let written = Probe::open(&path).unwrap().read().unwrap();
+        assert_eq!(written.primary_tag().unwrap().title().as_deref(), Some("The Hobbit"));
+        assert!(!tmp_path.exists());
+    }
+    
+    #[test]
+    fn test_id3v2_frames_are_staged_before_the_rename() {
+        let dir = temp_dir("tags-safe-id3");
+        let path = dir.join("book.mp3");
+        write_mp3_with_private_frame(&path);
+        let frames = unknown_id3v2_frames(&path);
+        let tagged = Probe::open(&path).unwrap().read().unwrap();
+        
+        let tmp_path = stage_safe_write(&tagged, &path, |tmp| {
+            write_id3v2_asin(tmp, "B002V1O6W6")?;
+            reattach_id3v2_frames(tmp, frames)
+        }).unwrap();
+        let asin = |p: &Path| read_id3v2_tag(p).unwrap().get_user_text(ASIN_TAG_KEY).map(str::to_string);
+        
+        // Everything is already on the copy the rename will move into place
+        let staged_asin = asin(&tmp_path);
+        let staged_private = has_private_frame(&tmp_path);
+        let original_asin = asin(&path);
+        commit_safe_write(&tmp_path, &path).unwrap();
+        let committed_asin = asin(&path);
+        let committed_private = has_private_frame(&path);
+        
+        assert_eq!(staged_asin.as_deref(), Some("B002V1O6W6"));
+        assert!(staged_private);
+        assert_eq!(original_asin, None);
+        assert_eq!(committed_asin.as_deref(), Some("B002V1O6W6"));
+        assert!(committed_private);
+    }
+    
+    #[tokio::test]
+    async fn test_safe_write_keeps_asin_and_unknown_frames() {
+        let dir = temp_dir("tags-safe-write");
+        let path = dir.join("book.mp3");
+        write_mp3_with_private_frame(&path);
+        let changes: HashMap<String, crate::scanner::FieldChange> = [("title", "Mort"), ("asin", "B002V1O6W6")].iter()
+            .map(|(k, v)| (k.to_string(), crate::scanner::FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        
+        write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], true, true).await.unwrap();
+        let tag = read_id3v2_tag(&path).unwrap();
+        let private = has_private_frame(&path);
+        let leftover = safe_write_temp_path(&path).exists();
+        
+        assert_eq!(tag.get_user_text(ASIN_TAG_KEY), Some("B002V1O6W6"));
+        assert!(private);
+        assert!(!leftover);
+    }
+    
+    #[test]
+    fn test_lock_round_trips_through_m4b() {
+        use lofty::mp4::{AtomIdent, Mp4File};
+        
+        let dir = temp_dir("tags-lock");
+        let path = dir.join("book.m4b");
+        write_m4b(&path, 60);
+        
+        set_locked(path.to_str().unwrap(), true).unwrap();
+        let mut file = std::fs::File::open(&path).unwrap();
+        let mp4 = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+        let freeform = AtomIdent::Freeform { mean: "com.apple.iTunes".into(), name: LOCK_TAG_KEY.into() };
+        assert!(mp4.ilst().and_then(|ilst| ilst.get(&freeform)).is_some());
+        assert!(crate::scanner::read_raw_file(&path).tags.locked);
+        
+        set_locked(path.to_str().unwrap(), false).unwrap();
+        assert!(!crate::scanner::read_raw_file(&path).tags.locked);
+    }
+    
+    #[tokio::test]
+    async fn test_asin_round_trips_through_m4b() {
+        use lofty::mp4::{AtomIdent, Mp4File};
+        
+        let dir = temp_dir("tags-asin");
+        let path = dir.join("book.m4b");
+        write_m4b(&path, 60);
+        let changes: HashMap<String, crate::scanner::FieldChange> = [("title", "Mort"), ("asin", "B002V1O6W6")].iter()
+            .map(|(k, v)| (k.to_string(), crate::scanner::FieldChange { old: String::new(), new: v.to_string() }))
+            .collect();
+        
+        write_file_tags(path.to_str().unwrap(), &changes, false, false, &[], false, false).await.unwrap();
+        let mut file = std::fs::File::open(&path).unwrap();
+        let mp4 = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+        let freeform = AtomIdent::Freeform { mean: "com.apple.iTunes".into(), name: ASIN_TAG_KEY.into() };
+        let stored = mp4.ilst().and_then(|ilst| ilst.get(&freeform)).is_some();
+        let asin = crate::scanner::read_raw_file(&path).tags.asin;
+        
+        assert!(stored);
+        assert_eq!(asin.as_deref(), Some("B002V1O6W6"));
+    }
+    
+    #[test]
+    fn test_validate_narrator_targets() {
+        assert!(validate_narrator_targets(&["composer".to_string(), "artist_sort".to_string()]).is_ok());
+        let err = validate_narrator_targets(&["composer".to_string(), "performer".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("performer"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Tiny audio files for tests that need something lofty can actually parse,
+//! and the scratch directories they're written into.
+
+use std::path::{Path, PathBuf};
+
+/// An empty scratch directory under the system temp dir, removed on drop.
+pub struct TempDir(PathBuf);
+
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+    
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A fresh `audiobook-tagger-<name>-<pid>` directory. Names must be unique
+/// across tests, since they run in parallel.
+pub fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!("audiobook-tagger-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
+
+/// Bare FLAC stream (just a STREAMINFO block) of 8kHz mono 8-bit audio.
+/// `seconds` sets the reported duration; there are no audio frames.
+pub fn write_flac(path: &Path, seconds: u64) {
+    let mut bytes = b"fLaC".to_vec();
+    bytes.extend_from_slice(&[0x80, 0, 0, 34]);
+    bytes.extend_from_slice(&4096u16.to_be_bytes());
+    bytes.extend_from_slice(&4096u16.to_be_bytes());
+    bytes.extend_from_slice(&[0; 6]);
+    let packed: u64 = (8000u64 << 44) | (7u64 << 36) | (seconds * 8000);
+    bytes.extend_from_slice(&packed.to_be_bytes());
+    bytes.extend_from_slice(&[0; 16]);
+    std::fs::write(path, bytes).unwrap();
+}
+
+/// Untagged MP3 of silent 128kbps/44.1kHz MPEG-1 Layer III frames.
+pub fn write_mp3(path: &Path, frames: usize) {
+    let mut frame = vec![0xFF, 0xFB, 0x90, 0x64];
+    frame.resize(417, 0);
+    std::fs::write(path, frame.repeat(frames)).unwrap();
+}
+
+/// M4B with one sound track of `seconds` and no sample data: just enough
+/// atoms for lofty to read and write its tags.
+pub fn write_m4b(path: &Path, seconds: u32) {
+    let atom = |name: &[u8; 4], payload: &[u8]| {
+        let mut bytes = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(name);
+        bytes.extend_from_slice(payload);
+        bytes
+    };
+    // version/flags, creation and modification times, then timescale and duration
+    let mut mdhd = vec![0; 12];
+    mdhd.extend_from_slice(&1000u32.to_be_bytes());
+    mdhd.extend_from_slice(&(seconds * 1000).to_be_bytes());
+    mdhd.extend_from_slice(&[0x55, 0xC4, 0, 0]);
+    let mut hdlr = vec![0; 8];
+    hdlr.extend_from_slice(b"soun");
+    hdlr.extend_from_slice(&[0; 13]);
+    let mdia = atom(b"mdia", &[atom(b"mdhd", &mdhd), atom(b"hdlr", &hdlr)].concat());
+    let moov = atom(b"moov", &atom(b"trak", &mdia));
+    let bytes = [atom(b"ftyp", b"M4B \0\0\0\0"), moov, atom(b"mdat", &[0; 16])].concat();
+    std::fs::write(path, bytes).unwrap();
+}
+
+/// One second of 8kHz mono 8-bit silence as a plain RIFF/WAVE file.
+pub fn write_wav(path: &Path) {
+    let data_len: u32 = 8000;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&8000u32.to_le_bytes());
+    bytes.extend_from_slice(&8000u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&8u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.extend(std::iter::repeat(128u8).take(data_len as usize));
+    std::fs::write(path, bytes).unwrap();
+}
@@ -0,0 +1,27 @@
+// src-tauri/src/util.rs
+// Small string helpers shared across modules
+
+/// Cut to at most `max_bytes`, backing off to a char boundary so multibyte
+/// text never panics
+pub(crate) fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
+    let mut end = s.len().min(max_bytes);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_truncate_utf8_backs_off_to_char_boundary() {
+        assert_eq!(truncate_utf8("Dune", 10), "Dune");
+        assert_eq!(truncate_utf8("Dune", 2), "Du");
+        // "é" is two bytes, so cutting inside it drops the whole char
+        assert_eq!(truncate_utf8("Café", 4), "Caf");
+        assert_eq!(truncate_utf8("Café", 5), "Café");
+        assert_eq!(truncate_utf8("", 3), "");
+    }
+}
@@ -1622,7 +1622,7 @@ function MaintenanceTab() {
                 onConfirm: async () => {
                   try {
                     const result = await invoke('clear_all_genres');
-                    alert('✅ ' + result);
+                    alert('✅ ' + result.message);
                   } catch (error) {
                     alert('❌ Failed: ' + error);
                   }
@@ -1649,7 +1649,7 @@ function MaintenanceTab() {
                 onConfirm: async () => {
                   try {
                     const result = await invoke('normalize_genres');
-                    alert('✅ ' + result);
+                    alert('✅ ' + result.message);
                   } catch (error) {
                     alert('❌ Failed: ' + error);
                   }